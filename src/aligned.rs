@@ -0,0 +1,215 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use core::fmt::Debug;
+
+use crate::{ByteBuf, Pod};
+
+/// A marker type which selects an alignment for [AlignedBytes].
+pub struct Alignment<const ALIGN: usize>;
+
+/// Implemented by the [Alignment]s supported by [AlignedBytes]: 2, 4, 8 and 16.
+pub trait SupportedAlignment {
+    /// A zero-sized type with the selected alignment.
+    type Marker: Copy;
+}
+
+macro_rules! impl_alignment {
+    ($($name: ident: $align: literal)*) => {
+        $(
+            #[doc(hidden)]
+            #[repr(align($align))]
+            #[derive(Clone, Copy)]
+            pub struct $name;
+
+            impl SupportedAlignment for Alignment<$align> {
+                type Marker = $name;
+            }
+        )*
+    };
+}
+
+impl_alignment!(Align2: 2 Align4: 4 Align8: 8 Align16: 16);
+
+/// A fixed size array of bytes whose address is always a multiple of `ALIGN`.
+#[repr(C)]
+pub struct AlignedBytes<const N: usize, const ALIGN: usize>
+where
+    Alignment<ALIGN>: SupportedAlignment
+{
+    _align: [<Alignment<ALIGN> as SupportedAlignment>::Marker; 0],
+    bytes: [u8; N]
+}
+
+impl<const N: usize, const ALIGN: usize> AlignedBytes<N, ALIGN>
+where
+    Alignment<ALIGN>: SupportedAlignment
+{
+    /// Copies the given array into aligned storage.
+    pub fn new(bytes: [u8; N]) -> Self {
+        Self { _align: [], bytes }
+    }
+}
+
+impl<const N: usize, const ALIGN: usize> AsRef<[u8]> for AlignedBytes<N, ALIGN>
+where
+    Alignment<ALIGN>: SupportedAlignment
+{
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<const N: usize, const ALIGN: usize> AsMut<[u8]> for AlignedBytes<N, ALIGN>
+where
+    Alignment<ALIGN>: SupportedAlignment
+{
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+}
+
+impl<const N: usize, const ALIGN: usize> Default for AlignedBytes<N, ALIGN>
+where
+    Alignment<ALIGN>: SupportedAlignment
+{
+    fn default() -> Self {
+        Self::new([0; N])
+    }
+}
+
+impl<const N: usize, const ALIGN: usize> Clone for AlignedBytes<N, ALIGN>
+where
+    Alignment<ALIGN>: SupportedAlignment
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<const N: usize, const ALIGN: usize> Copy for AlignedBytes<N, ALIGN> where Alignment<ALIGN>: SupportedAlignment {}
+
+impl<const N: usize, const ALIGN: usize> PartialEq for AlignedBytes<N, ALIGN>
+where
+    Alignment<ALIGN>: SupportedAlignment
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl<const N: usize, const ALIGN: usize> Eq for AlignedBytes<N, ALIGN> where Alignment<ALIGN>: SupportedAlignment {}
+
+impl<const N: usize, const ALIGN: usize> Debug for AlignedBytes<N, ALIGN>
+where
+    Alignment<ALIGN>: SupportedAlignment
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.bytes.fmt(f)
+    }
+}
+
+/// A shortcut to create a stack allocated fixed size [ByteBuf](ByteBuf) aligned to `ALIGN`
+/// bytes.
+pub type AlignedByteBuf<const N: usize, const ALIGN: usize> = ByteBuf<AlignedBytes<N, ALIGN>>;
+
+struct SliceCheck<V, const ALIGN: usize>(V);
+
+impl<V, const ALIGN: usize> SliceCheck<V, ALIGN> {
+    const ALIGNED: () = assert!(ALIGN.is_multiple_of(core::mem::align_of::<V>()), "type alignment does not divide the buffer alignment");
+}
+
+impl<const N: usize, const ALIGN: usize> AlignedByteBuf<N, ALIGN>
+where
+    Alignment<ALIGN>: SupportedAlignment
+{
+    /// Allocates a new aligned buffer by copying the given array.
+    pub fn from_array(bytes: [u8; N]) -> Self {
+        Self::new(AlignedBytes::new(bytes))
+    }
+
+    /// Allocates a new aligned buffer by copying the given slice.
+    ///
+    /// Returns None if the length of `bytes` is not `N`.
+    pub fn from_slice(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self::from_array)
+    }
+
+    /// Reinterprets this buffer as a slice of `V` in native byte order.
+    ///
+    /// Unlike [as_slice_of](ByteBuf::as_slice_of), this cannot fail: the alignment of `V` is
+    /// checked at compile time to divide `ALIGN`. Trailing bytes which do not form a whole `V`
+    /// are excluded.
+    ///
+    /// ```compile_fail
+    /// use bytesutil::AlignedByteBuf;
+    ///
+    /// let buffer = AlignedByteBuf::<16, 4>::default();
+    /// buffer.as_aligned_slice_of::<u64>();
+    /// ```
+    pub fn as_aligned_slice_of<V: Pod>(&self) -> &[V] {
+        #[allow(clippy::let_unit_value)]
+        let () = SliceCheck::<V, ALIGN>::ALIGNED;
+        let bytes = self.as_ref();
+        // SAFETY: the storage is aligned to ALIGN which is a multiple of the alignment of V, the
+        // slice covers only initialized bytes and V is Pod so any bit pattern is valid.
+        unsafe { core::slice::from_raw_parts(bytes.as_ptr() as *const V, bytes.len() / V::SIZE) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AlignedByteBuf, ByteBuf};
+
+    #[test]
+    fn alignment() {
+        let buffer = AlignedByteBuf::<64, 8>::default();
+        assert_eq!(buffer.as_ref().as_ptr() as usize % 8, 0);
+        let buffer = [AlignedByteBuf::<3, 16>::default(); 2];
+        assert_eq!(buffer[1].as_ref().as_ptr() as usize % 16, 0);
+        assert!(AlignedByteBuf::<4, 2>::from_slice(&[1, 2, 3]).is_none());
+    }
+
+    #[test]
+    fn slice_of() {
+        let mut buffer = AlignedByteBuf::<64, 8>::default();
+        for i in 0..8 {
+            buffer.set_le(i * 8, i as u64 * 1000);
+        }
+        let values = buffer.as_aligned_slice_of::<u64>();
+        assert_eq!(values.len(), 8);
+        if crate::is_native_le() {
+            assert_eq!(values[7], 7000);
+        }
+        assert_eq!(buffer.as_slice_of::<u64>(), Some(values));
+        assert_eq!(buffer.as_aligned_slice_of::<u16>().len(), 32);
+        let misaligned = ByteBuf::new(&buffer.as_ref()[1..9]);
+        assert!(misaligned.as_slice_of::<u64>().is_none());
+        assert!(ByteBuf::new(&buffer.as_ref()[..12]).as_slice_of::<u64>().is_none());
+    }
+}
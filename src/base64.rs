@@ -0,0 +1,273 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter};
+
+use crate::ByteBuf;
+
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// The alphabets supported for base64 encoding, as defined by RFC 4648.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Base64Alphabet {
+    /// The standard alphabet, using `+` and `/`.
+    Standard,
+
+    /// The URL and filename safe alphabet, using `-` and `_`.
+    UrlSafe
+}
+
+impl Base64Alphabet {
+    fn chars(self) -> &'static [u8; 64] {
+        match self {
+            Base64Alphabet::Standard => STANDARD,
+            Base64Alphabet::UrlSafe => URL_SAFE
+        }
+    }
+
+    fn value(self, c: u8) -> Option<u32> {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' if self == Base64Alphabet::Standard => 62,
+            b'/' if self == Base64Alphabet::Standard => 63,
+            b'-' if self == Base64Alphabet::UrlSafe => 62,
+            b'_' if self == Base64Alphabet::UrlSafe => 63,
+            _ => return None
+        };
+        Some(v as u32)
+    }
+}
+
+/// An error produced while decoding a base64 string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Base64Error {
+    /// The string contains a character which is not part of the alphabet.
+    InvalidChar {
+        /// The invalid character.
+        char: char,
+
+        /// The offset of the character in bytes.
+        offset: usize
+    },
+
+    /// The string has a length which no encoded data can have.
+    InvalidLength {
+        /// The length of the string without padding, in bytes.
+        len: usize
+    },
+
+    /// The string has padding which does not match its length.
+    InvalidPadding {
+        /// The offset of the first padding character in bytes.
+        offset: usize
+    }
+}
+
+impl Display for Base64Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Base64Error::InvalidChar { char, offset } => {
+                write!(f, "invalid base64 character {:?} at offset {}", char, offset)
+            },
+            Base64Error::InvalidLength { len } => write!(f, "invalid base64 length {}", len),
+            Base64Error::InvalidPadding { offset } => write!(f, "invalid base64 padding at offset {}", offset)
+        }
+    }
+}
+
+impl std::error::Error for Base64Error {}
+
+fn encode(bytes: &[u8], alphabet: Base64Alphabet, pad: bool) -> String {
+    let chars = alphabet.chars();
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut block = [0; 3];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let group = u32::from_be_bytes([0, block[0], block[1], block[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(chars[(group >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else if pad {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn decode(s: &str, alphabet: Base64Alphabet) -> Result<Vec<u8>, Base64Error> {
+    let bytes = s.as_bytes();
+    let data = s.trim_end_matches('=').len();
+    let padding = bytes.len() - data;
+    if padding > 0 && (padding > 2 || !bytes.len().is_multiple_of(4)) {
+        return Err(Base64Error::InvalidPadding { offset: data });
+    }
+    let mut out = Vec::with_capacity(data / 4 * 3 + 2);
+    let mut group = 0;
+    for (offset, c) in bytes[..data].iter().enumerate() {
+        let value = alphabet.value(*c).ok_or_else(|| Base64Error::InvalidChar {
+            char: s[offset..].chars().next().unwrap_or_default(),
+            offset
+        })?;
+        group = group << 6 | value;
+        if offset % 4 == 3 {
+            out.extend_from_slice(&group.to_be_bytes()[1..]);
+            group = 0;
+        }
+    }
+    match data % 4 {
+        1 => return Err(Base64Error::InvalidLength { len: data }),
+        2 => out.push((group >> 4) as u8),
+        3 => out.extend_from_slice(&((group >> 2) as u16).to_be_bytes()),
+        _ => ()
+    }
+    Ok(out)
+}
+
+impl<T: AsRef<[u8]>> ByteBuf<T> {
+    /// Encodes this buffer as padded base64 using the standard alphabet.
+    pub fn to_base64(&self) -> String {
+        encode(self.as_ref(), Base64Alphabet::Standard, true)
+    }
+
+    /// Encodes this buffer as base64.
+    ///
+    /// # Arguments
+    ///
+    /// * `alphabet`: the alphabet to encode with.
+    /// * `pad`: true to pad the output with `=` to a multiple of 4 characters.
+    pub fn to_base64_with(&self, alphabet: Base64Alphabet, pad: bool) -> String {
+        encode(self.as_ref(), alphabet, pad)
+    }
+}
+
+impl ByteBuf<Vec<u8>> {
+    /// Decodes a base64 string using the standard alphabet. Padding is optional.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [Base64Error] if the string is not valid base64.
+    pub fn from_base64(s: &str) -> Result<Self, Base64Error> {
+        Self::from_base64_with(s, Base64Alphabet::Standard)
+    }
+
+    /// Decodes a base64 string. Padding is optional.
+    ///
+    /// # Arguments
+    ///
+    /// * `s`: the string to decode.
+    /// * `alphabet`: the alphabet `s` is encoded with.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [Base64Error] if the string is not valid base64.
+    pub fn from_base64_with(s: &str, alphabet: Base64Alphabet) -> Result<Self, Base64Error> {
+        decode(s, alphabet).map(ByteBuf::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Base64Alphabet, Base64Error, ByteBuf};
+
+    #[test]
+    fn rfc4648() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy")
+        ];
+        for (plain, encoded) in vectors {
+            let buffer = ByteBuf::new(plain.as_bytes());
+            assert_eq!(buffer.to_base64(), encoded);
+            assert_eq!(buffer.to_base64_with(Base64Alphabet::UrlSafe, false), encoded.trim_end_matches('='));
+            assert_eq!(ByteBuf::from_base64(encoded).unwrap().as_ref(), plain.as_bytes());
+            assert_eq!(ByteBuf::from_base64(encoded.trim_end_matches('=')).unwrap().as_ref(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn alphabets() {
+        let buffer = ByteBuf::new([0xFB, 0xFF, 0xBF]);
+        assert_eq!(buffer.to_base64(), "+/+/");
+        assert_eq!(buffer.to_base64_with(Base64Alphabet::UrlSafe, true), "-_-_");
+        assert_eq!(ByteBuf::from_base64_with("-_-_", Base64Alphabet::UrlSafe).unwrap(), buffer.to_owned_buf());
+        assert_eq!(
+            ByteBuf::from_base64("-_-_").unwrap_err(),
+            Base64Error::InvalidChar { char: '-', offset: 0 }
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut state = 0x2545F491u32;
+        for len in 0..64 {
+            let data: Vec<u8> = (0..len)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    state as u8
+                })
+                .collect();
+            let buffer = ByteBuf::new(data);
+            for alphabet in [Base64Alphabet::Standard, Base64Alphabet::UrlSafe] {
+                for pad in [true, false] {
+                    let encoded = buffer.to_base64_with(alphabet, pad);
+                    assert_eq!(ByteBuf::from_base64_with(&encoded, alphabet).unwrap(), buffer);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            ByteBuf::from_base64("Zm9v!mFy").unwrap_err(),
+            Base64Error::InvalidChar { char: '!', offset: 4 }
+        );
+        assert_eq!(
+            ByteBuf::from_base64("Zm9vé").unwrap_err(),
+            Base64Error::InvalidChar { char: 'é', offset: 4 }
+        );
+        assert_eq!(ByteBuf::from_base64("Zm9vY").unwrap_err(), Base64Error::InvalidLength { len: 5 });
+        assert_eq!(ByteBuf::from_base64("Zm8==").unwrap_err(), Base64Error::InvalidPadding { offset: 3 });
+        assert_eq!(ByteBuf::from_base64("Zg===").unwrap_err(), Base64Error::InvalidPadding { offset: 2 });
+        assert_eq!(
+            ByteBuf::from_base64("Zg=a").unwrap_err(),
+            Base64Error::InvalidChar { char: '=', offset: 2 }
+        );
+    }
+}
@@ -0,0 +1,312 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use core::cmp::Ordering;
+use core::fmt::{Display, Formatter, LowerHex, UpperHex};
+
+use crate::{ByteSize, FixedSize, ReadBytes, WriteBytes};
+
+macro_rules! impl_bigint {
+    ($($(#[$meta: meta])* $name: ident: $limbs: literal)*) => {
+        $(
+            $(#[$meta])*
+            ///
+            /// The value is stored as an array of [u64] limbs, least significant limb first. The
+            /// little endian encoding is the whole number in little endian order, which is the
+            /// limbs in order each in little endian order; the big endian encoding is the whole
+            /// number in big endian order, which is the limbs in reverse order each in big endian
+            /// order.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+            pub struct $name([u64; $limbs]);
+
+            impl $name {
+                /// The number of bytes of this type.
+                const BYTES: usize = $limbs * 8;
+
+                /// The smallest value of this type, zero.
+                pub const ZERO: $name = $name([0; $limbs]);
+
+                /// The largest value of this type.
+                pub const MAX: $name = $name([u64::MAX; $limbs]);
+
+                /// Creates a value from its limbs, least significant limb first.
+                pub const fn from_limbs(limbs: [u64; $limbs]) -> $name {
+                    $name(limbs)
+                }
+
+                /// Returns the limbs of this value, least significant limb first.
+                pub const fn to_limbs(self) -> [u64; $limbs] {
+                    self.0
+                }
+
+                /// Creates a value from its little endian byte representation.
+                pub fn from_le_bytes(bytes: [u8; $limbs * 8]) -> $name {
+                    $name::read_bytes_le(&bytes)
+                }
+
+                /// Creates a value from its big endian byte representation.
+                pub fn from_be_bytes(bytes: [u8; $limbs * 8]) -> $name {
+                    $name::read_bytes_be(&bytes)
+                }
+
+                /// Returns the little endian byte representation of this value.
+                pub fn to_le_bytes(self) -> [u8; $limbs * 8] {
+                    let mut bytes = [0; $limbs * 8];
+                    self.write_bytes_le(&mut bytes);
+                    bytes
+                }
+
+                /// Returns the big endian byte representation of this value.
+                pub fn to_be_bytes(self) -> [u8; $limbs * 8] {
+                    let mut bytes = [0; $limbs * 8];
+                    self.write_bytes_be(&mut bytes);
+                    bytes
+                }
+
+                /// Returns the number of leading zero bits in the binary representation of this
+                /// value.
+                pub fn leading_zeros(&self) -> u32 {
+                    let mut zeros = 0;
+                    for limb in self.0.iter().rev() {
+                        zeros += limb.leading_zeros();
+                        if *limb != 0 {
+                            break;
+                        }
+                    }
+                    zeros
+                }
+
+                /// Returns true if this value is zero.
+                pub fn is_zero(&self) -> bool {
+                    self.0.iter().all(|limb| *limb == 0)
+                }
+            }
+
+            impl From<u64> for $name {
+                fn from(value: u64) -> Self {
+                    let mut limbs = [0; $limbs];
+                    limbs[0] = value;
+                    $name(limbs)
+                }
+            }
+
+            impl From<u128> for $name {
+                fn from(value: u128) -> Self {
+                    let mut limbs = [0; $limbs];
+                    limbs[0] = value as u64;
+                    limbs[1] = (value >> 64) as u64;
+                    $name(limbs)
+                }
+            }
+
+            impl PartialOrd for $name {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            /// Compares values as unsigned integers, starting from the most significant limb.
+            impl Ord for $name {
+                fn cmp(&self, other: &Self) -> Ordering {
+                    self.0.iter().rev().cmp(other.0.iter().rev())
+                }
+            }
+
+            /// Formats the value as lowercase hexadecimal, zero padded to the full width of the
+            /// type. The alternate flag (`{:#x}`) adds a `0x` prefix.
+            impl LowerHex for $name {
+                fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                    if f.alternate() {
+                        f.write_str("0x")?;
+                    }
+                    self.0.iter().rev().try_for_each(|limb| write!(f, "{:016x}", limb))
+                }
+            }
+
+            /// Formats the value as uppercase hexadecimal, zero padded to the full width of the
+            /// type. The alternate flag (`{:#X}`) adds a `0x` prefix.
+            impl UpperHex for $name {
+                fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                    if f.alternate() {
+                        f.write_str("0x")?;
+                    }
+                    self.0.iter().rev().try_for_each(|limb| write!(f, "{:016X}", limb))
+                }
+            }
+
+            /// Formats the value as [LowerHex].
+            impl Display for $name {
+                fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                    LowerHex::fmt(self, f)
+                }
+            }
+
+            #[cfg(feature = "arbitrary")]
+            impl<'a> arbitrary::Arbitrary<'a> for $name {
+                fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                    <[u64; $limbs]>::arbitrary(u).map($name)
+                }
+
+                fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                    <[u64; $limbs]>::size_hint(depth)
+                }
+            }
+
+            impl FixedSize for $name {
+                const SIZE: usize = $name::BYTES;
+            }
+
+            impl ByteSize for $name {
+                fn byte_size(&self) -> usize {
+                    $name::BYTES
+                }
+            }
+
+            impl WriteBytes for $name {
+                fn write_bytes_le(&self, bytes: &mut [u8]) {
+                    for (i, limb) in self.0.iter().enumerate() {
+                        limb.write_bytes_le(&mut bytes[i * 8..]);
+                    }
+                }
+
+                fn write_bytes_be(&self, bytes: &mut [u8]) {
+                    for (i, limb) in self.0.iter().rev().enumerate() {
+                        limb.write_bytes_be(&mut bytes[i * 8..]);
+                    }
+                }
+            }
+
+            impl ReadBytes for $name {
+                fn read_bytes_le(bytes: &[u8]) -> Self {
+                    $name(core::array::from_fn(|i| u64::read_bytes_le(&bytes[i * 8..])))
+                }
+
+                fn read_bytes_be(bytes: &[u8]) -> Self {
+                    $name(core::array::from_fn(|i| u64::read_bytes_be(&bytes[($limbs - 1 - i) * 8..])))
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl crate::WriteTo for $name {
+                fn write_to_le<W: std::io::Write>(&self, mut dst: W) -> std::io::Result<()> {
+                    dst.write_all(&self.to_le_bytes())
+                }
+
+                fn write_to_be<W: std::io::Write>(&self, mut dst: W) -> std::io::Result<()> {
+                    dst.write_all(&self.to_be_bytes())
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl crate::ReadFrom for $name {
+                fn read_from_le<R: std::io::Read>(mut src: R) -> std::io::Result<Self> {
+                    let mut block = [0; $limbs * 8];
+                    crate::error::read_exact_for::<Self, _>(&mut src, &mut block)?;
+                    Ok($name::from_le_bytes(block))
+                }
+
+                fn read_from_be<R: std::io::Read>(mut src: R) -> std::io::Result<Self> {
+                    let mut block = [0; $limbs * 8];
+                    crate::error::read_exact_for::<Self, _>(&mut src, &mut block)?;
+                    Ok($name::from_be_bytes(block))
+                }
+            }
+        )*
+    };
+}
+
+impl_bigint!(
+    /// A 256 bits unsigned integer, such as a hash or a content identifier.
+    U256: 4
+    /// A 512 bits unsigned integer, such as a hash or a content identifier.
+    U512: 8
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{U256, U512};
+
+    fn counting<const N: usize>() -> [u8; N] {
+        core::array::from_fn(|i| i as u8)
+    }
+
+    #[test]
+    fn layout() {
+        let value = U256::from_be_bytes(counting());
+        assert_eq!(
+            value.to_limbs(),
+            [0x18191A1B1C1D1E1F, 0x1011121314151617, 0x08090A0B0C0D0E0F, 0x0001020304050607]
+        );
+        let mut reversed = counting::<32>();
+        reversed.reverse();
+        assert_eq!(value.to_le_bytes(), reversed);
+        assert_eq!(U256::from_le_bytes(reversed), value);
+        assert_eq!(U256::from(0x0102u64).to_be_bytes()[30..], [1, 2]);
+        let wide = U512::from(u128::MAX).to_le_bytes();
+        assert_eq!(wide[..16], [0xFF; 16]);
+        assert_eq!(wide[16..], [0; 48]);
+        #[cfg(feature = "std")]
+        {
+            crate::testutil::assert_roundtrip_bytes(value, &reversed, &counting::<32>());
+            crate::testutil::assert_roundtrip_io(U512::from_be_bytes(counting()));
+        }
+    }
+
+    #[test]
+    fn ordering() {
+        let mut high = [0; 32];
+        high[0] = 1;
+        let mut low = [0; 32];
+        low[31] = 0xFF;
+        // Ordering follows the big integer values, not the byte order used to construct them.
+        assert!(U256::from_be_bytes(high) > U256::from_be_bytes(low));
+        assert!(U256::from_le_bytes(high) < U256::from_le_bytes(low));
+        assert!(U256::from(u128::MAX) < U256::from_limbs([0, 0, 1, 0]));
+        assert!(U512::MAX > U512::from(u64::MAX));
+        assert_eq!(U256::ZERO.cmp(&U256::default()), core::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn leading_zeros() {
+        assert_eq!(U256::ZERO.leading_zeros(), 256);
+        assert_eq!(U512::ZERO.leading_zeros(), 512);
+        assert_eq!(U256::MAX.leading_zeros(), 0);
+        assert_eq!(U256::from(1u64).leading_zeros(), 255);
+        assert_eq!(U256::from_limbs([0, 0, 1 << 5, 0]).leading_zeros(), 64 + 58);
+        assert!(U256::ZERO.is_zero() && !U512::from(1u64).is_zero());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hex() {
+        let value = U256::from_be_bytes(counting());
+        assert_eq!(value.to_string(), "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+        assert_eq!(format!("{:#X}", U256::from(0xABu64)), format!("0x{}AB", "0".repeat(62)));
+        assert_eq!(format!("{:x}", U512::ZERO).len(), 128);
+    }
+}
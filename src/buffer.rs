@@ -26,25 +26,730 @@
 // NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use core::{fmt::{Debug, Display}, ops::{Index, IndexMut}};
+use core::{borrow::Borrow, cmp::Ordering, fmt::{Debug, Display}, hash::{Hash, Hasher}, marker::PhantomData, ops::{Deref, DerefMut, Index, IndexMut, RangeBounds}};
 
-use crate::{ReadBytes, WriteBytes};
+use crate::{BitCast, BoundsError, FixedSize, LengthError, Pod, ReadBytes, WriteBytes};
 
 /// A java-like wrapper over a buffer of bytes.
 pub struct ByteBuf<T> {
     inner: T
 }
 
+/// Checks that a field of type `V` fits at offset `pos` of a buffer of length `len`, when the
+/// `debug-checks` feature is enabled in a build with debug assertions.
+///
+/// Otherwise, only the offset is checked; the field itself reports a buffer which is too short.
+#[track_caller]
+#[inline(always)]
+fn debug_check<V: FixedSize>(op: &str, pos: usize, len: usize) {
+    let size = match cfg!(all(feature = "debug-checks", debug_assertions)) {
+        true => V::SIZE,
+        false => 0
+    };
+    if let Err(e) = crate::cursor::check_bounds(pos, size, len) {
+        panic!(
+            "ByteBuf::{}::<{}>: {} (buffer length is {})",
+            op,
+            core::any::type_name::<V>(),
+            BoundsError::new(e.pos(), V::SIZE, len),
+            len
+        );
+    }
+}
+
+/// Checks that the `bit_len` bits starting at bit `bit_pos` fit in a buffer of `len` bytes.
+#[track_caller]
+fn check_bits(bit_pos: usize, bit_len: usize, len: usize) {
+    assert!(bit_len <= 64, "bit_len must be at most 64, got {}", bit_len);
+    let fits = bit_pos.checked_add(bit_len).is_some_and(|end| end <= len.saturating_mul(8));
+    assert!(fits, "{} bits at bit offset {} do not fit in a buffer of {} bytes", bit_len, bit_pos, len);
+}
+
 impl<T: AsRef<[u8]>> ByteBuf<T> {
     /// Read a little-endian field at the given `pos` offset in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
     pub fn get_le<V: ReadBytes>(&self, pos: usize) -> V {
+        debug_check::<V>("get_le", pos, self.inner.as_ref().len());
         V::read_bytes_le(&self.inner.as_ref()[pos..])
     }
 
     /// Read a big-endian field at the given `pos` offset in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
     pub fn get_be<V: ReadBytes>(&self, pos: usize) -> V {
+        debug_check::<V>("get_be", pos, self.inner.as_ref().len());
         V::read_bytes_be(&self.inner.as_ref()[pos..])
     }
+
+    /// Read a little-endian field at the given typed `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn get_field_le<V: ReadBytes>(&self, offset: Offset<V>) -> V {
+        self.get_le(offset.pos())
+    }
+
+    /// Read a big-endian field at the given typed `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn get_field_be<V: ReadBytes>(&self, offset: Offset<V>) -> V {
+        self.get_be(offset.pos())
+    }
+
+    /// Reads a little-endian structure from the start of the buffer, after checking once that
+    /// the buffer is large enough for all its fields.
+    ///
+    /// *This is typically used to decode a fixed size header in one call.*
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the buffer is shorter than [SIZE](FixedSize::SIZE).
+    pub fn read_struct_le<V: ReadBytes>(&self) -> Result<V, BoundsError> {
+        let bytes = self.inner.as_ref();
+        V::validate(bytes)?;
+        Ok(V::read_bytes_le(bytes))
+    }
+
+    /// Reads a big-endian structure from the start of the buffer, after checking once that the
+    /// buffer is large enough for all its fields.
+    ///
+    /// *This is typically used to decode a fixed size header in one call.*
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the buffer is shorter than [SIZE](FixedSize::SIZE).
+    pub fn read_struct_be<V: ReadBytes>(&self) -> Result<V, BoundsError> {
+        let bytes = self.inner.as_ref();
+        V::validate(bytes)?;
+        Ok(V::read_bytes_be(bytes))
+    }
+
+    /// Reads a little-endian field of type `Stored` at the given `pos` offset in bytes and
+    /// reinterprets its bits as `As`.
+    ///
+    /// *This is typically used when the signedness of a field differs from the in-memory type,
+    /// for example `get_le_as::<i32, u32>(pos)`.*
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn get_le_as<Stored: ReadBytes + BitCast<As>, As>(&self, pos: usize) -> As {
+        self.get_le::<Stored>(pos).bit_cast()
+    }
+
+    /// Reads a big-endian field of type `Stored` at the given `pos` offset in bytes and
+    /// reinterprets its bits as `As`.
+    ///
+    /// *This is typically used when the signedness of a field differs from the in-memory type,
+    /// for example `get_be_as::<i32, u32>(pos)`.*
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn get_be_as<Stored: ReadBytes + BitCast<As>, As>(&self, pos: usize) -> As {
+        self.get_be::<Stored>(pos).bit_cast()
+    }
+
+    /// Returns a view of the little-endian field at the given `pos` offset in bytes.
+    ///
+    /// On little endian platforms, when the field is suitably aligned for `V`, the returned
+    /// [View] borrows the field directly from the buffer without copying. Otherwise, the field is
+    /// copied and byte-swapped as with [get_le](ByteBuf::get_le).
+    ///
+    /// *The buffer is never assumed to be aligned: a misaligned field always falls back to a
+    /// copy. The borrowed view holds a shared borrow of the buffer so the field cannot be
+    /// modified while the view is alive.*
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn view_le<V: Pod>(&self, pos: usize) -> View<'_, V> {
+        match crate::is_native_le() {
+            true => self.view_native(pos),
+            false => View::Owned(self.get_le(pos))
+        }
+    }
+
+    /// Returns a view of the big-endian field at the given `pos` offset in bytes.
+    ///
+    /// See [view_le](ByteBuf::view_le) for details on when the field is borrowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn view_be<V: Pod>(&self, pos: usize) -> View<'_, V> {
+        match crate::is_native_be() {
+            true => self.view_native(pos),
+            false => View::Owned(self.get_be(pos))
+        }
+    }
+
+    #[track_caller]
+    fn view_native<V: Pod>(&self, pos: usize) -> View<'_, V> {
+        debug_check::<V>("view", pos, self.inner.as_ref().len());
+        let bytes = &self.inner.as_ref()[pos..pos + V::SIZE];
+        let ptr = bytes.as_ptr();
+        if ptr.align_offset(core::mem::align_of::<V>()) == 0 {
+            // SAFETY: the pointer is aligned for V, points to V::SIZE initialized bytes and V is
+            // Pod so any bit pattern is a valid value in native byte order.
+            View::Borrowed(unsafe { &*(ptr as *const V) })
+        } else if crate::is_native_le() {
+            View::Owned(V::read_bytes_le(bytes))
+        } else {
+            View::Owned(V::read_bytes_be(bytes))
+        }
+    }
+
+    /// Reads a little-endian field at the given `pos` offset in bytes, whatever the alignment of
+    /// the offset.
+    ///
+    /// *No method of [ByteBuf] ever assumes the buffer is aligned.* On little endian platforms,
+    /// the field is loaded with a single [read_unaligned](core::ptr::read_unaligned); otherwise,
+    /// it is decoded byte by byte as with [get_le](ByteBuf::get_le).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn read_unaligned_le<V: Pod>(&self, pos: usize) -> V {
+        match crate::is_native_le() {
+            true => self.read_native(pos),
+            false => self.get_le(pos)
+        }
+    }
+
+    /// Reads a big-endian field at the given `pos` offset in bytes, whatever the alignment of
+    /// the offset.
+    ///
+    /// See [read_unaligned_le](ByteBuf::read_unaligned_le) for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn read_unaligned_be<V: Pod>(&self, pos: usize) -> V {
+        match crate::is_native_be() {
+            true => self.read_native(pos),
+            false => self.get_be(pos)
+        }
+    }
+
+    #[track_caller]
+    fn read_native<V: Pod>(&self, pos: usize) -> V {
+        debug_check::<V>("read_unaligned", pos, self.inner.as_ref().len());
+        let bytes = &self.inner.as_ref()[pos..pos + V::SIZE];
+        // SAFETY: the pointer points to V::SIZE initialized bytes, read_unaligned has no
+        // alignment requirement and V is Pod so any bit pattern is a valid value in native byte
+        // order.
+        unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const V) }
+    }
+
+    /// Returns an iterator over consecutive little-endian fields, starting at offset 0.
+    ///
+    /// Trailing bytes which do not form a whole field are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `V` is zero-sized.
+    pub fn iter_le<V: ReadBytes>(&self) -> FieldIter<'_, V> {
+        FieldIter::new(self.inner.as_ref(), 0, V::SIZE, crate::Endian::Little)
+    }
+
+    /// Returns an iterator over consecutive big-endian fields, starting at offset 0.
+    ///
+    /// Trailing bytes which do not form a whole field are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `V` is zero-sized.
+    pub fn iter_be<V: ReadBytes>(&self) -> FieldIter<'_, V> {
+        FieldIter::new(self.inner.as_ref(), 0, V::SIZE, crate::Endian::Big)
+    }
+
+    /// Returns an iterator over the little-endian fields at offsets `offset + i * stride`.
+    ///
+    /// *This is typically used to read one column of a table of fixed size records.* The
+    /// iterator ends at the first field which does not fit in the buffer, including when the
+    /// offset computation would overflow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is 0.
+    pub fn column_le<V: ReadBytes>(&self, offset: usize, stride: usize) -> FieldIter<'_, V> {
+        FieldIter::new(self.inner.as_ref(), offset, stride, crate::Endian::Little)
+    }
+
+    /// Returns an iterator over the big-endian fields at offsets `offset + i * stride`.
+    ///
+    /// *This is typically used to read one column of a table of fixed size records.* The
+    /// iterator ends at the first field which does not fit in the buffer, including when the
+    /// offset computation would overflow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is 0.
+    pub fn column_be<V: ReadBytes>(&self, offset: usize, stride: usize) -> FieldIter<'_, V> {
+        FieldIter::new(self.inner.as_ref(), offset, stride, crate::Endian::Big)
+    }
+
+    /// Returns a [Display] adapter which formats this buffer as a classic hex dump: an offset
+    /// column, 16 bytes per line in hexadecimal and their printable ASCII characters.
+    pub fn hexdump(&self) -> HexDump<'_> {
+        HexDump(self.inner.as_ref())
+    }
+
+    /// Compares the content of this buffer with `other` in constant time.
+    ///
+    /// *The time taken only depends on the lengths of both buffers; in particular, it returns
+    /// early if the lengths differ.* This is meant to compare secrets such as MACs or hashes.
+    pub fn ct_eq(&self, other: &impl AsRef<[u8]>) -> bool {
+        let (a, b) = (self.inner.as_ref(), other.as_ref());
+        if a.len() != b.len() {
+            return false;
+        }
+        let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+        core::hint::black_box(diff) == 0
+    }
+
+    /// Reads `count` booleans packed as 8 bits per byte at the given `pos` offset in bytes.
+    ///
+    /// *This uses the layout of [write_bitset](crate::WriteExt::write_bitset).* Unused bits of
+    /// the last byte are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store `ceil(count / 8)` bytes at `pos`.
+    #[cfg(feature = "std")]
+    pub fn get_bitset(&self, pos: usize, count: usize) -> Vec<bool> {
+        let block = &self.inner.as_ref()[pos..pos + count.div_ceil(8)];
+        (0..count).map(|i| block[i / 8] & (1 << (i % 8)) != 0).collect()
+    }
+
+    /// Reads an unsigned integer of `bit_len` bits starting at the given `bit_pos` offset in bits,
+    /// least significant bit first.
+    ///
+    /// *Bit `i` of the buffer is bit `i % 8` of byte `i / 8`, counting from the least significant
+    /// bit; it becomes bit `i - bit_pos` of the result.* The range may span byte boundaries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_len` is greater than 64 or if the range does not fit in the buffer.
+    #[track_caller]
+    pub fn get_bits_le(&self, bit_pos: usize, bit_len: usize) -> u64 {
+        let bytes = self.inner.as_ref();
+        check_bits(bit_pos, bit_len, bytes.len());
+        (0..bit_len).fold(0, |value, i| {
+            let bit = bit_pos + i;
+            value | ((bytes[bit / 8] >> (bit % 8)) as u64 & 1) << i
+        })
+    }
+
+    /// Reads an unsigned integer of `bit_len` bits starting at the given `bit_pos` offset in bits,
+    /// most significant bit first.
+    ///
+    /// *Bit `i` of the buffer is bit `7 - i % 8` of byte `i / 8`, counting from the least
+    /// significant bit; the first bit of the range is the most significant bit of the result.*
+    /// The range may span byte boundaries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_len` is greater than 64 or if the range does not fit in the buffer.
+    #[track_caller]
+    pub fn get_bits_be(&self, bit_pos: usize, bit_len: usize) -> u64 {
+        let bytes = self.inner.as_ref();
+        check_bits(bit_pos, bit_len, bytes.len());
+        (bit_pos..bit_pos + bit_len).fold(0, |value, bit| value << 1 | (bytes[bit / 8] >> (7 - bit % 8)) as u64 & 1)
+    }
+
+    /// Copies the bytes of this buffer into a new [Vec].
+    #[cfg(feature = "std")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.inner.as_ref().to_vec()
+    }
+
+    /// Copies the bytes of this buffer into a new [ByteBuf] backed by a [Vec].
+    #[cfg(feature = "std")]
+    pub fn to_owned_buf(&self) -> ByteBuf<Vec<u8>> {
+        ByteBuf::new(self.to_vec())
+    }
+
+    /// Reinterprets this buffer as a slice of `V` in native byte order, without copying.
+    ///
+    /// Returns None if the buffer is not suitably aligned for `V` or if its length is not a
+    /// multiple of the size of `V`. Use an [AlignedByteBuf](crate::AlignedByteBuf) to guarantee
+    /// the alignment.
+    pub fn as_slice_of<V: Pod>(&self) -> Option<&[V]> {
+        let bytes = self.inner.as_ref();
+        if bytes.as_ptr().align_offset(core::mem::align_of::<V>()) != 0 || bytes.len() % V::SIZE != 0 {
+            return None;
+        }
+        // SAFETY: the pointer is aligned for V, the slice covers only initialized bytes and V
+        // is Pod so any bit pattern is a valid value in native byte order.
+        Some(unsafe { core::slice::from_raw_parts(bytes.as_ptr() as *const V, bytes.len() / V::SIZE) })
+    }
+
+    /// Returns the offset of the first occurrence of `needle` in this buffer.
+    ///
+    /// An empty `needle` always matches at offset 0.
+    pub fn find(&self, needle: &[u8]) -> Option<usize> {
+        find_bytes(self.inner.as_ref(), needle)
+    }
+
+    /// Returns the offset of the last occurrence of `needle` in this buffer.
+    ///
+    /// An empty `needle` always matches at the end of the buffer.
+    pub fn rfind(&self, needle: &[u8]) -> Option<usize> {
+        rfind_bytes(self.inner.as_ref(), needle)
+    }
+
+    /// Returns the offset of the first occurrence of `needle` starting at the given `pos` offset
+    /// in bytes.
+    ///
+    /// Returns None if `pos` is past the end of the buffer.
+    pub fn find_from(&self, pos: usize, needle: &[u8]) -> Option<usize> {
+        let haystack = self.inner.as_ref();
+        if pos > haystack.len() {
+            return None;
+        }
+        find_bytes(&haystack[pos..], needle).map(|i| i + pos)
+    }
+
+    /// Returns an iterator over the offsets of all non-overlapping occurrences of `needle` in
+    /// this buffer.
+    ///
+    /// An empty `needle` matches at every offset, including the end of the buffer.
+    pub fn find_iter<'a>(&'a self, needle: &'a [u8]) -> FindIter<'a> {
+        FindIter {
+            haystack: self.inner.as_ref(),
+            needle,
+            pos: Some(0)
+        }
+    }
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    let (&first, rest) = match needle.split_first() {
+        Some(v) => v,
+        None => return Some(0)
+    };
+    if needle.len() > haystack.len() {
+        return None;
+    }
+    // Only offsets where the whole needle still fits are candidates.
+    let last = haystack.len() - needle.len();
+    let mut i = 0;
+    while i <= last {
+        i += haystack[i..=last].iter().position(|&b| b == first)?;
+        if &haystack[i + 1..i + needle.len()] == rest {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+fn rfind_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    let (&first, rest) = match needle.split_first() {
+        Some(v) => v,
+        None => return Some(haystack.len())
+    };
+    if needle.len() > haystack.len() {
+        return None;
+    }
+    let mut end = haystack.len() - needle.len() + 1;
+    while end > 0 {
+        let i = haystack[..end].iter().rposition(|&b| b == first)?;
+        if &haystack[i + 1..i + needle.len()] == rest {
+            return Some(i);
+        }
+        end = i;
+    }
+    None
+}
+
+/// A typed field of a [ByteBuf] which is either borrowed from the buffer or copied out of it.
+///
+/// This is created by [view_le](ByteBuf::view_le) and [view_be](ByteBuf::view_be).
+#[derive(Debug)]
+pub enum View<'a, V> {
+    /// The field is borrowed directly from the buffer.
+    Borrowed(&'a V),
+
+    /// The field had to be copied out of the buffer.
+    Owned(V)
+}
+
+impl<'a, V> Deref for View<'a, V> {
+    type Target = V;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            View::Borrowed(v) => v,
+            View::Owned(v) => v
+        }
+    }
+}
+
+/// A decoded copy of a field of a [ByteBuf] which is written back to the buffer, in the byte
+/// order it was read with, when dropped or [committed](FieldMut::commit).
+///
+/// This is created by [get_mut_le](ByteBuf::get_mut_le) and [get_mut_be](ByteBuf::get_mut_be).
+pub struct FieldMut<'a, V: WriteBytes> {
+    bytes: &'a mut [u8],
+    value: V,
+    endian: crate::Endian,
+    active: bool
+}
+
+impl<'a, V: ReadBytes + WriteBytes> FieldMut<'a, V> {
+    fn new(bytes: &'a mut [u8], endian: crate::Endian) -> Self {
+        let value = match endian {
+            crate::Endian::Little => V::read_bytes_le(bytes),
+            crate::Endian::Big => V::read_bytes_be(bytes)
+        };
+        Self {
+            bytes,
+            value,
+            endian,
+            active: true
+        }
+    }
+}
+
+impl<V: WriteBytes> FieldMut<'_, V> {
+    fn write_back(&mut self) {
+        match self.endian {
+            crate::Endian::Little => self.value.write_bytes_le(self.bytes),
+            crate::Endian::Big => self.value.write_bytes_be(self.bytes)
+        }
+    }
+
+    /// Writes the value back to the buffer now.
+    pub fn commit(mut self) {
+        self.write_back();
+        self.active = false;
+    }
+
+    /// Leaves the buffer untouched, dropping any change made to the value.
+    pub fn discard(mut self) {
+        self.active = false;
+    }
+}
+
+impl<V: WriteBytes> Deref for FieldMut<'_, V> {
+    type Target = V;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<V: WriteBytes> DerefMut for FieldMut<'_, V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<V: WriteBytes> Drop for FieldMut<'_, V> {
+    fn drop(&mut self) {
+        if self.active {
+            self.write_back();
+        }
+    }
+}
+
+/// A byte offset in a [ByteBuf] which is tied to the type of the field stored there.
+///
+/// *This is used to declare the layout of a format as constants, so that a field can only be
+/// read or written with its declared type.* See [get_field_le](ByteBuf::get_field_le) and
+/// [set_field_le](ByteBuf::set_field_le).
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::Offset;
+///
+/// const MAGIC: Offset<u32> = Offset::new(0);
+/// const VERSION: Offset<u16> = MAGIC.next();
+/// const FLAGS: Offset<u8> = VERSION.next();
+///
+/// assert_eq!(VERSION.pos(), 4);
+/// assert_eq!(FLAGS.pos(), 6);
+/// ```
+pub struct Offset<V> {
+    pos: usize,
+    marker: PhantomData<fn() -> V>
+}
+
+impl<V> Offset<V> {
+    /// Creates a new [Offset] at the given `pos` offset in bytes.
+    pub const fn new(pos: usize) -> Offset<V> {
+        Self {
+            pos,
+            marker: PhantomData
+        }
+    }
+
+    /// Returns the offset in bytes.
+    pub const fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<V: FixedSize> Offset<V> {
+    /// Returns the offset of a field of type `W` which immediately follows this field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset overflows a usize; in a constant, this fails to compile.
+    pub const fn next<W>(&self) -> Offset<W> {
+        match self.pos.checked_add(V::SIZE) {
+            Some(pos) => Offset::new(pos),
+            None => panic!("field offset overflows usize")
+        }
+    }
+}
+
+impl<V> Clone for Offset<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for Offset<V> {}
+
+impl<V> PartialEq for Offset<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pos == other.pos
+    }
+}
+
+impl<V> Eq for Offset<V> {}
+
+impl<V> Debug for Offset<V> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Offset<{}>({})", core::any::type_name::<V>(), self.pos)
+    }
+}
+
+/// A hex dump of the bytes of a [ByteBuf].
+///
+/// This is created by [hexdump](ByteBuf::hexdump).
+pub struct HexDump<'a>(&'a [u8]);
+
+impl Display for HexDump<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, line) in self.0.chunks(16).enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{:08x} ", i * 16)?;
+            for j in 0..16 {
+                if j == 8 {
+                    f.write_str(" ")?;
+                }
+                match line.get(j) {
+                    Some(b) => write!(f, " {:02x}", b)?,
+                    None => f.write_str("   ")?
+                }
+            }
+            f.write_str("  |")?;
+            for b in line {
+                let c = if b.is_ascii_graphic() || *b == b' ' { *b as char } else { '.' };
+                write!(f, "{}", c)?;
+            }
+            f.write_str("|")?;
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over regularly spaced fields of a [ByteBuf].
+///
+/// This is created by [iter_le](ByteBuf::iter_le), [column_le](ByteBuf::column_le) and their big
+/// endian variants.
+pub struct FieldIter<'a, V> {
+    bytes: &'a [u8],
+    offset: usize,
+    stride: usize,
+    index: Option<usize>,
+    endian: crate::Endian,
+    phantom: PhantomData<V>
+}
+
+impl<'a, V> FieldIter<'a, V> {
+    fn new(bytes: &'a [u8], offset: usize, stride: usize, endian: crate::Endian) -> Self {
+        assert!(stride > 0, "stride must not be 0");
+        Self {
+            bytes,
+            offset,
+            stride,
+            index: Some(0),
+            endian,
+            phantom: PhantomData
+        }
+    }
+}
+
+impl<'a, V: ReadBytes> Iterator for FieldIter<'a, V> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.index.take()?;
+        let pos = index.checked_mul(self.stride)?.checked_add(self.offset)?;
+        let field = self.bytes.get(pos..pos.checked_add(V::SIZE)?)?;
+        self.index = index.checked_add(1);
+        Some(match self.endian {
+            crate::Endian::Little => V::read_bytes_le(field),
+            crate::Endian::Big => V::read_bytes_be(field)
+        })
+    }
+}
+
+/// An iterator over the offsets of all non-overlapping occurrences of a needle in a [ByteBuf].
+///
+/// This is created by [find_iter](ByteBuf::find_iter).
+pub struct FindIter<'a> {
+    haystack: &'a [u8],
+    needle: &'a [u8],
+    pos: Option<usize>
+}
+
+impl<'a> Iterator for FindIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.pos?;
+        match find_bytes(&self.haystack[pos..], self.needle) {
+            Some(i) => {
+                let offset = pos + i;
+                // An empty needle must still make progress to avoid matching forever.
+                let next = offset + self.needle.len().max(1);
+                self.pos = if next <= self.haystack.len() { Some(next) } else { None };
+                Some(offset)
+            },
+            None => {
+                self.pos = None;
+                None
+            }
+        }
+    }
 }
 
 impl<T: AsRef<[u8]>> AsRef<[u8]> for ByteBuf<T> {
@@ -55,16 +760,343 @@ impl<T: AsRef<[u8]>> AsRef<[u8]> for ByteBuf<T> {
 
 impl<T: AsMut<[u8]>> ByteBuf<T> {
     /// Write the given little-endian `value` field at the given `pos` offset in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
     pub fn set_le<V: WriteBytes>(&mut self, pos: usize, value: V) -> &mut Self {
+        debug_check::<V>("set_le", pos, self.inner.as_mut().len());
         value.write_bytes_le(&mut self.inner.as_mut()[pos..]);
         self
     }
 
     /// Write the given big-endian `value` field at the given `pos` offset in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
     pub fn set_be<V: WriteBytes>(&mut self, pos: usize, value: V) -> &mut Self {
+        debug_check::<V>("set_be", pos, self.inner.as_mut().len());
         value.write_bytes_be(&mut self.inner.as_mut()[pos..]);
         self
     }
+
+    /// Write the given little-endian `value` field at the given typed `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn set_field_le<V: WriteBytes>(&mut self, offset: Offset<V>, value: V) -> &mut Self {
+        self.set_le(offset.pos(), value)
+    }
+
+    /// Write the given big-endian `value` field at the given typed `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn set_field_be<V: WriteBytes>(&mut self, offset: Offset<V>, value: V) -> &mut Self {
+        self.set_be(offset.pos(), value)
+    }
+
+    /// Write the given little-endian `value` field at the given `pos` offset in bytes, if it fits
+    /// in the buffer.
+    ///
+    /// *This is the non-panicking counterpart of [set_le](ByteBuf::set_le), for buffers sized
+    /// from parsed data.*
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the field does not fit in the buffer, in which case nothing is
+    /// written.
+    pub fn checked_set_le<V: WriteBytes>(&mut self, pos: usize, value: V) -> Result<&mut Self, BoundsError> {
+        let bytes = self.inner.as_mut();
+        crate::cursor::check_bounds(pos, V::SIZE, bytes.len())?;
+        value.write_bytes_le(&mut bytes[pos..pos + V::SIZE]);
+        Ok(self)
+    }
+
+    /// Write the given big-endian `value` field at the given `pos` offset in bytes, if it fits in
+    /// the buffer.
+    ///
+    /// *This is the non-panicking counterpart of [set_be](ByteBuf::set_be), for buffers sized
+    /// from parsed data.*
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the field does not fit in the buffer, in which case nothing is
+    /// written.
+    pub fn checked_set_be<V: WriteBytes>(&mut self, pos: usize, value: V) -> Result<&mut Self, BoundsError> {
+        let bytes = self.inner.as_mut();
+        crate::cursor::check_bounds(pos, V::SIZE, bytes.len())?;
+        value.write_bytes_be(&mut bytes[pos..pos + V::SIZE]);
+        Ok(self)
+    }
+
+    /// Writes a little-endian structure at the start of the buffer, after checking once that the
+    /// buffer is large enough for all its fields.
+    ///
+    /// *This is the counterpart of [read_struct_le](ByteBuf::read_struct_le).*
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the buffer is shorter than [SIZE](FixedSize::SIZE), in which
+    /// case nothing is written.
+    pub fn write_struct_le<V: WriteBytes>(&mut self, value: &V) -> Result<&mut Self, BoundsError> {
+        let bytes = self.inner.as_mut();
+        V::validate(bytes)?;
+        value.write_bytes_le(bytes);
+        Ok(self)
+    }
+
+    /// Writes a big-endian structure at the start of the buffer, after checking once that the
+    /// buffer is large enough for all its fields.
+    ///
+    /// *This is the counterpart of [read_struct_be](ByteBuf::read_struct_be).*
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the buffer is shorter than [SIZE](FixedSize::SIZE), in which
+    /// case nothing is written.
+    pub fn write_struct_be<V: WriteBytes>(&mut self, value: &V) -> Result<&mut Self, BoundsError> {
+        let bytes = self.inner.as_mut();
+        V::validate(bytes)?;
+        value.write_bytes_be(bytes);
+        Ok(self)
+    }
+
+    /// Reinterprets the bits of `value` as `Stored` and writes it as a little-endian field at the
+    /// given `pos` offset in bytes.
+    ///
+    /// *This is the counterpart of [get_le_as](ByteBuf::get_le_as).*
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn set_le_as<Stored: WriteBytes, As: BitCast<Stored>>(&mut self, pos: usize, value: As) -> &mut Self {
+        self.set_le::<Stored>(pos, value.bit_cast())
+    }
+
+    /// Reinterprets the bits of `value` as `Stored` and writes it as a big-endian field at the
+    /// given `pos` offset in bytes.
+    ///
+    /// *This is the counterpart of [get_be_as](ByteBuf::get_be_as).*
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn set_be_as<Stored: WriteBytes, As: BitCast<Stored>>(&mut self, pos: usize, value: As) -> &mut Self {
+        self.set_be::<Stored>(pos, value.bit_cast())
+    }
+
+    /// Writes the given little-endian `value` field at the given `pos` offset in bytes, whatever
+    /// the alignment of the offset.
+    ///
+    /// On little endian platforms, the field is stored with a single
+    /// [write_unaligned](core::ptr::write_unaligned); otherwise, it is encoded byte by byte as
+    /// with [set_le](ByteBuf::set_le).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn write_unaligned_le<V: Pod + WriteBytes>(&mut self, pos: usize, value: V) -> &mut Self {
+        match crate::is_native_le() {
+            true => self.write_native(pos, value),
+            false => self.set_le(pos, value)
+        }
+    }
+
+    /// Writes the given big-endian `value` field at the given `pos` offset in bytes, whatever
+    /// the alignment of the offset.
+    ///
+    /// See [write_unaligned_le](ByteBuf::write_unaligned_le) for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn write_unaligned_be<V: Pod + WriteBytes>(&mut self, pos: usize, value: V) -> &mut Self {
+        match crate::is_native_be() {
+            true => self.write_native(pos, value),
+            false => self.set_be(pos, value)
+        }
+    }
+
+    #[track_caller]
+    fn write_native<V: Pod>(&mut self, pos: usize, value: V) -> &mut Self {
+        debug_check::<V>("write_unaligned", pos, self.inner.as_mut().len());
+        let bytes = &mut self.inner.as_mut()[pos..pos + V::SIZE];
+        // SAFETY: the pointer points to V::SIZE writable bytes, write_unaligned has no alignment
+        // requirement and V is Pod so it has no padding bytes.
+        unsafe { core::ptr::write_unaligned(bytes.as_mut_ptr() as *mut V, value) };
+        self
+    }
+
+    /// Writes the given booleans packed as 8 bits per byte at the given `pos` offset in bytes.
+    ///
+    /// *This uses the layout of [write_bitset](crate::WriteExt::write_bitset).* Unused bits of
+    /// the last byte are set to zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store `ceil(bits.len() / 8)` bytes at `pos`.
+    pub fn set_bitset(&mut self, pos: usize, bits: &[bool]) -> &mut Self {
+        let block = &mut self.inner.as_mut()[pos..pos + bits.len().div_ceil(8)];
+        for (byte, chunk) in block.iter_mut().zip(bits.chunks(8)) {
+            *byte = chunk.iter().enumerate().fold(0, |byte, (i, &bit)| byte | (bit as u8) << i);
+        }
+        self
+    }
+
+    /// Writes the `bit_len` least significant bits of `value` starting at the given `bit_pos`
+    /// offset in bits, least significant bit first.
+    ///
+    /// *This is the counterpart of [get_bits_le](ByteBuf::get_bits_le).* The other bits of the
+    /// buffer and of `value` are left untouched and ignored respectively.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_len` is greater than 64 or if the range does not fit in the buffer.
+    #[track_caller]
+    pub fn set_bits_le(&mut self, bit_pos: usize, bit_len: usize, value: u64) -> &mut Self {
+        let bytes = self.inner.as_mut();
+        check_bits(bit_pos, bit_len, bytes.len());
+        for i in 0..bit_len {
+            let bit = bit_pos + i;
+            let mask = 1 << (bit % 8);
+            match value >> i & 1 {
+                0 => bytes[bit / 8] &= !mask,
+                _ => bytes[bit / 8] |= mask
+            }
+        }
+        self
+    }
+
+    /// Writes the `bit_len` least significant bits of `value` starting at the given `bit_pos`
+    /// offset in bits, most significant bit first.
+    ///
+    /// *This is the counterpart of [get_bits_be](ByteBuf::get_bits_be).* The other bits of the
+    /// buffer and of `value` are left untouched and ignored respectively.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_len` is greater than 64 or if the range does not fit in the buffer.
+    #[track_caller]
+    pub fn set_bits_be(&mut self, bit_pos: usize, bit_len: usize, value: u64) -> &mut Self {
+        let bytes = self.inner.as_mut();
+        check_bits(bit_pos, bit_len, bytes.len());
+        for i in 0..bit_len {
+            let bit = bit_pos + i;
+            let mask = 0x80 >> (bit % 8);
+            match value >> (bit_len - 1 - i) & 1 {
+                0 => bytes[bit / 8] &= !mask,
+                _ => bytes[bit / 8] |= mask
+            }
+        }
+        self
+    }
+
+    /// Copies the bytes in `src` to the given `dest` offset in bytes; both regions may overlap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` is out of bounds or if the copied bytes do not fit at `dest`.
+    pub fn copy_within<R: RangeBounds<usize>>(&mut self, src: R, dest: usize) -> &mut Self {
+        self.inner.as_mut().copy_within(src, dest);
+        self
+    }
+
+    /// Swaps the `len` bytes at offset `a` with the `len` bytes at offset `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regions overlap or if one of them is out of bounds.
+    pub fn swap_regions(&mut self, a: usize, b: usize, len: usize) -> &mut Self {
+        let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+        assert!(lo + len <= hi || len == 0, "regions at {} and {} of length {} overlap", lo, hi, len);
+        let (head, tail) = self.inner.as_mut().split_at_mut(hi);
+        head[lo..lo + len].swap_with_slice(&mut tail[..len]);
+        self
+    }
+
+    /// Decodes the little-endian field at the given `pos` offset in bytes and returns a mutable
+    /// proxy which writes it back when dropped.
+    #[track_caller]
+    pub fn get_mut_le<V: ReadBytes + WriteBytes>(&mut self, pos: usize) -> FieldMut<'_, V> {
+        debug_check::<V>("get_mut_le", pos, self.inner.as_mut().len());
+        FieldMut::new(&mut self.inner.as_mut()[pos..], crate::Endian::Little)
+    }
+
+    /// Decodes the big-endian field at the given `pos` offset in bytes and returns a mutable
+    /// proxy which writes it back when dropped.
+    #[track_caller]
+    pub fn get_mut_be<V: ReadBytes + WriteBytes>(&mut self, pos: usize) -> FieldMut<'_, V> {
+        debug_check::<V>("get_mut_be", pos, self.inner.as_mut().len());
+        FieldMut::new(&mut self.inner.as_mut()[pos..], crate::Endian::Big)
+    }
+
+    /// Decodes the little-endian field at the given `pos` offset in bytes, passes it through `f`
+    /// and writes the result back at the same offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn map_le<V: ReadBytes + WriteBytes, F: FnOnce(V) -> V>(&mut self, pos: usize, f: F) -> &mut Self {
+        debug_check::<V>("map_le", pos, self.inner.as_mut().len());
+        let bytes = &mut self.inner.as_mut()[pos..];
+        f(V::read_bytes_le(bytes)).write_bytes_le(bytes);
+        self
+    }
+
+    /// Decodes the big-endian field at the given `pos` offset in bytes, passes it through `f`
+    /// and writes the result back at the same offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too small to store the field.
+    #[track_caller]
+    pub fn map_be<V: ReadBytes + WriteBytes, F: FnOnce(V) -> V>(&mut self, pos: usize, f: F) -> &mut Self {
+        debug_check::<V>("map_be", pos, self.inner.as_mut().len());
+        let bytes = &mut self.inner.as_mut()[pos..];
+        f(V::read_bytes_be(bytes)).write_bytes_be(bytes);
+        self
+    }
+
+    /// Applies [map_le](ByteBuf::map_le) to every consecutive field, starting at offset 0.
+    ///
+    /// Trailing bytes which do not form a whole field are left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `V` is zero-sized.
+    pub fn map_all_le<V: ReadBytes + WriteBytes, F: FnMut(V) -> V>(&mut self, mut f: F) -> &mut Self {
+        for bytes in self.inner.as_mut().chunks_exact_mut(V::SIZE) {
+            f(V::read_bytes_le(bytes)).write_bytes_le(bytes);
+        }
+        self
+    }
+
+    /// Applies [map_be](ByteBuf::map_be) to every consecutive field, starting at offset 0.
+    ///
+    /// Trailing bytes which do not form a whole field are left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `V` is zero-sized.
+    pub fn map_all_be<V: ReadBytes + WriteBytes, F: FnMut(V) -> V>(&mut self, mut f: F) -> &mut Self {
+        for bytes in self.inner.as_mut().chunks_exact_mut(V::SIZE) {
+            f(V::read_bytes_be(bytes)).write_bytes_be(bytes);
+        }
+        self
+    }
 }
 
 impl<T: AsMut<[u8]>> AsMut<[u8]> for ByteBuf<T> {
@@ -73,6 +1105,21 @@ impl<T: AsMut<[u8]>> AsMut<[u8]> for ByteBuf<T> {
     }
 }
 
+/// Allows passing a `&ByteBuf` where a `&[u8]` is expected and calling slice methods on it.
+impl<T: AsRef<[u8]>> Deref for ByteBuf<T> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.inner.as_ref()
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> DerefMut for ByteBuf<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.inner.as_mut()
+    }
+}
+
 impl<T> ByteBuf<T> {
     /// Allocates a new ByteBuf by wrapping the given bytes-like object.
     /// 
@@ -86,6 +1133,105 @@ impl<T> ByteBuf<T> {
     pub fn into_inner(self) -> T {
         self.inner
     }
+
+    /// Converts this buffer into a [Vec].
+    ///
+    /// *A buffer wrapping a [Vec] is moved out without copying.*
+    #[cfg(feature = "std")]
+    pub fn into_vec(self) -> Vec<u8>
+    where
+        T: Into<Vec<u8>>
+    {
+        self.inner.into()
+    }
+}
+
+/// Moves the bytes out of the buffer, without copying when it wraps a [Vec].
+#[cfg(feature = "std")]
+impl<T: Into<Vec<u8>>> From<ByteBuf<T>> for Vec<u8> {
+    fn from(value: ByteBuf<T>) -> Self {
+        value.into_vec()
+    }
+}
+
+impl<const N: usize> From<StaticByteBuf<N>> for [u8; N] {
+    fn from(value: StaticByteBuf<N>) -> Self {
+        value.inner
+    }
+}
+
+/// Collects bytes into a growable buffer.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::ByteBuf;
+///
+/// let buffer: ByteBuf<Vec<u8>> = (1u8..=4).collect();
+/// assert_eq!(buffer.as_ref(), [1, 2, 3, 4]);
+/// ```
+#[cfg(feature = "std")]
+impl FromIterator<u8> for ByteBuf<Vec<u8>> {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Extend<u8> for ByteBuf<Vec<u8>> {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        self.inner.extend(iter)
+    }
+}
+
+impl<T> ByteBuf<ByteBuf<T>> {
+    /// Removes one layer of wrapping from a nested [ByteBuf].
+    pub fn flatten(self) -> ByteBuf<T> {
+        self.inner
+    }
+}
+
+/// Iterates over the bytes of the buffer.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::ByteBuf;
+///
+/// let buffer = ByteBuf::new([1u8, 2, 3]);
+/// let mut sum = 0;
+/// for b in &buffer {
+///     sum += *b as u32;
+/// }
+/// assert_eq!(sum, 6);
+/// ```
+impl<'a, T: AsRef<[u8]>> IntoIterator for &'a ByteBuf<T> {
+    type Item = &'a u8;
+    type IntoIter = core::slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.as_ref().iter()
+    }
+}
+
+/// Consumes the buffer into an iterator over its bytes.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::ByteBuf;
+///
+/// let buffer = ByteBuf::new(vec![1u8, 2, 3]);
+/// assert_eq!(buffer.into_iter().map(u32::from).sum::<u32>(), 6);
+/// ```
+#[cfg(feature = "std")]
+impl IntoIterator for ByteBuf<Vec<u8>> {
+    type Item = u8;
+    type IntoIter = std::vec::IntoIter<u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
 }
 
 impl<T: AsRef<[u8]>> Index<usize> for ByteBuf<T> {
@@ -146,13 +1292,113 @@ impl<T: Clone> Clone for ByteBuf<T> {
 
 impl<T: Copy> Copy for ByteBuf<T> {}
 
-impl<T: PartialEq> PartialEq for ByteBuf<T> {
+/// Compares buffers by content, exactly like a `[u8]`, so that equality agrees with [Ord] and
+/// [Hash].
+impl<T: AsRef<[u8]>> PartialEq for ByteBuf<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.inner == other.inner
+        self.as_ref() == other.as_ref()
+    }
+}
+
+impl<T: AsRef<[u8]>> Eq for ByteBuf<T> {}
+
+/// Orders buffers lexicographically by content, exactly like a `[u8]`.
+impl<T: AsRef<[u8]>> PartialOrd for ByteBuf<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders buffers lexicographically by content, exactly like a `[u8]`.
+impl<T: AsRef<[u8]>> Ord for ByteBuf<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp(other.as_ref())
+    }
+}
+
+/// Hashes the content of the buffer exactly like a `[u8]`, which keeps lookups through
+/// [Borrow] consistent.
+impl<T: AsRef<[u8]>> Hash for ByteBuf<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().hash(state)
     }
 }
 
-impl<T: Eq> Eq for ByteBuf<T> {}
+/// The maximum number of bytes of a [ByteBuf] emitted by its [defmt::Format] implementation.
+#[cfg(feature = "defmt")]
+const DEFMT_MAX_BYTES: usize = 32;
+
+/// Emits the length of the buffer followed by its first 32 bytes in hexadecimal, and `..` if
+/// the buffer is longer.
+#[cfg(feature = "defmt")]
+impl<T: AsRef<[u8]>> defmt::Format for ByteBuf<T> {
+    fn format(&self, f: defmt::Formatter) {
+        let bytes = self.inner.as_ref();
+        let shown = &bytes[..bytes.len().min(DEFMT_MAX_BYTES)];
+        match shown.len() < bytes.len() {
+            true => defmt::write!(f, "ByteBuf[{=usize}] {=[u8]:02x}..", bytes.len(), shown),
+            false => defmt::write!(f, "ByteBuf[{=usize}] {=[u8]:02x}", bytes.len(), shown)
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for ByteBuf<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        T::arbitrary(u).map(Self::new)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        T::size_hint(depth)
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl<T: AsRef<[u8]>> subtle::ConstantTimeEq for ByteBuf<T> {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.as_ref().ct_eq(other.as_ref())
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl ByteBuf<bytes::Bytes> {
+    /// Creates a new [ByteBuf] over shared immutable bytes.
+    pub fn from_bytes(bytes: bytes::Bytes) -> ByteBuf<bytes::Bytes> {
+        Self::new(bytes)
+    }
+
+    /// Extracts the wrapped [Bytes](bytes::Bytes).
+    pub fn into_bytes(self) -> bytes::Bytes {
+        self.inner
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl ByteBuf<bytes::BytesMut> {
+    /// Creates a new [ByteBuf] over a unique mutable buffer.
+    ///
+    /// *Only the initialized length of the buffer is accessible, not its spare capacity: use
+    /// `BytesMut::zeroed` to create a buffer ready to be filled field by field.*
+    pub fn from_bytes_mut(bytes: bytes::BytesMut) -> ByteBuf<bytes::BytesMut> {
+        Self::new(bytes)
+    }
+
+    /// Converts this buffer into an immutable one which can be cheaply cloned and shared.
+    pub fn freeze(self) -> ByteBuf<bytes::Bytes> {
+        ByteBuf::new(self.inner.freeze())
+    }
+
+    /// Extracts the contents of this buffer as immutable [Bytes](bytes::Bytes).
+    pub fn into_bytes(self) -> bytes::Bytes {
+        self.inner.freeze()
+    }
+}
+
+impl<T: AsRef<[u8]>> Borrow<[u8]> for ByteBuf<T> {
+    fn borrow(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
 
 impl<T: Debug> Debug for ByteBuf<T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -169,9 +1415,261 @@ impl<T: Display> Display for ByteBuf<T> {
 /// A shortcut to create a stack allocated fixed size [ByteBuf](ByteBuf)
 pub type StaticByteBuf<const N: usize> = ByteBuf<[u8; N]>;
 
+struct FieldCheck<V, const N: usize, const POS: usize>(PhantomData<V>);
+
+impl<V: FixedSize, const N: usize, const POS: usize> FieldCheck<V, N, POS> {
+    const IN_BOUNDS: () = assert!(POS + V::SIZE <= N, "field is out of the bounds of the buffer");
+}
+
+struct SizeCheck<const A: usize, const B: usize, const N: usize>;
+
+impl<const A: usize, const B: usize, const N: usize> SizeCheck<A, B, N> {
+    const SUM: () = assert!(A + B == N, "the sizes of the parts do not add up to the size of the buffer");
+}
+
+/// Copies a slice of exactly `N` bytes into a new buffer.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::StaticByteBuf;
+///
+/// let buffer = StaticByteBuf::<4>::try_from(&[1, 2, 3, 4][..]).unwrap();
+/// assert_eq!(buffer.into_inner(), [1, 2, 3, 4]);
+/// let err = StaticByteBuf::<4>::try_from(&[1, 2][..]).unwrap_err();
+/// assert_eq!(err.to_string(), "expected exactly 4 bytes, got 2");
+/// ```
+impl<const N: usize> TryFrom<&[u8]> for StaticByteBuf<N> {
+    type Error = LengthError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        value.try_into().map(Self::new).map_err(|_| LengthError::new(N, value.len()))
+    }
+}
+
+impl<const N: usize> StaticByteBuf<N> {
+    /// Copies the `N` bytes at the given `pos` offset of `bytes` into a new buffer.
+    ///
+    /// *This snapshots a fixed size window of a larger buffer, such as a record header.*
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes`: the buffer to copy from.
+    /// * `pos`: the offset of the window in bytes.
+    ///
+    /// returns: Result<StaticByteBuf<N>, BoundsError>
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the window does not fit in `bytes`.
+    pub fn try_from_slice_at(bytes: &[u8], pos: usize) -> Result<Self, BoundsError> {
+        crate::cursor::check_bounds(pos, N, bytes.len())?;
+        let mut inner = [0; N];
+        inner.copy_from_slice(&bytes[pos..pos + N]);
+        Ok(Self::new(inner))
+    }
+
+    /// Concatenates two buffers into a new buffer whose size is the sum of both sizes.
+    ///
+    /// The sizes are checked at compile time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytesutil::StaticByteBuf;
+    ///
+    /// let header: StaticByteBuf<6> = StaticByteBuf::concat(StaticByteBuf::new(*b"BPX"), StaticByteBuf::new([1, 2, 3]));
+    /// assert_eq!(header.into_inner(), [b'B', b'P', b'X', 1, 2, 3]);
+    /// ```
+    ///
+    /// Mismatched sizes fail to compile:
+    ///
+    /// ```compile_fail
+    /// use bytesutil::StaticByteBuf;
+    ///
+    /// let header: StaticByteBuf<8> = StaticByteBuf::concat(StaticByteBuf::new([0; 4]), StaticByteBuf::new([0; 2]));
+    /// ```
+    pub fn concat<const A: usize, const B: usize>(a: StaticByteBuf<A>, b: StaticByteBuf<B>) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = SizeCheck::<A, B, N>::SUM;
+        let mut inner = [0; N];
+        inner[..A].copy_from_slice(&a.inner);
+        inner[A..].copy_from_slice(&b.inner);
+        ByteBuf::new(inner)
+    }
+
+    /// Splits this buffer into a buffer of the first `A` bytes and a buffer of the remaining
+    /// `B` bytes.
+    ///
+    /// The sizes are checked at compile time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytesutil::StaticByteBuf;
+    ///
+    /// let buffer = StaticByteBuf::new([1, 2, 3, 4, 5]);
+    /// let (magic, rest) = buffer.split_array::<2, 3>();
+    /// assert_eq!(magic.into_inner(), [1, 2]);
+    /// assert_eq!(rest.into_inner(), [3, 4, 5]);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use bytesutil::StaticByteBuf;
+    ///
+    /// let buffer = StaticByteBuf::new([0; 5]);
+    /// buffer.split_array::<2, 2>();
+    /// ```
+    pub fn split_array<const A: usize, const B: usize>(&self) -> (StaticByteBuf<A>, StaticByteBuf<B>) {
+        #[allow(clippy::let_unit_value)]
+        let () = SizeCheck::<A, B, N>::SUM;
+        let mut a = [0; A];
+        let mut b = [0; B];
+        a.copy_from_slice(&self.inner[..A]);
+        b.copy_from_slice(&self.inner[A..]);
+        (ByteBuf::new(a), ByteBuf::new(b))
+    }
+
+    /// Read a little-endian field at the constant `POS` offset in bytes.
+    ///
+    /// The field is checked at compile time to fit in the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytesutil::StaticByteBuf;
+    ///
+    /// let buffer = StaticByteBuf::new([0, 0, 0, 0, 42, 0, 0, 0]);
+    /// assert_eq!(buffer.get_le_at::<u32, 4>(), 42);
+    /// ```
+    ///
+    /// An out of bounds field fails to compile:
+    ///
+    /// ```compile_fail
+    /// use bytesutil::StaticByteBuf;
+    ///
+    /// let buffer = StaticByteBuf::new([0; 8]);
+    /// buffer.get_le_at::<u32, 5>();
+    /// ```
+    pub fn get_le_at<V: ReadBytes, const POS: usize>(&self) -> V {
+        #[allow(clippy::let_unit_value)]
+        let () = FieldCheck::<V, N, POS>::IN_BOUNDS;
+        V::read_bytes_le(&self.inner[POS..])
+    }
+
+    /// Read a big-endian field at the constant `POS` offset in bytes.
+    ///
+    /// The field is checked at compile time to fit in the buffer.
+    ///
+    /// ```compile_fail
+    /// use bytesutil::StaticByteBuf;
+    ///
+    /// let buffer = StaticByteBuf::new([0; 8]);
+    /// buffer.get_be_at::<u64, 1>();
+    /// ```
+    pub fn get_be_at<V: ReadBytes, const POS: usize>(&self) -> V {
+        #[allow(clippy::let_unit_value)]
+        let () = FieldCheck::<V, N, POS>::IN_BOUNDS;
+        V::read_bytes_be(&self.inner[POS..])
+    }
+
+    /// Write the given little-endian `value` field at the constant `POS` offset in bytes.
+    ///
+    /// The field is checked at compile time to fit in the buffer.
+    ///
+    /// ```compile_fail
+    /// use bytesutil::StaticByteBuf;
+    ///
+    /// let mut buffer = StaticByteBuf::new([0; 8]);
+    /// buffer.set_le_at::<u16, 7>(42);
+    /// ```
+    pub fn set_le_at<V: WriteBytes, const POS: usize>(&mut self, value: V) -> &mut Self {
+        #[allow(clippy::let_unit_value)]
+        let () = FieldCheck::<V, N, POS>::IN_BOUNDS;
+        value.write_bytes_le(&mut self.inner[POS..]);
+        self
+    }
+
+    /// Write the given big-endian `value` field at the constant `POS` offset in bytes.
+    ///
+    /// The field is checked at compile time to fit in the buffer.
+    ///
+    /// ```compile_fail
+    /// use bytesutil::StaticByteBuf;
+    ///
+    /// let mut buffer = StaticByteBuf::new([0; 8]);
+    /// buffer.set_be_at::<u64, 8>(42);
+    /// ```
+    pub fn set_be_at<V: WriteBytes, const POS: usize>(&mut self, value: V) -> &mut Self {
+        #[allow(clippy::let_unit_value)]
+        let () = FieldCheck::<V, N, POS>::IN_BOUNDS;
+        value.write_bytes_be(&mut self.inner[POS..]);
+        self
+    }
+}
+
+impl<const N: usize> FixedSize for StaticByteBuf<N> {
+    const SIZE: usize = N;
+}
+
+/// Writes the raw bytes of the buffer; both byte orders are identical.
+impl<const N: usize> crate::ByteSize for StaticByteBuf<N> {
+    fn byte_size(&self) -> usize {
+        N
+    }
+}
+
+impl<const N: usize> WriteBytes for StaticByteBuf<N> {
+    fn write_bytes_le(&self, bytes: &mut [u8]) {
+        bytes[..N].copy_from_slice(&self.inner);
+    }
+
+    fn write_bytes_be(&self, bytes: &mut [u8]) {
+        bytes[..N].copy_from_slice(&self.inner);
+    }
+}
+
+/// Reads the raw bytes of the buffer; both byte orders are identical.
+impl<const N: usize> ReadBytes for StaticByteBuf<N> {
+    fn read_bytes_le(bytes: &[u8]) -> Self {
+        Self::new(bytes[..N].try_into().unwrap())
+    }
+
+    fn read_bytes_be(bytes: &[u8]) -> Self {
+        Self::new(bytes[..N].try_into().unwrap())
+    }
+}
+
+/// Writes the raw bytes of the buffer; both byte orders are identical.
+#[cfg(feature = "std")]
+impl<const N: usize> crate::WriteTo for StaticByteBuf<N> {
+    fn write_to_le<W: std::io::Write>(&self, mut dst: W) -> std::io::Result<()> {
+        dst.write_all(&self.inner)
+    }
+
+    fn write_to_be<W: std::io::Write>(&self, mut dst: W) -> std::io::Result<()> {
+        dst.write_all(&self.inner)
+    }
+}
+
+/// Reads the raw bytes of the buffer; both byte orders are identical.
+#[cfg(feature = "std")]
+impl<const N: usize> crate::ReadFrom for StaticByteBuf<N> {
+    fn read_from_le<R: std::io::Read>(mut src: R) -> std::io::Result<Self> {
+        let mut inner = [0; N];
+        crate::error::read_exact_for::<Self, _>(&mut src, &mut inner)?;
+        Ok(Self::new(inner))
+    }
+
+    fn read_from_be<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+        Self::read_from_le(src)
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::extra_unused_lifetimes, clippy::unnecessary_cast)]
 mod tests {
-    use crate::{StaticByteBuf, ByteBuf};
+    use crate::{StaticByteBuf, ByteBuf, View};
 
     fn test_function<'a, I: Into<ByteBuf<[u8; 16]>>>(_: I) {
     }
@@ -203,4 +1701,528 @@ mod tests {
         assert!(buffer.get_be::<i32>(0) == 42);
         assert!(inner[3] == 42);
     }
+
+    #[test]
+    fn find() {
+        let buffer = ByteBuf::new(b"aaabaab".as_slice());
+        assert_eq!(buffer.find(b"aab"), Some(1));
+        assert_eq!(buffer.rfind(b"aab"), Some(4));
+        assert_eq!(buffer.find_from(2, b"aab"), Some(4));
+        assert_eq!(buffer.find_from(5, b"aab"), None);
+        assert_eq!(buffer.find_from(8, b"a"), None);
+        assert_eq!(buffer.find(b"ab"), Some(2));
+        assert_eq!(buffer.rfind(b"b"), Some(6));
+        assert_eq!(buffer.find(b"c"), None);
+        assert_eq!(buffer.find_iter(b"a").collect::<Vec<_>>(), vec![0, 1, 2, 4, 5]);
+        assert_eq!(buffer.find_iter(b"aa").collect::<Vec<_>>(), vec![0, 4]);
+    }
+
+    #[test]
+    fn find_edge_cases() {
+        let buffer = ByteBuf::new(b"abc".as_slice());
+        assert_eq!(buffer.find(b"bc"), Some(1));
+        assert_eq!(buffer.rfind(b"abc"), Some(0));
+        assert_eq!(buffer.find(b"abcd"), None);
+        assert_eq!(buffer.rfind(b"abcd"), None);
+        assert_eq!(buffer.find(b""), Some(0));
+        assert_eq!(buffer.rfind(b""), Some(3));
+        assert_eq!(buffer.find_from(3, b""), Some(3));
+        assert_eq!(buffer.find_iter(b"").collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(ByteBuf::new([]).find_iter(b"a").count(), 0);
+    }
+
+    #[test]
+    fn view() {
+        #[repr(align(8))]
+        struct Aligned([u8; 17]);
+
+        let mut data = Aligned([0; 17]);
+        let mut buffer = ByteBuf::new(&mut data.0[..]);
+        buffer.set_le(0, 0x0102030405060708u64).set_be(9, 0x0102030405060708u64);
+        assert!(matches!(buffer.view_le::<u64>(0), View::Borrowed(_)) == crate::is_native_le());
+        assert!(matches!(buffer.view_le::<u64>(1), View::Owned(_)));
+        for pos in [0, 1, 9] {
+            assert_eq!(*buffer.view_le::<u64>(pos), buffer.get_le::<u64>(pos));
+            assert_eq!(*buffer.view_be::<u64>(pos), buffer.get_be::<u64>(pos));
+        }
+    }
+
+    #[test]
+    fn unaligned() {
+        #[repr(align(8))]
+        struct Aligned([u8; 24]);
+
+        let mut data = Aligned([0; 24]);
+        let mut buffer = ByteBuf::new(&mut data.0[..]);
+        for pos in [1, 3, 5, 7] {
+            buffer.write_unaligned_le(pos, 0x0102030405060708u64);
+            assert_eq!(buffer.as_ref()[pos..pos + 8], [8, 7, 6, 5, 4, 3, 2, 1]);
+            assert_eq!(buffer.read_unaligned_le::<u64>(pos), 0x0102030405060708);
+            assert_eq!(buffer.read_unaligned_be::<u64>(pos), 0x0807060504030201);
+            buffer.write_unaligned_be(pos + 8, -2i64);
+            assert_eq!(buffer.get_be::<i64>(pos + 8), -2);
+            assert_eq!(buffer.read_unaligned_be::<i64>(pos + 8), -2);
+        }
+        assert_eq!(buffer.read_unaligned_le::<f32>(19), buffer.get_le::<f32>(19));
+    }
+
+    #[test]
+    fn static_buffer_field() {
+        type Header = (u32, StaticByteBuf<16>, u16);
+        let hash = StaticByteBuf::new([0xAB; 16]);
+        let mut buffer = StaticByteBuf::<22>::default();
+        buffer.set_le(0, (42u32, hash, 7u16));
+        assert_eq!(&buffer.as_ref()[4..20], &[0xAB; 16]);
+        assert_eq!(buffer.get_le::<Header>(0), (42, hash, 7));
+        assert_eq!(buffer.get_be::<StaticByteBuf<16>>(4), hash);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn static_buffer_io() {
+        use std::io::Cursor;
+
+        use crate::{ReadExt, WriteExt};
+
+        type Header = (u32, StaticByteBuf<16>, u16);
+        let hash = StaticByteBuf::new([0xAB; 16]);
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_be((42u32, hash, 7u16)).unwrap();
+        assert_eq!(cursor.get_ref().len(), 22);
+        cursor.set_position(0);
+        assert_eq!(cursor.read_be::<Header>().unwrap(), (42, hash, 7));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn to_vec() {
+        let buffer = StaticByteBuf::new([1, 2, 3]);
+        assert_eq!(buffer.to_vec(), [1, 2, 3]);
+        assert_eq!(buffer.into_vec(), [1, 2, 3]);
+        assert_eq!(ByteBuf::new(&[4u8, 5][..]).into_vec(), [4, 5]);
+        let inner = vec![6u8, 7];
+        let ptr = inner.as_ptr();
+        let v = ByteBuf::new(inner).into_vec();
+        assert_eq!(v.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn const_fields() {
+        let mut buffer = StaticByteBuf::<12>::default();
+        buffer.set_le_at::<u32, 0>(42).set_be_at::<u64, 4>(0x0102030405060708);
+        assert_eq!(buffer.get_le_at::<u32, 0>(), 42);
+        assert_eq!(buffer.get_be_at::<u64, 4>(), 0x0102030405060708);
+        assert_eq!(buffer.get_le_at::<u8, 11>(), 8);
+        assert_eq!(buffer.get_le_at::<(), 12>(), ());
+    }
+
+    #[test]
+    fn nested() {
+        let mut buffer = ByteBuf::new(ByteBuf::new(vec![0u8; 8]));
+        buffer.set_be(4, 42u32);
+        assert_eq!(buffer.get_be::<u32>(4), 42);
+        assert_eq!(buffer.as_ref().len(), 8);
+        let buffer = buffer.flatten();
+        assert_eq!(buffer.get_be::<u32>(4), 42);
+        assert_eq!(buffer.into_inner()[7], 42);
+    }
+
+    #[test]
+    fn field_iterators() {
+        let mut buffer = ByteBuf::new([0u8; 13]);
+        for i in 0..6 {
+            buffer.set_be(i * 2, i as u16);
+        }
+        assert_eq!(buffer.iter_be::<u16>().collect::<Vec<_>>(), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(buffer.iter_le::<u32>().count(), 3);
+        assert_eq!(buffer.column_be::<u16>(2, 4).collect::<Vec<_>>(), [1, 3, 5]);
+        assert_eq!(buffer.column_le::<u8>(12, 1).collect::<Vec<_>>(), [0]);
+        assert_eq!(buffer.column_le::<u8>(13, 1).count(), 0);
+        assert_eq!(buffer.column_le::<u8>(usize::MAX, 1).count(), 0);
+    }
+
+    #[test]
+    fn field_iterators_overflow() {
+        let buffer = ByteBuf::new([1u8; 16]);
+        assert_eq!(buffer.column_le::<u8>(0, usize::MAX / 2).collect::<Vec<_>>(), [1]);
+        assert_eq!(buffer.column_le::<u8>(8, usize::MAX / 2 + 1).collect::<Vec<_>>(), [1]);
+        assert_eq!(buffer.column_le::<u8>(usize::MAX - 1, usize::MAX).count(), 0);
+        let mut iter = buffer.column_le::<u64>(8, usize::MAX / 2);
+        assert_eq!(iter.next(), Some(0x0101010101010101));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn borrow() {
+        use std::collections::HashMap;
+
+        let mut map = HashMap::new();
+        map.insert(ByteBuf::new([1u8, 2, 3]).to_owned_buf(), "abc");
+        map.insert(ByteBuf::new(vec![4, 5]), "de");
+        assert_eq!(map.get(&[1u8, 2, 3][..]), Some(&"abc"));
+        assert_eq!(map.get(&[4u8, 5][..]), Some(&"de"));
+        assert_eq!(map.get(&[4u8][..]), None);
+    }
+
+    #[test]
+    fn field_mut() {
+        fn increment(mut field: crate::FieldMut<u32>) {
+            *field += 1;
+        }
+
+        let mut buffer = ByteBuf::new([0u8; 16]);
+        buffer.set_le(12, 41u32);
+        increment(buffer.get_mut_le(12));
+        assert_eq!(buffer.as_ref()[12..], [42, 0, 0, 0]);
+        {
+            let mut field = buffer.get_mut_be::<u16>(0);
+            *field = 0x0102;
+            field.commit();
+        }
+        assert_eq!(buffer.as_ref()[..2], [1, 2]);
+        let mut field = buffer.get_mut_le::<u32>(12);
+        *field = 7;
+        field.discard();
+        assert_eq!(buffer.get_le::<u32>(12), 42);
+    }
+
+    #[test]
+    fn ct_eq() {
+        let mac = ByteBuf::new([0xAB; 32]);
+        assert!(mac.ct_eq(&[0xAB; 32]));
+        assert!(!mac.ct_eq(&[0xAB; 31]));
+        assert!(!mac.ct_eq(&[]));
+        let mut other = [0xAB; 32];
+        other[31] = 0;
+        assert!(!mac.ct_eq(&other));
+        assert!(ByteBuf::new([0u8; 0]).ct_eq(&[]));
+        #[cfg(feature = "subtle")]
+        {
+            use subtle::ConstantTimeEq;
+            assert!(bool::from(ConstantTimeEq::ct_eq(&mac, &ByteBuf::new([0xAB; 32]))));
+            assert!(!bool::from(ConstantTimeEq::ct_eq(&mac, &ByteBuf::new(other))));
+        }
+    }
+
+    #[test]
+    fn copy_within() {
+        let mut buffer = ByteBuf::new([1, 2, 3, 4, 5, 6, 7, 8]);
+        buffer.copy_within(0..4, 2);
+        assert_eq!(buffer.as_ref(), &[1, 2, 1, 2, 3, 4, 7, 8]);
+        buffer.copy_within(4.., 0).swap_regions(6, 0, 2);
+        assert_eq!(buffer.as_ref(), &[7, 8, 7, 8, 3, 4, 3, 4]);
+        buffer.swap_regions(3, 3, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "overlap")]
+    fn swap_regions_overlap() {
+        ByteBuf::new([0; 8]).swap_regions(0, 2, 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn swap_regions_out_of_bounds() {
+        ByteBuf::new([0; 8]).swap_regions(0, 6, 4);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hexdump() {
+        let mut data = b"Hello, world!\x00\x01\xff".to_vec();
+        data.extend_from_slice(b"BPX");
+        let dump = ByteBuf::new(data).hexdump().to_string();
+        assert_eq!(
+            dump,
+            "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 00 01 ff  |Hello, world!...|\n\
+             00000010  42 50 58                                          |BPX|"
+        );
+        assert_eq!(ByteBuf::new([0u8; 0]).hexdump().to_string(), "");
+    }
+
+    #[test]
+    fn concat_split() {
+        let magic = StaticByteBuf::new(*b"BPX");
+        let mut version = StaticByteBuf::new([0; 2]);
+        version.set_be(0, 2u16);
+        let header = StaticByteBuf::<5>::concat(magic, version);
+        assert_eq!(header.into_inner(), [b'B', b'P', b'X', 0, 2]);
+        let (left, right) = header.split_array::<3, 2>();
+        assert_eq!((left, right), (magic, version));
+        let (empty, all) = header.split_array::<0, 5>();
+        assert_eq!(empty.into_inner(), []);
+        assert_eq!(StaticByteBuf::<5>::concat(empty, all), header);
+        assert_eq!(header.split_at(3), (&b"BPX"[..], &[0, 2][..]));
+    }
+
+    #[cfg(all(feature = "debug-checks", debug_assertions))]
+    #[test]
+    #[should_panic(expected = "ByteBuf::get_le::<u32>: need 4 bytes at offset 6, got 2 (buffer length is 8)")]
+    fn debug_checks() {
+        ByteBuf::new([0; 8]).get_le::<u32>(6);
+    }
+
+    #[cfg(all(feature = "debug-checks", debug_assertions))]
+    #[test]
+    #[should_panic(expected = "ByteBuf::set_be::<u16>: need 2 bytes at offset 9, got 0 (buffer length is 8)")]
+    fn debug_checks_past_end() {
+        ByteBuf::new([0; 8]).set_be(9, 1u16);
+    }
+
+    #[test]
+    fn conversions() {
+        use crate::{BoundsError, LengthError};
+
+        let bytes = [1u8, 2, 3, 4, 5, 6];
+        assert_eq!(StaticByteBuf::<6>::try_from(&bytes[..]).unwrap().into_inner(), bytes);
+        assert_eq!(StaticByteBuf::<4>::try_from(&bytes[..]).unwrap_err(), LengthError::new(4, 6));
+        assert_eq!(StaticByteBuf::<8>::try_from(&bytes[..]).unwrap_err().actual(), 6);
+        let window = StaticByteBuf::<4>::try_from_slice_at(&bytes, 2).unwrap();
+        assert_eq!(window.get_be::<u32>(0), 0x03040506);
+        assert_eq!(StaticByteBuf::<4>::try_from_slice_at(&bytes, 3).unwrap_err(), BoundsError::new(3, 4, 6));
+        assert!(StaticByteBuf::<4>::try_from_slice_at(&bytes, usize::MAX).is_err());
+
+        let mut buffer: ByteBuf<Vec<u8>> = bytes.iter().copied().filter(|b| b % 2 == 0).collect();
+        buffer.extend([0x10, 0x20]);
+        assert_eq!(buffer.get_le::<u32>(1), 0x20100604);
+        let v: Vec<u8> = buffer.into();
+        assert_eq!(v, [2, 4, 6, 0x10, 0x20]);
+        assert_eq!(Vec::from(window), [3, 4, 5, 6]);
+    }
+
+    #[cfg(not(all(feature = "debug-checks", debug_assertions)))]
+    #[test]
+    #[should_panic(expected = "need 4 bytes, got 0")]
+    fn empty_get() {
+        ByteBuf::new(&[][..]).get_le::<u32>(0);
+    }
+
+    #[cfg(not(all(feature = "debug-checks", debug_assertions)))]
+    #[test]
+    #[should_panic(expected = "need 2 bytes, got 1")]
+    fn short_set() {
+        ByteBuf::new([0; 3]).set_be(2, 1u16);
+    }
+
+    #[test]
+    #[should_panic(expected = "ByteBuf::get_be::<u64>: need 8 bytes at offset 1, got 0 (buffer length is 0)")]
+    fn empty_get_past_end() {
+        ByteBuf::new([0u8; 0]).get_be::<u64>(1);
+    }
+
+    #[test]
+    fn empty() {
+        let mut buffer = ByteBuf::new([0u8; 0]);
+        buffer.set_le(0, ());
+        assert_eq!(buffer.get_be::<()>(0), ());
+        assert_eq!(buffer.iter_le::<u8>().count(), 0);
+    }
+
+    #[cfg(all(feature = "defmt", feature = "std"))]
+    #[test]
+    fn defmt() {
+        use crate::testutil::capture_defmt;
+
+        fn contains(bytes: &[u8], part: &[u8]) -> bool {
+            bytes.windows(part.len()).any(|w| w == part)
+        }
+
+        // Arguments are encoded as a u32 length, then a u32 element count and the bytes.
+        let short = capture_defmt(|| defmt::println!("{}", ByteBuf::new([0xAB; 3])));
+        assert!(contains(&short, &[3, 0, 0, 0, 3, 0, 0, 0, 0xAB, 0xAB, 0xAB]));
+        let long: StaticByteBuf<100> = StaticByteBuf::new(core::array::from_fn(|i| i as u8));
+        let long = capture_defmt(|| defmt::println!("{}", long));
+        let mut expected = vec![100, 0, 0, 0, 32, 0, 0, 0];
+        expected.extend(0..32);
+        assert!(contains(&long, &expected));
+        assert!(!contains(&long, &[31, 32]));
+        let big = capture_defmt(|| defmt::println!("{}", crate::Endian::Big));
+        let little = capture_defmt(|| defmt::println!("{}", crate::Endian::Little));
+        let diff: Vec<_> = big.iter().zip(&little).filter(|(a, b)| a != b).collect();
+        assert!(diff.contains(&(&1, &0)));
+    }
+
+    #[test]
+    fn ordering() {
+        let keys: [&[u8]; 5] = [b"b", b"", b"ab", b"a", b"\xFF"];
+        let mut buffers: Vec<ByteBuf<Vec<u8>>> = keys.iter().map(|k| ByteBuf::new(k.to_vec())).collect();
+        buffers.sort();
+        let mut sorted = keys;
+        sorted.sort();
+        assert!(buffers.iter().map(|b| b.as_ref()).eq(sorted));
+        assert!(ByteBuf::new([1, 2]) < ByteBuf::new([1, 3]));
+        let map: std::collections::BTreeMap<_, _> = buffers.into_iter().zip(0..).collect();
+        assert_eq!(map.keys().next().unwrap().as_ref(), b"");
+        assert_eq!(map[&ByteBuf::new(b"ab".to_vec())], 2);
+        struct Prefix([u8; 4], usize);
+        impl AsRef<[u8]> for Prefix {
+            fn as_ref(&self) -> &[u8] {
+                &self.0[..self.1]
+            }
+        }
+        assert!(ByteBuf::new(Prefix([1, 2, 3, 4], 2)) == ByteBuf::new(Prefix([1, 2, 9, 9], 2)));
+    }
+
+    #[test]
+    fn bit_cast() {
+        let mut buffer = ByteBuf::new([0; 14]);
+        buffer.set_le_as::<i32, u32>(0, u32::MAX).set_be_as::<u16, i16>(4, -2).set_le_as::<u64, f64>(6, 1.0);
+        assert_eq!(buffer.get_le::<i32>(0), -1);
+        assert_eq!(buffer.get_le_as::<i32, u32>(0), u32::MAX);
+        assert_eq!(buffer.get_be::<u16>(4), 0xFFFE);
+        assert_eq!(buffer.get_be_as::<u16, i16>(4), -2);
+        assert_eq!(buffer.get_le::<u64>(6), 0x3FF0000000000000);
+        assert_eq!(buffer.get_le_as::<u64, f64>(6), 1.0);
+        assert_eq!(crate::BitCast::<u8>::bit_cast(-128i8), 0x80);
+        assert_eq!(crate::BitCast::<u32>::bit_cast(-0.0f32), 0x80000000);
+    }
+
+    #[test]
+    fn checked_set() {
+        use crate::BoundsError;
+
+        let mut buffer = ByteBuf::new([0u8; 6]);
+        buffer.checked_set_le(0, 0x0102u16).unwrap().checked_set_be(2, 0x03040506u32).unwrap();
+        assert_eq!(buffer.as_ref(), [2, 1, 3, 4, 5, 6]);
+        assert_eq!(buffer.checked_set_le(4, 0u32).unwrap_err(), BoundsError::new(4, 4, 6));
+        assert_eq!(buffer.checked_set_be(usize::MAX, 0u8).unwrap_err(), BoundsError::new(usize::MAX, 1, 6));
+        assert_eq!(buffer.as_ref(), [2, 1, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn bits() {
+        let mut buffer = ByteBuf::new([0xFFu8; 3]);
+        buffer.set_bits_le(4, 12, 0xABC);
+        assert_eq!(buffer.as_ref(), [0xCF, 0xAB, 0xFF]);
+        assert_eq!(buffer.get_bits_le(4, 12), 0xABC);
+        assert_eq!(buffer.get_bits_le(0, 4), 0xF);
+        assert_eq!(buffer.get_bits_le(16, 8), 0xFF);
+        buffer.set_bits_be(4, 12, 0xABC);
+        assert_eq!(buffer.as_ref(), [0xCA, 0xBC, 0xFF]);
+        assert_eq!(buffer.get_bits_be(4, 12), 0xABC);
+        assert_eq!(buffer.get_bits_be(0, 8), 0xCA);
+        buffer.set_bits_le(3, 2, 0xFFFF_FFFC);
+        assert_eq!(buffer.as_ref()[0], 0xC2);
+        assert_eq!(buffer.get_bits_le(5, 0), 0);
+
+        let mut buffer = ByteBuf::new([0u8; 9]);
+        buffer.set_bits_le(3, 64, u64::MAX - 1);
+        assert_eq!(buffer.get_bits_le(3, 64), u64::MAX - 1);
+        assert_eq!(buffer.as_ref()[0], 0xF0);
+        assert_eq!(buffer.as_ref()[8], 0x07);
+        buffer.set_bits_be(7, 64, 0x0123456789ABCDEF);
+        assert_eq!(buffer.get_bits_be(7, 64), 0x0123456789ABCDEF);
+        assert_eq!(buffer.get_bits_be(7, 8), 0x01);
+    }
+
+    #[test]
+    #[should_panic(expected = "9 bits at bit offset 16 do not fit in a buffer of 3 bytes")]
+    fn bits_out_of_bounds() {
+        ByteBuf::new([0u8; 3]).get_bits_le(16, 9);
+    }
+
+    #[test]
+    #[should_panic(expected = "bit_len must be at most 64, got 65")]
+    fn bits_too_long() {
+        ByteBuf::new([0u8; 16]).set_bits_be(0, 65, 0);
+    }
+
+    #[test]
+    fn slice_interop() {
+        fn checksum(bytes: &[u8]) -> u32 {
+            bytes.iter().map(|&b| b as u32).sum()
+        }
+
+        let mut buffer = StaticByteBuf::new([1, 2, 3, 4]);
+        assert_eq!(checksum(&buffer), 10);
+        assert_eq!(buffer.len(), 4);
+        assert!(buffer.starts_with(&[1, 2]));
+        buffer.split_at_mut(2).1.copy_from_slice(&[5, 6]);
+        buffer.set_le(0, 0x0807u16);
+        assert_eq!(buffer.iter().max(), Some(&8));
+        let bytes: [u8; 4] = buffer.into();
+        assert_eq!(bytes, [7, 8, 5, 6]);
+        let vec: Vec<u8> = StaticByteBuf::new(bytes).into();
+        assert_eq!(vec, [7, 8, 5, 6]);
+        assert_eq!(checksum(&ByteBuf::new(vec)), 26);
+    }
+
+    #[test]
+    fn map_fields() {
+        let mut buf = ByteBuf::new([0x12, 0x34, 0x56, 0x78, 0x9A]);
+        buf.map_le::<u16, _>(0, |v| v | 0x00FF).map_be::<u16, _>(2, u16::swap_bytes);
+        assert_eq!(buf.as_ref(), [0xFF, 0x34, 0x78, 0x56, 0x9A]);
+        buf.map_all_be::<u16, _>(|v| v & 0x0FF0);
+        assert_eq!(buf.as_ref(), [0x0F, 0x30, 0x08, 0x50, 0x9A]);
+        let mut calls = 0;
+        buf.map_all_le::<u16, _>(|v| {
+            calls += 1;
+            v.swap_bytes()
+        });
+        assert_eq!(calls, 2);
+        assert_eq!(buf.as_ref(), [0x30, 0x0F, 0x50, 0x08, 0x9A]);
+    }
+
+    #[test]
+    fn typed_offsets() {
+        use crate::Offset;
+
+        const KIND: Offset<u8> = Offset::new(1);
+        const LEN: Offset<u32> = KIND.next();
+        const CRC: Offset<u16> = LEN.next();
+        let mut buf = ByteBuf::new([0; 8]);
+        buf.set_field_le(KIND, 7).set_field_be(LEN, 0x01020304).set_field_le(CRC, 0xBEEF);
+        assert_eq!(buf.as_ref(), [0, 7, 1, 2, 3, 4, 0xEF, 0xBE]);
+        assert_eq!(buf.get_field_le(KIND), 7);
+        assert_eq!(buf.get_field_be(LEN), 0x01020304);
+        assert_eq!(buf.get_field_be(CRC), 0xEFBE);
+        assert_eq!((LEN.pos(), CRC.pos()), (2, 6));
+        assert_eq!(format!("{:?}", CRC), "Offset<u16>(6)");
+    }
+
+    #[test]
+    #[should_panic(expected = "field offset overflows usize")]
+    fn typed_offset_overflow() {
+        crate::Offset::<u32>::new(usize::MAX - 2).next::<u8>();
+    }
+
+    #[test]
+    fn structs() {
+        // magic, version, flags, data size, data offset, checksum, reserved
+        type Header = (u32, u16, u16, u64, u64, u32, u32);
+        let header: Header = (0x58504200, 2, 0x8001, 1 << 40, 32, 0xDEADBEEF, 0);
+        let mut buf = ByteBuf::new([0xFF; 36]);
+        buf.write_struct_be(&header).unwrap();
+        assert_eq!(buf.as_ref()[..8], [0x58, 0x50, 0x42, 0x00, 0, 2, 0x80, 0x01]);
+        assert_eq!(buf.as_ref()[32..], [0xFF; 4]);
+        assert_eq!(buf.read_struct_be::<Header>().unwrap(), header);
+        assert_eq!(buf.get_be::<u64>(16), 32);
+        buf.write_struct_le(&header).unwrap();
+        assert_eq!(buf.read_struct_le::<Header>().unwrap(), header);
+        assert_eq!(buf.get_le::<u32>(24), 0xDEADBEEF);
+        let mut short = ByteBuf::new([0; 31]);
+        let err = short.read_struct_le::<Header>().unwrap_err();
+        assert_eq!(err.to_string(), "need 32 bytes at offset 0, got 31");
+        assert!(short.write_struct_be(&header).is_err());
+        assert_eq!(short.as_ref(), [0; 31]);
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    fn bytes_interop() {
+        use bytes::{Bytes, BytesMut};
+
+        let mut buf = ByteBuf::from_bytes_mut(BytesMut::zeroed(6));
+        buf.set_be(0, 0x0102u16).set_le(2, 0x06050403u32);
+        assert_eq!(buf.get_le::<u16>(4), 0x0605);
+        let shared = buf.freeze();
+        let copy = shared.clone();
+        assert_eq!(copy.get_be::<u32>(0), 0x01020304);
+        assert_eq!(shared.into_bytes(), Bytes::from_static(&[1, 2, 3, 4, 5, 6]));
+        let buf = ByteBuf::from_bytes(Bytes::from_static(b"\x00\x2A"));
+        assert_eq!(buf.get_be::<u16>(0), 42);
+        assert_eq!(ByteBuf::from_bytes_mut(BytesMut::from(&b"ab"[..])).into_bytes(), &b"ab"[..]);
+    }
 }
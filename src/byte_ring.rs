@@ -0,0 +1,237 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{BoundsError, ReadBytes, StaticByteBuf, WriteBytes};
+
+/// A fixed capacity FIFO of fields stored in a [StaticByteBuf], without allocation.
+///
+/// Each field occupies [SIZE](crate::FixedSize::SIZE) bytes and may wrap around the end of the buffer.
+/// Fields are popped in the order they were pushed; popping a field with a different type than
+/// it was pushed with reinterprets its bytes.
+///
+/// By default, pushing a field which does not fit fails. A ring created with
+/// [overwriting](ByteRing::overwriting) instead drops the oldest bytes to make room, which keeps
+/// fields intact as long as they all have the same size and that size divides `N`.
+///
+/// *Wrapped data is occasionally moved to the start of the buffer so that every field can be
+/// encoded and decoded in place, which costs `O(N)` when it happens.*
+pub struct ByteRing<const N: usize> {
+    buffer: StaticByteBuf<N>,
+    head: usize,
+    len: usize,
+    overwrite: bool
+}
+
+impl<const N: usize> ByteRing<N> {
+    /// Creates a new empty [ByteRing] which rejects fields that do not fit.
+    pub fn new() -> ByteRing<N> {
+        Self {
+            buffer: StaticByteBuf::new([0; N]),
+            head: 0,
+            len: 0,
+            overwrite: false
+        }
+    }
+
+    /// Creates a new empty [ByteRing] which drops its oldest bytes to make room for new fields.
+    pub fn overwriting() -> ByteRing<N> {
+        Self {
+            overwrite: true,
+            ..Self::new()
+        }
+    }
+
+    /// Returns the number of bytes waiting to be popped.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the maximum number of bytes this [ByteRing] can hold.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns true if there is no byte to pop.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if no more bytes can be pushed without overwriting.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Removes all bytes from this [ByteRing].
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Pushes a little-endian field.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the field does not fit in the free space, or in the whole
+    /// buffer when overwriting.
+    pub fn push_le<V: WriteBytes>(&mut self, value: V) -> Result<(), BoundsError> {
+        let tail = self.reserve(V::SIZE)?;
+        self.buffer.set_le(tail, value);
+        Ok(())
+    }
+
+    /// Pushes a big-endian field.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the field does not fit in the free space, or in the whole
+    /// buffer when overwriting.
+    pub fn push_be<V: WriteBytes>(&mut self, value: V) -> Result<(), BoundsError> {
+        let tail = self.reserve(V::SIZE)?;
+        self.buffer.set_be(tail, value);
+        Ok(())
+    }
+
+    /// Pops a little-endian field, or returns [None] if fewer than
+    /// [SIZE](crate::FixedSize::SIZE) bytes are waiting.
+    pub fn pop_le<V: ReadBytes>(&mut self) -> Option<V> {
+        let head = self.release(V::SIZE)?;
+        Some(self.buffer.get_le(head))
+    }
+
+    /// Pops a big-endian field, or returns [None] if fewer than
+    /// [SIZE](crate::FixedSize::SIZE) bytes are waiting.
+    pub fn pop_be<V: ReadBytes>(&mut self) -> Option<V> {
+        let head = self.release(V::SIZE)?;
+        Some(self.buffer.get_be(head))
+    }
+
+    fn wrap(pos: usize) -> usize {
+        if pos >= N {
+            pos - N
+        } else {
+            pos
+        }
+    }
+
+    /// Moves the bytes waiting to be popped to the start of the buffer.
+    fn rewind(&mut self) {
+        self.buffer.as_mut().rotate_left(self.head);
+        self.head = 0;
+    }
+
+    /// Makes room for `size` contiguous bytes and returns their offset.
+    fn reserve(&mut self, size: usize) -> Result<usize, BoundsError> {
+        if size > N - self.len {
+            if !self.overwrite || size > N {
+                return Err(BoundsError::new(self.len, size, N));
+            }
+            let dropped = size - (N - self.len);
+            self.head = Self::wrap(self.head + dropped);
+            self.len -= dropped;
+        }
+        if Self::wrap(self.head + self.len) + size > N {
+            self.rewind();
+        }
+        let tail = Self::wrap(self.head + self.len);
+        self.len += size;
+        Ok(tail)
+    }
+
+    /// Releases the `size` oldest bytes, made contiguous, and returns their offset.
+    fn release(&mut self, size: usize) -> Option<usize> {
+        if size > self.len {
+            return None;
+        }
+        if self.head + size > N {
+            self.rewind();
+        }
+        let head = self.head;
+        self.head = Self::wrap(self.head + size);
+        self.len -= size;
+        Some(head)
+    }
+}
+
+impl<const N: usize> Default for ByteRing<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundsError, ByteRing};
+
+    #[test]
+    fn wrap_around() {
+        let mut ring = ByteRing::<10>::new();
+        ring.push_le(0x01020304u32).unwrap();
+        ring.push_be(0x0506u16).unwrap();
+        assert_eq!(ring.pop_le::<u32>(), Some(0x01020304));
+        ring.push_le(0x0708090A0B0C0D0Eu64).unwrap();
+        assert!(ring.is_full());
+        assert_eq!(ring.pop_be::<u16>(), Some(0x0506));
+        assert_eq!(ring.pop_le::<u64>(), Some(0x0708090A0B0C0D0E));
+        assert!(ring.is_empty());
+        for i in 0..20u16 {
+            ring.push_be(i).unwrap();
+            ring.push_le(i as u8).unwrap();
+            assert_eq!(ring.pop_be::<u16>(), Some(i));
+            assert_eq!(ring.pop_le::<u8>(), Some(i as u8));
+        }
+        assert_eq!(ring.pop_le::<u8>(), None);
+    }
+
+    #[test]
+    fn full() {
+        let mut ring = ByteRing::<6>::default();
+        ring.push_le(1u32).unwrap();
+        assert_eq!(ring.push_le(2u32), Err(BoundsError::new(4, 4, 6)));
+        assert_eq!((ring.len(), ring.capacity()), (4, 6));
+        assert_eq!(ring.pop_le::<u64>(), None);
+        assert_eq!(ring.pop_le::<u32>(), Some(1));
+        ring.clear();
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn overwriting() {
+        let mut ring = ByteRing::<8>::overwriting();
+        for i in 0..5u16 {
+            ring.push_le(i).unwrap();
+        }
+        assert!(ring.is_full());
+        assert_eq!(ring.pop_le::<u16>(), Some(1));
+        ring.push_le(5u16).unwrap();
+        ring.push_le(6u16).unwrap();
+        assert_eq!(ring.pop_le::<u16>(), Some(3));
+        assert_eq!(ring.pop_le::<u16>(), Some(4));
+        assert_eq!(ring.push_le(0u128).unwrap_err(), BoundsError::new(4, 16, 8));
+        assert_eq!(ring.pop_le::<u32>(), Some(0x00060005));
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{ByteSize, FixedSize, ReadBytes, WriteBytes};
+
+macro_rules! impl_canonical {
+    ($($(#[$doc: meta])* $name: ident: $t: ty => $bits: ty, $nan: literal;)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Clone, Copy, PartialEq, Default)]
+            pub struct $name<const FOLD_ZERO: bool = true>(pub $t);
+
+            impl<const FOLD_ZERO: bool> $name<FOLD_ZERO> {
+                /// The bit pattern every NaN is written as.
+                pub const NAN_BITS: $bits = $nan;
+
+                /// Returns the value which is actually written.
+                pub fn canonical(&self) -> $t {
+                    if self.0.is_nan() {
+                        <$t>::from_bits(Self::NAN_BITS)
+                    } else if FOLD_ZERO && self.0 == 0.0 {
+                        0.0
+                    } else {
+                        self.0
+                    }
+                }
+            }
+
+            #[cfg(feature = "arbitrary")]
+            impl<'a, const FOLD_ZERO: bool> arbitrary::Arbitrary<'a> for $name<FOLD_ZERO> {
+                fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                    <$t>::arbitrary(u).map(Self)
+                }
+
+                fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                    <$t>::size_hint(depth)
+                }
+            }
+
+            impl<const FOLD_ZERO: bool> FixedSize for $name<FOLD_ZERO> {
+                const SIZE: usize = <$t>::SIZE;
+            }
+
+            impl<const FOLD_ZERO: bool> ByteSize for $name<FOLD_ZERO> {
+                fn byte_size(&self) -> usize {
+                    <$t>::SIZE
+                }
+            }
+
+            impl<const FOLD_ZERO: bool> WriteBytes for $name<FOLD_ZERO> {
+                fn write_bytes_le(&self, bytes: &mut [u8]) {
+                    self.canonical().write_bytes_le(bytes)
+                }
+
+                fn write_bytes_be(&self, bytes: &mut [u8]) {
+                    self.canonical().write_bytes_be(bytes)
+                }
+            }
+
+            /// Reads the value as is, without normalizing it.
+            impl<const FOLD_ZERO: bool> ReadBytes for $name<FOLD_ZERO> {
+                fn read_bytes_le(bytes: &[u8]) -> Self {
+                    Self(<$t>::read_bytes_le(bytes))
+                }
+
+                fn read_bytes_be(bytes: &[u8]) -> Self {
+                    Self(<$t>::read_bytes_be(bytes))
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl<const FOLD_ZERO: bool> crate::WriteTo for $name<FOLD_ZERO> {
+                fn write_to_le<W: std::io::Write>(&self, dst: W) -> std::io::Result<()> {
+                    self.canonical().write_to_le(dst)
+                }
+
+                fn write_to_be<W: std::io::Write>(&self, dst: W) -> std::io::Result<()> {
+                    self.canonical().write_to_be(dst)
+                }
+            }
+
+            /// Reads the value as is, without normalizing it.
+            #[cfg(feature = "std")]
+            impl<const FOLD_ZERO: bool> crate::ReadFrom for $name<FOLD_ZERO> {
+                fn read_from_le<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+                    <$t>::read_from_le(src).map(Self)
+                }
+
+                fn read_from_be<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+                    <$t>::read_from_be(src).map(Self)
+                }
+            }
+        )*
+    };
+}
+
+impl_canonical! {
+    /// An [f32] which is always written with the same bytes for equal logical values, so that
+    /// serialized buffers can be hashed or compared byte for byte.
+    ///
+    /// Every NaN, whatever its sign and payload, is written as the quiet NaN `0x7FC00000`. When
+    /// `FOLD_ZERO` is true, which is the default, `-0.0` is written as `+0.0` (`0x00000000`).
+    /// Any other value is written unchanged. Reading never normalizes the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytesutil::{CanonicalF32, WriteBytes};
+    ///
+    /// let mut a = [0; 4];
+    /// let mut b = [0; 4];
+    /// CanonicalF32::<true>(f32::from_bits(0xFFC00001)).write_bytes_le(&mut a);
+    /// CanonicalF32::<true>(f32::NAN).write_bytes_le(&mut b);
+    /// assert_eq!(a, b);
+    /// ```
+    CanonicalF32: f32 => u32, 0x7FC00000;
+
+    /// An [f64] which is always written with the same bytes for equal logical values, so that
+    /// serialized buffers can be hashed or compared byte for byte.
+    ///
+    /// Every NaN, whatever its sign and payload, is written as the quiet NaN
+    /// `0x7FF8000000000000`. When `FOLD_ZERO` is true, which is the default, `-0.0` is written as
+    /// `+0.0` (`0x0000000000000000`). Any other value is written unchanged. Reading never
+    /// normalizes the value.
+    CanonicalF64: f64 => u64, 0x7FF8000000000000;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CanonicalF32, CanonicalF64, ReadBytes, WriteBytes};
+
+    fn le32<const FOLD_ZERO: bool>(value: f32) -> [u8; 4] {
+        let mut bytes = [0; 4];
+        CanonicalF32::<FOLD_ZERO>(value).write_bytes_le(&mut bytes);
+        bytes
+    }
+
+    fn be64<const FOLD_ZERO: bool>(value: f64) -> [u8; 8] {
+        let mut bytes = [0; 8];
+        CanonicalF64::<FOLD_ZERO>(value).write_bytes_be(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn nan() {
+        for bits in [0x7FC00000, 0x7FC00001, 0xFFC00000, 0x7F800001, 0xFFFFFFFF] {
+            assert_eq!(le32::<true>(f32::from_bits(bits)), [0x00, 0x00, 0xC0, 0x7F]);
+            assert_eq!(le32::<false>(f32::from_bits(bits)), [0x00, 0x00, 0xC0, 0x7F]);
+        }
+        for bits in [0x7FF8000000000000, 0x7FF8000000000001, 0xFFF8000000000000, 0x7FF0000000000001] {
+            assert_eq!(be64::<true>(f64::from_bits(bits)), [0x7F, 0xF8, 0, 0, 0, 0, 0, 0]);
+        }
+        let read = CanonicalF32::<true>::read_bytes_le(&0xFFC00001u32.to_le_bytes());
+        assert_eq!(read.0.to_bits(), 0xFFC00001);
+    }
+
+    #[test]
+    fn zero() {
+        assert_eq!(le32::<true>(-0.0), le32::<true>(0.0));
+        assert_eq!(be64::<true>(-0.0), [0; 8]);
+        assert_eq!(le32::<false>(-0.0), [0, 0, 0, 0x80]);
+        assert_eq!(be64::<false>(-0.0), [0x80, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn passthrough() {
+        for value in [1.5f32, -2.25, f32::INFINITY, f32::NEG_INFINITY, f32::MIN_POSITIVE / 2.0, f32::MIN] {
+            assert_eq!(le32::<true>(value), value.to_le_bytes());
+        }
+        for value in [1.5f64, -1e300, f64::NEG_INFINITY, -f64::MIN_POSITIVE / 4.0] {
+            assert_eq!(be64::<true>(value), value.to_be_bytes());
+        }
+        #[cfg(feature = "std")]
+        crate::testutil::assert_roundtrip_io(CanonicalF64::<true>(-3.75));
+    }
+}
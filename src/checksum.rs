@@ -0,0 +1,215 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::hash::Hasher;
+use std::io::{Read, Result, Write};
+
+/// A [Read] wrapper which feeds every byte read into a [Hasher], in order to validate the data
+/// against a stored checksum.
+///
+/// Any [Hasher] which processes bytes as a stream can be used, such as a CRC implementation.
+/// Bytes read directly from the wrapped stream through [get_mut](ChecksumReader::get_mut), for
+/// example a checksum trailer, are not hashed.
+pub struct ChecksumReader<R, H> {
+    inner: R,
+    hasher: H
+}
+
+impl<R, H: Hasher> ChecksumReader<R, H> {
+    /// Creates a new [ChecksumReader].
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the [Read] to wrap.
+    /// * `hasher`: the [Hasher] to update with every byte read.
+    pub fn new(inner: R, hasher: H) -> ChecksumReader<R, H> {
+        Self {
+            inner,
+            hasher
+        }
+    }
+
+    /// Returns the checksum of all bytes read so far.
+    pub fn checksum(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    /// Returns a reference to the [Hasher].
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Returns a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped stream; bytes read through it are not hashed.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Extracts the wrapped stream and the [Hasher].
+    pub fn into_inner(self) -> (R, H) {
+        (self.inner, self.hasher)
+    }
+}
+
+impl<R: Read, H: Hasher> Read for ChecksumReader<R, H> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.hasher.write(&buf[..len]);
+        Ok(len)
+    }
+}
+
+/// A [Write] wrapper which feeds every byte written into a [Hasher], in order to append a
+/// checksum trailer.
+///
+/// This is the counterpart of [ChecksumReader]. Bytes written directly to the wrapped stream
+/// through [get_mut](ChecksumWriter::get_mut) are not hashed.
+pub struct ChecksumWriter<W, H> {
+    inner: W,
+    hasher: H
+}
+
+impl<W, H: Hasher> ChecksumWriter<W, H> {
+    /// Creates a new [ChecksumWriter].
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the [Write] to wrap.
+    /// * `hasher`: the [Hasher] to update with every byte written.
+    pub fn new(inner: W, hasher: H) -> ChecksumWriter<W, H> {
+        Self {
+            inner,
+            hasher
+        }
+    }
+
+    /// Returns the checksum of all bytes written so far.
+    pub fn checksum(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    /// Returns a reference to the [Hasher].
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Returns a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped stream; bytes written through it are not
+    /// hashed.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Extracts the wrapped stream and the [Hasher].
+    pub fn into_inner(self) -> (W, H) {
+        (self.inner, self.hasher)
+    }
+}
+
+impl<W: Write, H: Hasher> Write for ChecksumWriter<W, H> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.hasher.write(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hasher;
+    use std::io::Cursor;
+
+    use crate::testutil::{ShortReader, ShortWriter};
+    use crate::{ChecksumReader, ChecksumWriter, ReadExt, WriteExt};
+
+    /// The 32 bits FNV-1a hash, which processes bytes one at a time.
+    struct Fnv(u32);
+
+    impl Default for Fnv {
+        fn default() -> Self {
+            Fnv(0x811C9DC5)
+        }
+    }
+
+    impl Hasher for Fnv {
+        fn finish(&self) -> u64 {
+            self.0 as u64
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 = (self.0 ^ byte as u32).wrapping_mul(0x01000193);
+            }
+        }
+    }
+
+    #[test]
+    fn trailer() {
+        let mut writer = ChecksumWriter::new(ShortWriter::new(Vec::new()), Fnv::default());
+        writer.write_le(0x01020304u32).unwrap();
+        writer.write_be(-42i64).unwrap();
+        let checksum = writer.checksum() as u32;
+        writer.get_mut().write_le(checksum).unwrap();
+        let (out, _) = writer.into_inner();
+        let out = out.into_inner();
+        let mut expected = Fnv::default();
+        expected.write(&out[..12]);
+        assert_eq!(checksum as u64, expected.finish());
+
+        let mut reader = ChecksumReader::new(ShortReader::new(Cursor::new(&out)), Fnv::default());
+        assert_eq!(reader.read_le::<u32>().unwrap(), 0x01020304);
+        assert_eq!(reader.read_be::<i64>().unwrap(), -42);
+        let computed = reader.checksum();
+        assert_eq!(reader.get_mut().read_le::<u32>().unwrap() as u64, computed);
+        assert_eq!(reader.checksum(), computed);
+    }
+
+    #[test]
+    fn corrupted() {
+        let mut data = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        let mut writer = ChecksumWriter::new(Vec::new(), Fnv::default());
+        writer.write_le(u64::from_le_bytes(data.clone().try_into().unwrap())).unwrap();
+        let checksum = writer.checksum();
+        data[3] ^= 0x10;
+        let mut reader = ChecksumReader::new(Cursor::new(data), Fnv::default());
+        reader.read_le::<u64>().unwrap();
+        assert_ne!(reader.checksum(), checksum);
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::io::{Error, ErrorKind, Result};
+
+use crate::{ByteSize, ReadFrom, WriteTo};
+
+/// Encodes a value into a new [Vec], in little endian order.
+///
+/// *The [Vec] is allocated once with the capacity reported by [ByteSize].*
+///
+/// # Panics
+///
+/// Panics if the [WriteTo] implementation of `T` fails, which cannot be caused by the [Vec]
+/// itself.
+pub fn to_vec_le<T: WriteTo + ByteSize + ?Sized>(value: &T) -> Vec<u8> {
+    let mut out = Vec::with_capacity(value.byte_size());
+    value.write_to_le(&mut out).expect("failed to encode value");
+    out
+}
+
+/// Encodes a value into a new [Vec], in big endian order.
+///
+/// *The [Vec] is allocated once with the capacity reported by [ByteSize].*
+///
+/// # Panics
+///
+/// Panics if the [WriteTo] implementation of `T` fails, which cannot be caused by the [Vec]
+/// itself.
+pub fn to_vec_be<T: WriteTo + ByteSize + ?Sized>(value: &T) -> Vec<u8> {
+    let mut out = Vec::with_capacity(value.byte_size());
+    value.write_to_be(&mut out).expect("failed to encode value");
+    out
+}
+
+/// Decodes a value from the start of a slice, in little endian order.
+///
+/// returns: the value and the number of bytes consumed.
+///
+/// # Errors
+///
+/// Returns an [Error](std::io::Error) if the value could not be decoded.
+pub fn from_slice_le_partial<T: ReadFrom>(bytes: &[u8]) -> Result<(T, usize)> {
+    let mut src = bytes;
+    let value = T::read_from_le(&mut src).map_err(crate::error::attach::<T>)?;
+    Ok((value, bytes.len() - src.len()))
+}
+
+/// Decodes a value from the start of a slice, in big endian order.
+///
+/// returns: the value and the number of bytes consumed.
+///
+/// # Errors
+///
+/// Returns an [Error](std::io::Error) if the value could not be decoded.
+pub fn from_slice_be_partial<T: ReadFrom>(bytes: &[u8]) -> Result<(T, usize)> {
+    let mut src = bytes;
+    let value = T::read_from_be(&mut src).map_err(crate::error::attach::<T>)?;
+    Ok((value, bytes.len() - src.len()))
+}
+
+/// Decodes a value spanning a whole slice, in little endian order.
+///
+/// # Errors
+///
+/// Returns an [Error](std::io::Error) if the value could not be decoded. If bytes remain after
+/// the value, an error of kind [InvalidData](ErrorKind::InvalidData) is returned.
+pub fn from_slice_le<T: ReadFrom>(bytes: &[u8]) -> Result<T> {
+    from_slice_le_partial(bytes).and_then(|(value, len)| check_trailing(value, len, bytes.len()))
+}
+
+/// Decodes a value spanning a whole slice, in big endian order.
+///
+/// # Errors
+///
+/// Returns an [Error](std::io::Error) if the value could not be decoded. If bytes remain after
+/// the value, an error of kind [InvalidData](ErrorKind::InvalidData) is returned.
+pub fn from_slice_be<T: ReadFrom>(bytes: &[u8]) -> Result<T> {
+    from_slice_be_partial(bytes).and_then(|(value, len)| check_trailing(value, len, bytes.len()))
+}
+
+fn check_trailing<T>(value: T, consumed: usize, len: usize) -> Result<T> {
+    if consumed < len {
+        return Err(Error::new(ErrorKind::InvalidData, format!("{} trailing bytes after value", len - consumed)));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use crate::{from_slice_be, from_slice_le, from_slice_le_partial, to_vec_be, to_vec_le, ReadExt, VarU32, WriteExt};
+
+    #[test]
+    fn roundtrip() {
+        let value = (0x0102u16, true, VarU32(300));
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_le(value).unwrap();
+        assert_eq!(to_vec_le(&value), *cursor.get_ref());
+        assert_eq!(from_slice_le::<(u16, bool, VarU32)>(cursor.get_ref()).unwrap(), value);
+        cursor.set_position(0);
+        assert_eq!(cursor.read_le::<(u16, bool, VarU32)>().unwrap(), value);
+        assert_eq!(to_vec_be(&0x01020304u32), [1, 2, 3, 4]);
+        assert_eq!(from_slice_be::<u32>(&[1, 2, 3, 4]).unwrap(), 0x01020304);
+    }
+
+    #[test]
+    fn trailing() {
+        let err = from_slice_le::<u16>(&[1, 2, 3]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "1 trailing bytes after value");
+        assert_eq!(from_slice_le_partial::<u16>(&[1, 2, 3]).unwrap(), (0x0201, 2));
+        assert_eq!(from_slice_le_partial::<VarU32>(&[0xAC, 0x02, 0xFF]).unwrap(), (VarU32(300), 2));
+        assert_eq!(from_slice_be::<u32>(&[1, 2]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn byte_size() {
+        use std::rc::Rc;
+
+        use crate::{ByteSize, StaticByteBuf};
+
+        let value = (0x0102u16, true, VarU32(300), Box::new(VarU32(1)), ());
+        assert_eq!(value.byte_size(), 6);
+        assert_eq!(to_vec_le(&value).len(), value.byte_size());
+        assert_eq!(to_vec_be(&value).capacity(), 6);
+        assert_eq!(VarU32(u32::MAX).byte_size(), 5);
+        assert_eq!(Rc::new(StaticByteBuf::new([0; 3])).byte_size(), 3);
+        assert_eq!((&&1.5f64).byte_size(), 8);
+    }
+}
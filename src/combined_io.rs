@@ -26,19 +26,54 @@
 // NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::fmt::Arguments;
-use std::io::{IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
+use std::cell::RefCell;
+use std::fmt::{Arguments, Debug, Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::rc::Rc;
 
 /// A tool which combines a [Read]+[Seek] and a [Write]+[Seek] into a [Read]+[Write]+[Seek].
 ///
 /// * All calls to the [Read] interface are forwarded only to the [Read] end.
 /// * All calls to the [Write] interface are forwarded only to the [Write] end.
-/// * All calls to the [Seek] interface are forwarded to both the [Read] and the [Write] ends.
+/// * All calls to the [Seek] interface are forwarded to both the [Write] and the [Read] ends, in
+///   that order, except [stream_position](Seek::stream_position) which only queries the [Read]
+///   end.
 ///
 /// All interfaces are optional.
+///
+/// # Seek failures
+///
+/// A failed [seek](Seek::seek) or [rewind](Seek::rewind) leaves both ends where they were
+/// whenever possible:
+///
+/// * if the position of the [Write] end cannot be queried or if seeking it fails, the [Read]
+///   end is not touched;
+/// * if seeking the [Read] end fails, the [Write] end is moved back to its previous position.
+///
+/// This is best-effort: if moving the [Write] end back also fails, the ends are left at
+/// different positions and a [DesyncError] is returned so that the caller knows the [Combine]
+/// can no longer be used safely. Note that a failed seek may still have flushed a buffered
+/// [Write] end.
+///
+/// When both ends are the same object (see [from_shared](Combine::from_shared)), calls to the
+/// [Seek] interface are forwarded only once.
+///
+/// # Independent positions
+///
+/// [seek_to](Combine::seek_to) and [position](Combine::position) name the end they act on with
+/// a [Whom], so that the [Read] and [Write] ends can move independently while their positions
+/// stay measured from the same origin. Code relying on the [Seek] interface can migrate as
+/// follows:
+///
+/// * `seek(pos)` is `seek_to(Whom::Both, pos)`;
+/// * `stream_position()` is `position(Whom::Reader)`, or `position(Whom::Both)` to also check
+///   that the ends have not drifted apart.
 pub struct Combine<R, W> {
     reader: R,
-    writer: W
+    writer: W,
+    shared: bool
 }
 
 impl<R, W> Combine<R, W> {
@@ -51,9 +86,172 @@ impl<R, W> Combine<R, W> {
     pub fn new(read_end: R, write_end: W) -> Combine<R, W> {
         Self {
             reader: read_end,
-            writer: write_end
+            writer: write_end,
+            shared: false
         }
     }
+
+    /// Wraps the [Write] end of this [Combine] in an [AutoFlush] so that the writer is flushed
+    /// when the [Combine] is dropped.
+    ///
+    /// Errors which occur while flushing on drop are ignored; use [finish](Combine::finish) to
+    /// observe them.
+    pub fn auto_flush(self) -> Combine<R, AutoFlush<W>>
+    where
+        W: Write
+    {
+        Combine {
+            reader: self.reader,
+            writer: AutoFlush::new(self.writer),
+            shared: self.shared
+        }
+    }
+}
+
+impl<R: Read, W: Write> Combine<BufReader<R>, BufWriter<W>> {
+    /// Creates a new instance of a [Combine] tool with buffering on both ends.
+    ///
+    /// Seeking flushes the [BufWriter] before the [BufReader] discards its read-ahead, so that
+    /// the [Read] end never returns stale data when both ends refer to the same file.
+    /// [stream_position](Seek::stream_position) does not flush nor discard anything: it returns
+    /// the logical position of the [Read] end.
+    ///
+    /// # Arguments
+    ///
+    /// * `read_end`: the [Read] (optionally [Seek]) end.
+    /// * `write_end`: the [Write] (optionally [Seek]) end.
+    /// * `capacity`: the capacity of each buffer in bytes.
+    pub fn buffered(read_end: R, write_end: W, capacity: usize) -> Self {
+        Combine::new(
+            BufReader::with_capacity(capacity, read_end),
+            BufWriter::with_capacity(capacity, write_end)
+        )
+    }
+}
+
+impl Combine<File, File> {
+    /// Opens an existing file twice: once read-only as the [Read] end and once read-write,
+    /// without truncating it, as the [Write] end.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: the path of the file to open.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if either end could not be opened.
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Combine::open_with(path, OpenOptions::new().read(true), OpenOptions::new().read(true).write(true))
+    }
+
+    /// Creates a file, or truncates it if it exists, then opens it as both the [Read] end and the
+    /// [Write] end.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: the path of the file to create.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if either end could not be opened.
+    pub fn create<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Combine::open_with(
+            path,
+            OpenOptions::new().read(true),
+            OpenOptions::new().read(true).write(true).create(true).truncate(true)
+        )
+    }
+
+    /// Opens a file twice with custom options, then rewinds both ends.
+    ///
+    /// *The [Write] end is opened first, so that `write_opts` may create or truncate the file
+    /// before the [Read] end is opened.*
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: the path of the file to open.
+    /// * `read_opts`: the options used to open the [Read] end.
+    /// * `write_opts`: the options used to open the [Write] end.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if either end could not be opened or rewound.
+    pub fn open_with<P: AsRef<Path>>(path: P, read_opts: &OpenOptions, write_opts: &OpenOptions) -> std::io::Result<Self> {
+        let mut writer = write_opts.open(path.as_ref())?;
+        let mut reader = read_opts.open(path.as_ref())?;
+        writer.rewind()?;
+        reader.rewind()?;
+        Ok(Combine::new(reader, writer))
+    }
+}
+
+impl<T> Combine<Shared<T>, Shared<T>> {
+    /// Creates a new instance of a [Combine] tool where both ends are the same
+    /// [Read]+[Write]+[Seek] object, such as a [File](std::fs::File).
+    ///
+    /// # Arguments
+    ///
+    /// * `handle`: the object to use as both the [Read] and the [Write] end.
+    pub fn from_shared(handle: T) -> Self {
+        let handle = Shared::new(handle);
+        Self {
+            reader: handle.clone(),
+            writer: handle,
+            shared: true
+        }
+    }
+
+    /// Returns the object shared by both ends of this [Combine].
+    ///
+    /// This is only valid for a [Combine] created with [from_shared](Combine::from_shared).
+    ///
+    /// # Errors
+    ///
+    /// Returns this [Combine] unchanged if it was not created with
+    /// [from_shared](Combine::from_shared) or if a [Shared] handle to the object is still alive
+    /// elsewhere.
+    pub fn into_shared(self) -> Result<T, Self> {
+        let unique = Rc::ptr_eq(&self.reader.0, &self.writer.0) && Rc::strong_count(&self.reader.0) == 2;
+        if !self.shared || !unique {
+            return Err(self);
+        }
+        drop(self.writer);
+        match Rc::try_unwrap(self.reader.0) {
+            Ok(cell) => Ok(cell.into_inner()),
+            Err(_) => unreachable!("the handle is only referenced by the combine")
+        }
+    }
+}
+
+impl<R, W> Combine<R, W> {
+    /// Returns references to the [Read] and [Write] ends.
+    pub fn get_ref(&self) -> (&R, &W) {
+        (&self.reader, &self.writer)
+    }
+
+    /// Returns mutable references to the [Read] and [Write] ends.
+    ///
+    /// *Seeking one end through these references leaves the ends at different positions.*
+    pub fn get_mut(&mut self) -> (&mut R, &mut W) {
+        (&mut self.reader, &mut self.writer)
+    }
+
+    /// Returns both ends of this [Combine] without flushing the [Write] end.
+    pub fn into_inner(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+}
+
+impl<R, W: Write> Combine<R, W> {
+    /// Flushes the [Write] end and returns both ends of this [Combine].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the [Write] end could not be flushed.
+    pub fn finish(mut self) -> std::io::Result<(R, W)> {
+        self.writer.flush()?;
+        Ok((self.reader, self.writer))
+    }
 }
 
 impl<R: Read, W> Read for Combine<R, W> {
@@ -100,18 +298,598 @@ impl<R, W: Write> Write for Combine<R, W> {
     }
 }
 
+impl<R: Seek, W: Seek> Combine<R, W> {
+    /// Seeks both ends of this [Combine] and returns the new position of each end.
+    ///
+    /// Unlike [seek](Seek::seek), which only returns the position of the [Read] end, this allows
+    /// the caller to detect ends which ended up at different positions, for example after seeking
+    /// relative to the end of streams of different lengths.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos`: the position to seek to.
+    ///
+    /// returns: the positions of the [Read] and [Write] ends, in that order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if one of the ends could not be seeked, with the same
+    /// recovery as [seek](Seek::seek) (see [Seek failures](Combine#seek-failures)).
+    pub fn seek_both(&mut self, pos: SeekFrom) -> std::io::Result<(u64, u64)> {
+        self.seek_ends(|end| end.seek(pos))
+    }
+
+    /// Seeks one or both ends of this [Combine].
+    ///
+    /// # Arguments
+    ///
+    /// * `whom`: the ends to seek.
+    /// * `pos`: the position to seek to.
+    ///
+    /// returns: the new position of the [Read] end when seeking [Both](Whom::Both), otherwise the
+    /// new position of the end which was seeked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if one of the ends could not be seeked, with the same
+    /// recovery as [seek](Seek::seek) when seeking [Both](Whom::Both), or an error of kind
+    /// [Unsupported](std::io::ErrorKind::Unsupported) when seeking a single end of a [Combine]
+    /// created with [from_shared](Combine::from_shared), whose ends cannot move independently.
+    pub fn seek_to(&mut self, whom: Whom, pos: SeekFrom) -> std::io::Result<u64> {
+        if self.shared && whom != Whom::Both {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "the ends of a shared combine cannot be seeked independently"
+            ));
+        }
+        match whom {
+            Whom::Reader => self.reader.seek(pos),
+            Whom::Writer => self.writer.seek(pos),
+            Whom::Both => self.seek(pos)
+        }
+    }
+
+    /// Returns the position of one or both ends of this [Combine].
+    ///
+    /// # Arguments
+    ///
+    /// * `whom`: the ends to query.
+    ///
+    /// returns: u64
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the position of one of the ends could not be
+    /// queried, or an error of kind [InvalidData](std::io::ErrorKind::InvalidData) when querying
+    /// [Both](Whom::Both) ends and they are at different positions.
+    pub fn position(&mut self, whom: Whom) -> std::io::Result<u64> {
+        match whom {
+            Whom::Reader => self.reader.stream_position(),
+            Whom::Writer => self.writer.stream_position(),
+            Whom::Both => match self.seek_ends(|end| end.stream_position())? {
+                (reader, writer) if reader == writer => Ok(reader),
+                (reader, writer) => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("combine ends are at different positions: read {}, write {}", reader, writer)
+                ))
+            }
+        }
+    }
+
+    fn seek_ends<T: Copy>(&mut self, mut op: impl FnMut(&mut dyn Seek) -> std::io::Result<T>) -> std::io::Result<(T, T)> {
+        if self.shared {
+            return op(&mut self.reader).map(|value| (value, value));
+        }
+        let previous = self.writer.stream_position()?;
+        let writer = op(&mut self.writer)?;
+        let reader = op(&mut self.reader).map_err(|seek| match self.writer.seek(SeekFrom::Start(previous)) {
+            Ok(_) => seek,
+            Err(restore) => DesyncError { seek, restore }.into()
+        })?;
+        Ok((reader, writer))
+    }
+}
+
 impl<R: Seek, W: Seek> Seek for Combine<R, W> {
     fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
-        self.reader.seek(pos)?;
-        self.writer.seek(pos)
+        self.seek_ends(|end| end.seek(pos)).map(|(reader, _)| reader)
     }
 
     fn rewind(&mut self) -> std::io::Result<()> {
-        self.reader.rewind()?;
-        self.writer.rewind()
+        self.seek_ends(|end| end.rewind()).map(|_| ())
     }
 
     fn stream_position(&mut self) -> std::io::Result<u64> {
         self.reader.stream_position()
     }
 }
+
+/// The ends of a [Combine] targeted by [seek_to](Combine::seek_to) and
+/// [position](Combine::position).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Whom {
+    /// Only the [Read] end.
+    Reader,
+
+    /// Only the [Write] end.
+    Writer,
+
+    /// Both ends, as with the [Seek] interface.
+    Both
+}
+
+/// Details about a [Combine] whose ends no longer point to the same position.
+///
+/// A [DesyncError] is carried as the payload of an [Error](std::io::Error) of kind
+/// [Other](std::io::ErrorKind::Other) returned by [Combine] when seeking the [Read] end failed and the
+/// [Write] end could not be moved back. Use [downcast](DesyncError::downcast) to retrieve it.
+#[derive(Debug)]
+pub struct DesyncError {
+    seek: std::io::Error,
+    restore: std::io::Error
+}
+
+impl DesyncError {
+    /// Returns the error which occurred while seeking the [Read] end.
+    pub fn seek_error(&self) -> &std::io::Error {
+        &self.seek
+    }
+
+    /// Returns the error which occurred while moving the [Write] end back.
+    pub fn restore_error(&self) -> &std::io::Error {
+        &self.restore
+    }
+
+    /// Extracts the [DesyncError] carried by the given [Error](std::io::Error), if any.
+    pub fn downcast(error: &std::io::Error) -> Option<&DesyncError> {
+        error.get_ref()?.downcast_ref()
+    }
+}
+
+impl Display for DesyncError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "combine ends are inconsistent: seeking the read end failed ({}) and restoring the write end failed ({})",
+            self.seek, self.restore
+        )
+    }
+}
+
+impl std::error::Error for DesyncError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.seek)
+    }
+}
+
+impl From<DesyncError> for std::io::Error {
+    fn from(value: DesyncError) -> Self {
+        std::io::Error::other(value)
+    }
+}
+
+/// A reference counted handle to an IO object which is used by several owners, such as both
+/// ends of a [Combine].
+///
+/// All calls to the [Read], [Write] and [Seek] interfaces are forwarded to the shared object.
+pub struct Shared<T>(Rc<RefCell<T>>);
+
+impl<T> Shared<T> {
+    /// Creates a new [Shared] handle to the given object.
+    pub fn new(inner: T) -> Shared<T> {
+        Self(Rc::new(RefCell::new(inner)))
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Read> Read for Shared<T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().read(buf)
+    }
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+impl<T: Seek> Seek for Shared<T> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.0.borrow_mut().seek(pos)
+    }
+}
+
+/// A [Write] wrapper which flushes the wrapped [Write] when dropped.
+///
+/// Because [Drop] cannot report errors, flush errors on drop are either ignored or passed to an
+/// error handler. Use [into_inner](AutoFlush::into_inner) to flush and observe errors explicitly.
+pub struct AutoFlush<W: Write> {
+    inner: Option<W>,
+    handler: Option<fn(std::io::Error)>
+}
+
+impl<W: Write> AutoFlush<W> {
+    /// Creates a new [AutoFlush] which ignores flush errors on drop.
+    pub fn new(inner: W) -> AutoFlush<W> {
+        Self {
+            inner: Some(inner),
+            handler: None
+        }
+    }
+
+    /// Creates a new [AutoFlush] which passes flush errors on drop to the given `handler`.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the [Write] to wrap.
+    /// * `handler`: the function to call with the error if flushing on drop fails.
+    pub fn with_handler(inner: W, handler: fn(std::io::Error)) -> AutoFlush<W> {
+        Self {
+            inner: Some(inner),
+            handler: Some(handler)
+        }
+    }
+
+    /// Returns a reference to the wrapped [Write].
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().unwrap()
+    }
+
+    /// Returns a mutable reference to the wrapped [Write].
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.as_mut().unwrap()
+    }
+
+    /// Flushes and returns the wrapped [Write].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [AutoFlushError] if the wrapped [Write] could not be flushed. It holds this
+    /// [AutoFlush] back, so the flush can be retried.
+    pub fn into_inner(mut self) -> Result<W, AutoFlushError<W>> {
+        match self.writer().flush() {
+            Ok(()) => Ok(self.inner.take().unwrap()),
+            Err(error) => Err(AutoFlushError {
+                writer: self,
+                error
+            })
+        }
+    }
+
+    fn writer(&mut self) -> &mut W {
+        // The inner writer is only ever taken by into_inner which consumes self.
+        self.inner.as_mut().unwrap()
+    }
+}
+
+impl<W: Write> Write for AutoFlush<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer().write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> std::io::Result<usize> {
+        self.writer().write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer().flush()
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.writer().write_all(buf)
+    }
+}
+
+impl<W: Write + Seek> Seek for AutoFlush<W> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.writer().seek(pos)
+    }
+}
+
+impl<W: Write> Drop for AutoFlush<W> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.as_mut() {
+            if let Err(e) = inner.flush() {
+                if let Some(handler) = self.handler {
+                    handler(e);
+                }
+            }
+        }
+    }
+}
+
+/// The error returned by [AutoFlush::into_inner] when the wrapped [Write] could not be flushed.
+///
+/// It carries the [AutoFlush] back so that the wrapped [Write] is not lost. Dropping it flushes
+/// again, as dropping the [AutoFlush] would.
+pub struct AutoFlushError<W: Write> {
+    writer: AutoFlush<W>,
+    error: std::io::Error
+}
+
+impl<W: Write> AutoFlushError<W> {
+    /// Returns the error which occurred while flushing.
+    pub fn error(&self) -> &std::io::Error {
+        &self.error
+    }
+
+    /// Returns the error which occurred while flushing, dropping the [AutoFlush].
+    pub fn into_error(self) -> std::io::Error {
+        self.error
+    }
+
+    /// Returns the [AutoFlush] whose wrapped [Write] could not be flushed.
+    pub fn into_inner(self) -> AutoFlush<W> {
+        self.writer
+    }
+}
+
+impl<W: Write> Debug for AutoFlushError<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AutoFlushError").field("error", &self.error).finish_non_exhaustive()
+    }
+}
+
+impl<W: Write> Display for AutoFlushError<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to flush the wrapped writer: {}", self.error)
+    }
+}
+
+impl<W: Write> std::error::Error for AutoFlushError<W> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<W: Write> From<AutoFlushError<W>> for std::io::Error {
+    fn from(value: AutoFlushError<W>) -> Self {
+        value.error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufWriter, Cursor, Seek, SeekFrom, Write};
+
+    use crate::{AutoFlush, Combine, WriteExt};
+
+    #[test]
+    fn flush_on_drop() {
+        let mut out = Vec::new();
+        {
+            let mut combine = Combine::new(Cursor::new([0u8; 0]), BufWriter::new(&mut out)).auto_flush();
+            combine.write_le(42u32).unwrap();
+        }
+        assert_eq!(out, [42, 0, 0, 0]);
+    }
+
+    #[test]
+    fn finish() {
+        let mut combine = Combine::new(Cursor::new([0u8; 0]), BufWriter::new(Vec::new()));
+        combine.write_be(42u16).unwrap();
+        let (_, writer) = combine.finish().unwrap();
+        assert_eq!(writer.buffer().len(), 0);
+        assert_eq!(writer.into_inner().unwrap(), [0, 42]);
+    }
+
+    #[test]
+    fn flush_error() {
+        use std::io::ErrorKind;
+
+        use crate::testutil::{ScriptedWriter, Step};
+
+        let failing = ScriptedWriter::new([Step::Error(ErrorKind::Other), Step::Error(ErrorKind::BrokenPipe)]);
+        let err = AutoFlush::new(failing).into_inner().err().unwrap();
+        assert_eq!(err.error().kind(), ErrorKind::Other);
+        // The writer is handed back, so the flush can be retried.
+        let err = err.into_inner().into_inner().err().unwrap();
+        assert_eq!(std::io::Error::from(err).kind(), ErrorKind::BrokenPipe);
+        let failing = ScriptedWriter::new([Step::Error(ErrorKind::Other)]);
+        let err = AutoFlush::new(failing).into_inner().err().unwrap();
+        assert!(err.into_inner().into_inner().ok().unwrap().is_done());
+        std::thread_local! {
+            static CALLED: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+        }
+        let failing = ScriptedWriter::new([Step::Error(ErrorKind::Other)]);
+        drop(AutoFlush::with_handler(failing, |_| CALLED.with(|v| v.set(true))));
+        assert!(CALLED.with(|v| v.get()));
+        let mut combine = Combine::new(Cursor::new([0u8; 0]), ScriptedWriter::new([Step::Short(1), Step::Data(&[0, 42])]));
+        combine.write_be(42u16).unwrap();
+        let (_, writer) = combine.finish().unwrap();
+        assert!(writer.is_done());
+    }
+
+    #[test]
+    fn shared() {
+        use std::io::{Seek, SeekFrom};
+
+        use crate::ReadExt;
+
+        let mut combine = Combine::from_shared(Cursor::new(Vec::new()));
+        combine.write_le(0x0102u16).unwrap();
+        combine.write_le(0x0304u16).unwrap();
+        assert_eq!(combine.seek(SeekFrom::Current(-2)).unwrap(), 2);
+        assert_eq!(combine.read_le::<u16>().unwrap(), 0x0304);
+        let cursor = combine.into_shared().ok().unwrap();
+        assert_eq!(cursor.into_inner(), [2, 1, 4, 3]);
+    }
+
+    #[test]
+    fn not_shared() {
+        use crate::Shared;
+
+        let a = Shared::new(Cursor::new(Vec::<u8>::new()));
+        let b = Shared::new(Cursor::new(Vec::<u8>::new()));
+        assert!(Combine::new(a.clone(), a).into_shared().is_err());
+        assert!(Combine::new(b.clone(), b.clone()).into_shared().is_err());
+        let combine = Combine::from_shared(Cursor::new(Vec::<u8>::new()));
+        let extra = combine.reader.clone();
+        let combine = combine.into_shared().err().unwrap();
+        drop(extra);
+        assert!(combine.into_shared().is_ok());
+    }
+
+    #[test]
+    fn buffered() {
+        use std::io::{Read, Seek, SeekFrom};
+
+        use crate::{ReadExt, WriteExt};
+
+        fn run<R: Read + Seek, W: Write + Seek>(combine: &mut Combine<R, W>) -> Vec<u32> {
+            let mut values = Vec::new();
+            combine.write_le(1u32).unwrap();
+            values.push(combine.read_le::<u32>().unwrap());
+            combine.seek(SeekFrom::Current(4)).unwrap();
+            combine.write_le(2u32).unwrap();
+            values.push(combine.read_le::<u32>().unwrap());
+            combine.seek(SeekFrom::Start(2)).unwrap();
+            combine.write_be(3u32).unwrap();
+            values.push(combine.read_be::<u32>().unwrap());
+            combine.seek(SeekFrom::End(-4)).unwrap();
+            values.push(combine.read_le::<u32>().unwrap());
+            combine.rewind().unwrap();
+            combine.write_le(4u16).unwrap();
+            values
+        }
+
+        let input: Vec<u8> = (0..32).collect();
+        let mut reference = Combine::new(Cursor::new(input.clone()), Cursor::new(vec![0; 32]));
+        let expected = run(&mut reference);
+        let mut buffered = Combine::buffered(Cursor::new(input), Cursor::new(vec![0; 32]), 8);
+        assert_eq!(run(&mut buffered), expected);
+        let (_, writer) = buffered.finish().unwrap();
+        let (_, reference) = reference.finish().unwrap();
+        assert_eq!(writer.into_inner().unwrap().into_inner(), reference.into_inner());
+    }
+
+    #[test]
+    fn seek_rollback() {
+        use std::io::{ErrorKind, Seek, SeekFrom};
+
+        use crate::testutil::{ScriptedReader, ScriptedWriter, Step};
+        use crate::DesyncError;
+
+        let mut combine = Combine::new(Cursor::new(vec![0; 16]), ScriptedWriter::new([Step::Error(ErrorKind::Other)]));
+        combine.reader.set_position(3);
+        assert!(combine.seek(SeekFrom::Start(8)).is_err());
+        assert_eq!(combine.reader.position(), 3);
+        // The write end reports its position but fails to seek.
+        let mut combine = Combine::new(Cursor::new(vec![0; 16]), ScriptedWriter::new([Step::Seek, Step::Error(ErrorKind::Other)]));
+        combine.reader.set_position(3);
+        assert!(combine.rewind().is_err());
+        assert_eq!(combine.reader.position(), 3);
+        assert!(combine.writer.is_done());
+        let reader = ScriptedReader::new([Step::Error(ErrorKind::Other), Step::Error(ErrorKind::Other)]);
+        let mut combine = Combine::new(reader, ScriptedWriter::new([]));
+        combine.writer.seek(SeekFrom::Start(5)).unwrap();
+        let err = combine.seek(SeekFrom::Start(8)).unwrap_err();
+        assert!(DesyncError::downcast(&err).is_none());
+        assert_eq!(combine.writer.stream_position().unwrap(), 5);
+        assert!(combine.rewind().is_err());
+        assert_eq!(combine.writer.stream_position().unwrap(), 5);
+        assert!(combine.reader.is_done());
+    }
+
+    #[test]
+    fn seek_desync() {
+        use std::io::{ErrorKind, Seek, SeekFrom};
+
+        use crate::testutil::{ScriptedReader, ScriptedWriter, Step};
+        use crate::DesyncError;
+
+        // The write end allows capturing its position and seeking, but not moving back.
+        let reader = ScriptedReader::new([Step::Error(ErrorKind::BrokenPipe)]);
+        let writer = ScriptedWriter::new([Step::Seek, Step::Seek, Step::Error(ErrorKind::Other)]);
+        let mut combine = Combine::new(reader, writer);
+        let err = combine.seek(SeekFrom::Start(8)).unwrap_err();
+        let desync = DesyncError::downcast(&err).unwrap();
+        assert_eq!(desync.seek_error().kind(), ErrorKind::BrokenPipe);
+        assert!(err.to_string().contains("inconsistent"));
+        assert!(combine.writer.is_done());
+        assert_eq!(combine.writer.stream_position().unwrap(), 8);
+    }
+
+    #[test]
+    fn seek_both() {
+        let mut combine = Combine::new(Cursor::new([0u8; 10]), Cursor::new(vec![0u8; 2]));
+        assert_eq!(combine.seek_both(SeekFrom::Start(1)).unwrap(), (1, 1));
+        assert_eq!(combine.seek_both(SeekFrom::End(-1)).unwrap(), (9, 1));
+        assert_eq!(combine.stream_position().unwrap(), 9);
+        assert!(combine.seek_both(SeekFrom::Current(-5)).is_err());
+        let (reader, writer) = combine.finish().unwrap();
+        assert_eq!((reader.position(), writer.position()), (9, 1));
+        let mut shared = Combine::from_shared(Cursor::new(vec![0u8; 4]));
+        assert_eq!(shared.seek_both(SeekFrom::End(0)).unwrap(), (4, 4));
+    }
+
+    #[test]
+    fn independent_positions() {
+        use std::io::ErrorKind;
+
+        use crate::{ReadExt, Whom};
+
+        let mut combine = Combine::new(Cursor::new([1u8, 2, 3, 4, 5, 6]), Cursor::new(vec![0u8; 6]));
+        assert_eq!(combine.seek_to(Whom::Reader, SeekFrom::Start(4)).unwrap(), 4);
+        assert_eq!(combine.seek_to(Whom::Writer, SeekFrom::End(-4)).unwrap(), 2);
+        assert_eq!(combine.read_be::<u16>().unwrap(), 0x0506);
+        combine.write_le(0x0807u16).unwrap();
+        assert_eq!(combine.position(Whom::Reader).unwrap(), 6);
+        assert_eq!(combine.position(Whom::Writer).unwrap(), 4);
+        assert_eq!(combine.position(Whom::Both).unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(combine.seek_to(Whom::Writer, SeekFrom::Current(2)).unwrap(), 6);
+        assert_eq!(combine.position(Whom::Both).unwrap(), 6);
+        assert_eq!(combine.seek_to(Whom::Both, SeekFrom::Start(1)).unwrap(), 1);
+        assert_eq!(combine.get_ref().1.position(), 1);
+        assert!(combine.seek_to(Whom::Reader, SeekFrom::Current(-2)).is_err());
+        assert_eq!(combine.position(Whom::Both).unwrap(), 1);
+        let (_, writer) = combine.finish().unwrap();
+        assert_eq!(writer.into_inner(), [0, 0, 7, 8, 0, 0]);
+        let mut shared = Combine::from_shared(Cursor::new(vec![0u8; 4]));
+        let err = shared.seek_to(Whom::Writer, SeekFrom::Start(2)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        assert_eq!(shared.seek_to(Whom::Both, SeekFrom::Start(2)).unwrap(), 2);
+        assert_eq!(shared.position(Whom::Both).unwrap(), 2);
+    }
+
+    #[test]
+    fn open() {
+        use std::fs::OpenOptions;
+
+        use crate::ReadExt;
+
+        let path = std::env::temp_dir().join(format!("bytesutil-combine-{}", std::process::id()));
+        let mut file = Combine::create(&path).unwrap();
+        file.write_le(0x42505846u32).unwrap();
+        file.write_be(7u16).unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        assert_eq!(file.read_le::<u32>().unwrap(), 0x42505846);
+        assert_eq!(file.seek(SeekFrom::End(0)).unwrap(), 6);
+        file.write_le(1u8).unwrap();
+        drop(file);
+
+        let mut file = Combine::open(&path).unwrap();
+        assert_eq!(file.read_le::<u32>().unwrap(), 0x42505846);
+        assert_eq!(file.read_be::<u16>().unwrap(), 7);
+        assert_eq!(file.read_le::<u8>().unwrap(), 1);
+        drop(file);
+
+        let mut file = Combine::open_with(&path, OpenOptions::new().read(true), OpenOptions::new().append(true)).unwrap();
+        file.write_le(2u8).unwrap();
+        assert_eq!(file.seek(SeekFrom::End(0)).unwrap(), 8);
+        drop(file);
+        Combine::create(&path).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
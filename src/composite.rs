@@ -0,0 +1,231 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use core::marker::PhantomData;
+
+use crate::{ByteSize, FixedSize, ReadBytes, WriteBytes};
+
+macro_rules! impl_zero_sized {
+    ($(($($g: ident),*) $t: ty => $value: expr;)*) => {
+        $(
+            impl<$($g),*> FixedSize for $t {
+                const SIZE: usize = 0;
+            }
+
+            impl<$($g),*> ByteSize for $t {
+                fn byte_size(&self) -> usize {
+                    0
+                }
+            }
+
+            impl<$($g),*> WriteBytes for $t {
+                fn write_bytes_le(&self, _: &mut [u8]) {}
+
+                fn write_bytes_be(&self, _: &mut [u8]) {}
+            }
+
+            impl<$($g),*> ReadBytes for $t {
+                fn read_bytes_le(_: &[u8]) -> Self {
+                    $value
+                }
+
+                fn read_bytes_be(_: &[u8]) -> Self {
+                    $value
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl<$($g),*> crate::WriteTo for $t {
+                fn write_to_le<W: std::io::Write>(&self, _: W) -> std::io::Result<()> {
+                    Ok(())
+                }
+
+                fn write_to_be<W: std::io::Write>(&self, _: W) -> std::io::Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl<$($g),*> crate::ReadFrom for $t {
+                fn read_from_le<R: std::io::Read>(_: R) -> std::io::Result<Self> {
+                    Ok($value)
+                }
+
+                fn read_from_be<R: std::io::Read>(_: R) -> std::io::Result<Self> {
+                    Ok($value)
+                }
+            }
+        )*
+    };
+}
+
+impl_zero_sized! {
+    () () => ();
+    (T) PhantomData<T> => PhantomData;
+    (T) [T; 0] => [];
+}
+
+macro_rules! impl_tuple {
+    ($(($($n: tt $t: ident)+))*) => {
+        $(
+            impl<$($t: FixedSize),+> FixedSize for ($($t,)+) {
+                const SIZE: usize = 0 $(+ $t::SIZE)+;
+            }
+
+            impl<$($t: ByteSize),+> ByteSize for ($($t,)+) {
+                fn byte_size(&self) -> usize {
+                    0 $(+ self.$n.byte_size())+
+                }
+            }
+
+            impl<$($t: WriteBytes),+> WriteBytes for ($($t,)+) {
+                #[allow(unused_assignments)]
+                fn write_bytes_le(&self, bytes: &mut [u8]) {
+                    let mut offset = 0;
+                    $(
+                        self.$n.write_bytes_le(&mut bytes[offset..]);
+                        offset += $t::SIZE;
+                    )+
+                }
+
+                #[allow(unused_assignments)]
+                fn write_bytes_be(&self, bytes: &mut [u8]) {
+                    let mut offset = 0;
+                    $(
+                        self.$n.write_bytes_be(&mut bytes[offset..]);
+                        offset += $t::SIZE;
+                    )+
+                }
+            }
+
+            impl<$($t: ReadBytes),+> ReadBytes for ($($t,)+) {
+                #[allow(unused_assignments)]
+                fn read_bytes_le(bytes: &[u8]) -> Self {
+                    let mut offset = 0;
+                    ($({
+                        let value = $t::read_bytes_le(&bytes[offset..]);
+                        offset += $t::SIZE;
+                        value
+                    },)+)
+                }
+
+                #[allow(unused_assignments)]
+                fn read_bytes_be(bytes: &[u8]) -> Self {
+                    let mut offset = 0;
+                    ($({
+                        let value = $t::read_bytes_be(&bytes[offset..]);
+                        offset += $t::SIZE;
+                        value
+                    },)+)
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl<$($t: crate::WriteTo),+> crate::WriteTo for ($($t,)+) {
+                fn write_to_le<W: std::io::Write>(&self, mut dst: W) -> std::io::Result<()> {
+                    $(self.$n.write_to_le(&mut dst)?;)+
+                    Ok(())
+                }
+
+                fn write_to_be<W: std::io::Write>(&self, mut dst: W) -> std::io::Result<()> {
+                    $(self.$n.write_to_be(&mut dst)?;)+
+                    Ok(())
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl<$($t: crate::ReadFrom),+> crate::ReadFrom for ($($t,)+) {
+                fn read_from_le<R: std::io::Read>(mut src: R) -> std::io::Result<Self> {
+                    Ok(($($t::read_from_le(&mut src)?,)+))
+                }
+
+                fn read_from_be<R: std::io::Read>(mut src: R) -> std::io::Result<Self> {
+                    Ok(($($t::read_from_be(&mut src)?,)+))
+                }
+            }
+        )*
+    };
+}
+
+impl_tuple! {
+    (0 A)
+    (0 A 1 B)
+    (0 A 1 B 2 C)
+    (0 A 1 B 2 C 3 D)
+    (0 A 1 B 2 C 3 D 4 E)
+    (0 A 1 B 2 C 3 D 4 E 5 F)
+    (0 A 1 B 2 C 3 D 4 E 5 F 6 G)
+    (0 A 1 B 2 C 3 D 4 E 5 F 6 G 7 H)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::marker::PhantomData;
+
+    use crate::{ByteBuf, FixedSize, ReadBytes};
+
+    #[test]
+    fn zero_sized() {
+        assert_eq!(<()>::SIZE, 0);
+        assert_eq!(<[u64; 0]>::SIZE, 0);
+        let mut buffer = ByteBuf::new([0u8; 0]);
+        buffer.set_le(0, ()).set_be(0, PhantomData::<u32>).set_le(0, [0u64; 0]);
+        let _: PhantomData<u32> = buffer.get_le(0);
+        let _: [u64; 0] = <[u64; 0]>::read_bytes_be(&[]);
+    }
+
+    #[test]
+    fn tuple() {
+        type Record = (u16, PhantomData<u64>, u32, ());
+        assert_eq!(Record::SIZE, 6);
+        let mut buffer = ByteBuf::new([0; 6]);
+        buffer.set_be(0, (0x0102u16, PhantomData::<u64>, 0x03040506u32, ()));
+        assert_eq!(buffer.into_inner(), [1, 2, 3, 4, 5, 6]);
+        let (a, _, b, ()) = buffer.get_be::<Record>(0);
+        assert_eq!((a, b), (0x0102, 0x03040506));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io() {
+        use std::io::Cursor;
+
+        use crate::{ReadExt, WriteExt};
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_le((1u8, PhantomData::<u32>, true)).unwrap();
+        cursor.write_le(()).unwrap();
+        assert_eq!(cursor.get_ref(), &[1, 1]);
+        cursor.set_position(0);
+        let (a, _, b) = cursor.read_le::<(u8, PhantomData<u32>, bool)>().unwrap();
+        assert_eq!((a, b), (1, true));
+        cursor.read_le::<()>().unwrap();
+        crate::testutil::assert_roundtrip_io((0x0102u16, (), -3i64, true));
+        crate::testutil::assert_roundtrip_bytes((0x0102u16, 7u8), &[2, 1, 7], &[1, 2, 7]);
+    }
+}
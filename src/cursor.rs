@@ -0,0 +1,496 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use core::fmt::{Display, Formatter};
+
+use crate::{Endian, ReadBytes, WriteBytes};
+
+/// An error produced when an access does not fit in a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundsError {
+    pos: usize,
+    size: usize,
+    len: usize
+}
+
+impl BoundsError {
+    /// Creates a new [BoundsError].
+    ///
+    /// # Arguments
+    ///
+    /// * `pos`: the offset of the access in bytes.
+    /// * `size`: the size of the access in bytes.
+    /// * `len`: the length of the buffer in bytes.
+    pub fn new(pos: usize, size: usize, len: usize) -> BoundsError {
+        Self { pos, size, len }
+    }
+
+    /// Returns the offset of the access in bytes.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the size of the access in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the length of the buffer in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the buffer was empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Display for BoundsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "need {} bytes at offset {}, got {}",
+            self.size,
+            self.pos,
+            self.len.saturating_sub(self.pos)
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BoundsError {}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for BoundsError {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "need {=usize} bytes at offset {=usize}, got {=usize}",
+            self.size,
+            self.pos,
+            self.len.saturating_sub(self.pos)
+        )
+    }
+}
+
+/// An error produced when a slice does not have the exact length of a fixed size buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthError {
+    expected: usize,
+    actual: usize
+}
+
+impl LengthError {
+    /// Creates a new [LengthError].
+    ///
+    /// # Arguments
+    ///
+    /// * `expected`: the required length in bytes.
+    /// * `actual`: the length of the slice in bytes.
+    pub fn new(expected: usize, actual: usize) -> LengthError {
+        Self { expected, actual }
+    }
+
+    /// Returns the required length in bytes.
+    pub fn expected(&self) -> usize {
+        self.expected
+    }
+
+    /// Returns the length of the slice in bytes.
+    pub fn actual(&self) -> usize {
+        self.actual
+    }
+}
+
+impl Display for LengthError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "expected exactly {} bytes, got {}", self.expected, self.actual)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LengthError {}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for LengthError {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "expected exactly {=usize} bytes, got {=usize}", self.expected, self.actual)
+    }
+}
+
+/// Checks that `size` bytes at offset `pos` fit in a buffer of length `len`.
+pub(crate) fn check_bounds(pos: usize, size: usize, len: usize) -> Result<(), BoundsError> {
+    match pos.checked_add(size) {
+        Some(end) if end <= len => Ok(()),
+        _ => Err(BoundsError::new(pos, size, len))
+    }
+}
+
+/// A cursor which reads and writes fields sequentially over a buffer, without requiring std.
+///
+/// *This is the no_std counterpart of [ReadExt](crate::ReadExt) and [WriteExt](crate::WriteExt)
+/// over a [Cursor](std::io::Cursor).* Every field access needs [SIZE](crate::FixedSize::SIZE)
+/// bytes to remain in the buffer and advances the position by that amount.
+///
+/// The cursor also carries a default byte order, used by [read](SliceCursor::read) and
+/// [write](SliceCursor::write), so that mixed-endian formats only spell out the fields which
+/// differ from it.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::{Endian, SliceCursor};
+///
+/// let mut cursor = SliceCursor::new([1, 0, 0, 2]).with_endianness(Endian::Little);
+/// assert_eq!(cursor.read::<u16>().unwrap(), 1);
+/// assert_eq!(cursor.with_endianness(Endian::Big).read::<u16>().unwrap(), 2);
+/// ```
+pub struct SliceCursor<T> {
+    inner: T,
+    pos: usize,
+    endian: Endian
+}
+
+impl<T> SliceCursor<T> {
+    /// Creates a new [SliceCursor] at the start of the given buffer.
+    ///
+    /// *The default byte order is the native byte order until changed with
+    /// [with_endianness](SliceCursor::with_endianness).*
+    pub fn new(inner: T) -> SliceCursor<T> {
+        Self {
+            inner,
+            pos: 0,
+            endian: Endian::NATIVE
+        }
+    }
+
+    /// Sets the default byte order of [read](SliceCursor::read) and [write](SliceCursor::write).
+    ///
+    /// # Arguments
+    ///
+    /// * `endian`: the byte order of fields which are not read or written with an explicit one.
+    ///
+    /// returns: SliceCursor<T>
+    pub fn with_endianness(mut self, endian: Endian) -> SliceCursor<T> {
+        self.endian = endian;
+        self
+    }
+
+    /// Returns the default byte order of the cursor.
+    pub fn endianness(&self) -> Endian {
+        self.endian
+    }
+
+    /// Returns the current position in bytes.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Sets the current position in bytes.
+    ///
+    /// *The position may be past the end of the buffer, in which case any access fails.*
+    pub fn set_position(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
+    /// Moves the position back to the start of the buffer, so that the buffer can be reused
+    /// without reallocating it.
+    pub fn reset(&mut self) {
+        self.pos = 0;
+    }
+
+    /// Returns a reference to the wrapped buffer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped buffer.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Extracts the wrapped buffer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: AsRef<[u8]>> SliceCursor<T> {
+    /// Returns the number of bytes between the current position and the end of the buffer.
+    pub fn remaining(&self) -> usize {
+        self.inner.as_ref().len().saturating_sub(self.pos)
+    }
+
+    /// Returns the bytes before the current position: the bytes produced so far when writing,
+    /// or the bytes already consumed when reading.
+    ///
+    /// *A position past the end of the buffer returns the whole buffer.*
+    pub fn written(&self) -> &[u8] {
+        let bytes = self.inner.as_ref();
+        &bytes[..self.pos.min(bytes.len())]
+    }
+
+    /// Extracts the wrapped buffer, checking that the position reached exactly its end.
+    ///
+    /// *This catches a forgotten field when filling a fixed size buffer, such as a
+    /// [StaticByteBuf](crate::StaticByteBuf) header.*
+    ///
+    /// # Errors
+    ///
+    /// Returns a [LengthError] with the length of the buffer and the current position if the
+    /// position is not at the end of the buffer.
+    pub fn finish(self) -> Result<T, LengthError> {
+        let len = self.inner.as_ref().len();
+        if self.pos != len {
+            return Err(LengthError::new(len, self.pos));
+        }
+        Ok(self.inner)
+    }
+
+    /// Extracts the wrapped buffer, along with the length of the prefix written or read so far.
+    ///
+    /// *This is the counterpart of [finish](SliceCursor::finish) for buffers which may be only
+    /// partially filled.*
+    pub fn finish_partial(self) -> (T, usize) {
+        let len = self.written().len();
+        (self.inner, len)
+    }
+
+    fn take(&mut self, size: usize) -> Result<&[u8], BoundsError> {
+        let bytes = self.inner.as_ref();
+        check_bounds(self.pos, size, bytes.len())?;
+        let pos = self.pos;
+        self.pos += size;
+        Ok(&bytes[pos..pos + size])
+    }
+
+    /// Reads a little-endian field and advances the position.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the field does not fit in the rest of the buffer.
+    pub fn read_le<V: ReadBytes>(&mut self) -> Result<V, BoundsError> {
+        self.take(V::SIZE).map(V::read_bytes_le)
+    }
+
+    /// Reads a big-endian field and advances the position.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the field does not fit in the rest of the buffer.
+    pub fn read_be<V: ReadBytes>(&mut self) -> Result<V, BoundsError> {
+        self.take(V::SIZE).map(V::read_bytes_be)
+    }
+
+    /// Reads a field in the default byte order of the cursor and advances the position.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the field does not fit in the rest of the buffer.
+    pub fn read<V: ReadBytes>(&mut self) -> Result<V, BoundsError> {
+        let endian = self.endian;
+        self.take(V::SIZE).map(|bytes| match endian {
+            Endian::Little => V::read_bytes_le(bytes),
+            Endian::Big => V::read_bytes_be(bytes)
+        })
+    }
+
+    /// Returns the next `len` bytes and advances the position.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if there are less than `len` bytes left in the buffer.
+    pub fn read_slice(&mut self, len: usize) -> Result<&[u8], BoundsError> {
+        self.take(len)
+    }
+}
+
+impl<T: AsMut<[u8]>> SliceCursor<T> {
+    fn take_mut(&mut self, size: usize) -> Result<&mut [u8], BoundsError> {
+        let bytes = self.inner.as_mut();
+        check_bounds(self.pos, size, bytes.len())?;
+        let pos = self.pos;
+        self.pos += size;
+        Ok(&mut bytes[pos..pos + size])
+    }
+
+    /// Writes a little-endian field and advances the position.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the field does not fit in the rest of the buffer, in which case
+    /// nothing is written.
+    pub fn write_le<V: WriteBytes>(&mut self, value: V) -> Result<(), BoundsError> {
+        value.write_bytes_le(self.take_mut(V::SIZE)?);
+        Ok(())
+    }
+
+    /// Writes a big-endian field and advances the position.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the field does not fit in the rest of the buffer, in which case
+    /// nothing is written.
+    pub fn write_be<V: WriteBytes>(&mut self, value: V) -> Result<(), BoundsError> {
+        value.write_bytes_be(self.take_mut(V::SIZE)?);
+        Ok(())
+    }
+
+    /// Writes a field in the default byte order of the cursor and advances the position.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the field does not fit in the rest of the buffer, in which case
+    /// nothing is written.
+    pub fn write<V: WriteBytes>(&mut self, value: V) -> Result<(), BoundsError> {
+        let endian = self.endian;
+        let bytes = self.take_mut(V::SIZE)?;
+        match endian {
+            Endian::Little => value.write_bytes_le(bytes),
+            Endian::Big => value.write_bytes_be(bytes)
+        }
+        Ok(())
+    }
+
+    /// Copies `bytes` at the current position and advances the position.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if `bytes` does not fit in the rest of the buffer, in which case
+    /// nothing is written.
+    pub fn write_slice(&mut self, bytes: &[u8]) -> Result<(), BoundsError> {
+        self.take_mut(bytes.len())?.copy_from_slice(bytes);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundsError, SliceCursor};
+
+    #[test]
+    fn roundtrip() {
+        let mut buffer = [0u8; 8];
+        let mut cursor = SliceCursor::new(&mut buffer[..]);
+        cursor.write_le(0x0102u16).unwrap();
+        cursor.write_be(0x0304u16).unwrap();
+        cursor.write_slice(&[5, 6]).unwrap();
+        assert_eq!(cursor.write_le(7u32), Err(BoundsError::new(6, 4, 8)));
+        assert_eq!(cursor.position(), 6);
+        cursor.write_le(true).unwrap();
+        assert_eq!(buffer, [2, 1, 3, 4, 5, 6, 1, 0]);
+        let mut cursor = SliceCursor::new(&buffer);
+        assert_eq!(cursor.read_le::<u16>().unwrap(), 0x0102);
+        assert_eq!(cursor.read_be::<u16>().unwrap(), 0x0304);
+        assert_eq!(cursor.read_slice(2).unwrap(), [5, 6]);
+        assert_eq!(cursor.remaining(), 2);
+        let err = cursor.read_le::<u32>().unwrap_err();
+        assert_eq!((err.pos(), err.size(), err.len()), (6, 4, 8));
+        assert!(cursor.read_le::<bool>().unwrap());
+        cursor.set_position(usize::MAX);
+        assert_eq!(cursor.remaining(), 0);
+        assert!(cursor.read_slice(1).is_err());
+    }
+
+    #[test]
+    fn reuse() {
+        let mut cursor = SliceCursor::new([0u8; 8]);
+        for round in 0..3u8 {
+            cursor.reset();
+            assert_eq!(cursor.written(), []);
+            for i in 0..=round {
+                cursor.write_le(i).unwrap();
+            }
+            cursor.write_be(0x0102u16).unwrap();
+            assert_eq!(cursor.written().len(), round as usize + 3);
+        }
+        assert_eq!(cursor.written(), [0, 1, 2, 1, 2]);
+        cursor.reset();
+        assert_eq!(cursor.read_le::<u16>().unwrap(), 0x0100);
+        assert_eq!(cursor.written(), [0, 1]);
+        cursor.set_position(12);
+        assert_eq!(cursor.written().len(), 8);
+    }
+
+    #[test]
+    fn finish() {
+        use crate::{LengthError, StaticByteBuf};
+
+        let mut cursor = SliceCursor::new(StaticByteBuf::<6>::default());
+        cursor.write_be(0x01020304u32).unwrap();
+        cursor.write_le(0x0605u16).unwrap();
+        assert_eq!(cursor.finish().unwrap().as_ref(), [1, 2, 3, 4, 5, 6]);
+        let mut cursor = SliceCursor::new(StaticByteBuf::<6>::default());
+        cursor.write_be(0x01020304u32).unwrap();
+        assert_eq!(cursor.finish().unwrap_err(), LengthError::new(6, 4));
+        let mut cursor = SliceCursor::new([0u8; 6]);
+        cursor.write_le(7u8).unwrap();
+        assert_eq!(cursor.finish_partial(), ([7, 0, 0, 0, 0, 0], 1));
+        let mut cursor = SliceCursor::new([0u8; 6]);
+        cursor.set_position(8);
+        assert_eq!(cursor.finish_partial().1, 6);
+    }
+
+    #[test]
+    fn default_endianness() {
+        use crate::Endian;
+
+        let mut buffer = [0u8; 8];
+        let mut cursor = SliceCursor::new(&mut buffer[..]).with_endianness(Endian::Little);
+        assert_eq!(cursor.endianness(), Endian::Little);
+        cursor.write(0x0102u16).unwrap();
+        cursor.write_be(0x0304u16).unwrap();
+        cursor.write(0x05060708u32).unwrap();
+        assert_eq!(cursor.write(0u8), Err(BoundsError::new(8, 1, 8)));
+        assert_eq!(buffer, [2, 1, 3, 4, 8, 7, 6, 5]);
+        let mut cursor = SliceCursor::new(&buffer).with_endianness(Endian::Little);
+        assert_eq!(cursor.read::<u16>().unwrap(), 0x0102);
+        assert_eq!(cursor.read_be::<u16>().unwrap(), 0x0304);
+        assert_eq!(cursor.read::<u32>().unwrap(), 0x05060708);
+        cursor.set_position(0);
+        let mut cursor = cursor.with_endianness(Endian::Big);
+        assert_eq!(cursor.read::<u16>().unwrap(), 0x0201);
+        assert_eq!(cursor.read_le::<u16>().unwrap(), 0x0403);
+        assert_eq!(SliceCursor::new(&buffer).endianness(), Endian::NATIVE);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn display() {
+        assert_eq!(BoundsError::new(6, 4, 8).to_string(), "need 4 bytes at offset 6, got 2");
+    }
+
+    #[cfg(all(feature = "defmt", feature = "std"))]
+    #[test]
+    fn defmt() {
+        let bytes = crate::testutil::capture_defmt(|| defmt::println!("{}", BoundsError::new(3, 4, 6)));
+        // The size, the offset and the number of remaining bytes, as u32 values.
+        assert!(bytes.windows(12).any(|w| w == [4, 0, 0, 0, 3, 0, 0, 0, 3, 0, 0, 0]));
+    }
+}
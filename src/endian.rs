@@ -0,0 +1,289 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use core::marker::PhantomData;
+
+use crate::{ByteSize, FixedSize, ReadBytes, WriteBytes};
+
+/// A byte order known at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Endian {
+    /// Little endian byte order.
+    Little,
+
+    /// Big endian byte order.
+    Big
+}
+
+impl Endian {
+    /// The byte order of the target platform.
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: Endian = Endian::Little;
+
+    /// The byte order of the target platform.
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: Endian = Endian::Big;
+
+    /// Detects the byte order of a magic number stored at the start of `bytes`.
+    ///
+    /// *If the magic number reads the same in both byte orders, the native byte order is
+    /// returned.*
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes`: the buffer containing the magic number.
+    /// * `expected`: the expected value of the magic number.
+    ///
+    /// returns: Option<Endian>
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of bytes is too small to store the magic number.
+    pub fn detect<T: ReadBytes + PartialEq>(bytes: &[u8], expected: T) -> Option<Endian> {
+        let le = T::read_bytes_le(bytes) == expected;
+        let be = T::read_bytes_be(bytes) == expected;
+        match (le, be) {
+            (true, true) => Some(Endian::NATIVE),
+            (true, false) => Some(Endian::Little),
+            (false, true) => Some(Endian::Big),
+            (false, false) => None
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Endian {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        match bool::arbitrary(u)? {
+            true => Ok(Endian::Big),
+            false => Ok(Endian::Little)
+        }
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        bool::size_hint(depth)
+    }
+}
+
+/// A byte order known at compile time, used to select an endianness through a generic parameter.
+pub trait ByteOrder {
+    /// The byte order represented by this type.
+    const ENDIAN: Endian;
+}
+
+/// Little endian byte order, as a type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LittleEndian;
+
+/// Big endian byte order, as a type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BigEndian;
+
+impl ByteOrder for LittleEndian {
+    const ENDIAN: Endian = Endian::Little;
+}
+
+impl ByteOrder for BigEndian {
+    const ENDIAN: Endian = Endian::Big;
+}
+
+/// A value which is always encoded in the byte order `E`, whichever order is requested.
+///
+/// *This is used to describe formats mixing byte orders within one record, such as a big endian
+/// header around little endian fields.* The byte order passed to the read and write methods is
+/// ignored in favor of `E`.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::{Be, Le, ReadBytes, WriteBytes};
+///
+/// let record = (Be::new(0x0102u16), Le::new(0x0304u16));
+/// let mut bytes = [0; 4];
+/// record.write_bytes_le(&mut bytes);
+/// assert_eq!(bytes, [1, 2, 4, 3]);
+/// let (header, field) = <(Be<u16>, Le<u16>)>::read_bytes_be(&bytes);
+/// assert_eq!((header.value, field.value), (0x0102, 0x0304));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Ordered<T, E> {
+    /// The wrapped value.
+    pub value: T,
+    marker: PhantomData<E>
+}
+
+/// A value which is always encoded in little endian order.
+pub type Le<T> = Ordered<T, LittleEndian>;
+
+/// A value which is always encoded in big endian order.
+pub type Be<T> = Ordered<T, BigEndian>;
+
+impl<T, E> Ordered<T, E> {
+    /// Wraps a value.
+    pub const fn new(value: T) -> Ordered<T, E> {
+        Self {
+            value,
+            marker: PhantomData
+        }
+    }
+
+    /// Extracts the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, E> From<T> for Ordered<T, E> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a>, E> arbitrary::Arbitrary<'a> for Ordered<T, E> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        T::arbitrary(u).map(Self::new)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        T::size_hint(depth)
+    }
+}
+
+impl<T: FixedSize, E> FixedSize for Ordered<T, E> {
+    const SIZE: usize = T::SIZE;
+}
+
+impl<T: ByteSize, E> ByteSize for Ordered<T, E> {
+    fn byte_size(&self) -> usize {
+        self.value.byte_size()
+    }
+}
+
+impl<T: WriteBytes, E: ByteOrder> WriteBytes for Ordered<T, E> {
+    fn write_bytes_le(&self, bytes: &mut [u8]) {
+        self.write_bytes_be(bytes)
+    }
+
+    fn write_bytes_be(&self, bytes: &mut [u8]) {
+        match E::ENDIAN {
+            Endian::Little => self.value.write_bytes_le(bytes),
+            Endian::Big => self.value.write_bytes_be(bytes)
+        }
+    }
+}
+
+impl<T: ReadBytes, E: ByteOrder> ReadBytes for Ordered<T, E> {
+    fn read_bytes_le(bytes: &[u8]) -> Self {
+        Self::read_bytes_be(bytes)
+    }
+
+    fn read_bytes_be(bytes: &[u8]) -> Self {
+        match E::ENDIAN {
+            Endian::Little => Self::new(T::read_bytes_le(bytes)),
+            Endian::Big => Self::new(T::read_bytes_be(bytes))
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: crate::WriteTo, E: ByteOrder> crate::WriteTo for Ordered<T, E> {
+    fn write_to_le<W: std::io::Write>(&self, dst: W) -> std::io::Result<()> {
+        self.write_to_be(dst)
+    }
+
+    fn write_to_be<W: std::io::Write>(&self, dst: W) -> std::io::Result<()> {
+        match E::ENDIAN {
+            Endian::Little => self.value.write_to_le(dst),
+            Endian::Big => self.value.write_to_be(dst)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: crate::ReadFrom, E: ByteOrder> crate::ReadFrom for Ordered<T, E> {
+    fn read_from_le<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+        Self::read_from_be(src)
+    }
+
+    fn read_from_be<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+        match E::ENDIAN {
+            Endian::Little => T::read_from_le(src).map(Self::new),
+            Endian::Big => T::read_from_be(src).map(Self::new)
+        }
+    }
+}
+
+/// Returns true if the target platform is little endian.
+pub const fn is_native_le() -> bool {
+    cfg!(target_endian = "little")
+}
+
+/// Returns true if the target platform is big endian.
+pub const fn is_native_be() -> bool {
+    cfg!(target_endian = "big")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Endian;
+
+    #[test]
+    fn detect() {
+        assert_eq!(Endian::detect(&[0x2A, 0x00], 42u16), Some(Endian::Little));
+        assert_eq!(Endian::detect(&[0x00, 0x2A], 42u16), Some(Endian::Big));
+        assert_eq!(Endian::detect(b"XPB\0", 0x00425058u32), Some(Endian::Little));
+        assert_eq!(Endian::detect(b"\0BPX", 0x00425058u32), Some(Endian::Big));
+        assert_eq!(Endian::detect(&[0x2B, 0x00], 42u16), None);
+        assert_eq!(Endian::detect(&[1, 2, 3, 4], 42u32), None);
+        assert_eq!(Endian::detect(&[0x11, 0x11], 0x1111u16), Some(Endian::NATIVE));
+    }
+
+    #[test]
+    fn native() {
+        assert_ne!(crate::is_native_le(), crate::is_native_be());
+        assert_eq!(crate::is_native_le(), Endian::NATIVE == Endian::Little);
+    }
+
+    #[test]
+    fn mixed() {
+        use crate::{Be, Le, ReadBytes, WriteBytes};
+
+        type Record = (Be<u32>, Le<u16>, Be<u16>, Le<u32>);
+        let record: Record = (0x01020304.into(), 0x0506.into(), 0x0708.into(), 0x090A0B0C.into());
+        let mut le = [0; 12];
+        let mut be = [0; 12];
+        record.write_bytes_le(&mut le);
+        record.write_bytes_be(&mut be);
+        assert_eq!(le, [1, 2, 3, 4, 6, 5, 7, 8, 0x0C, 0x0B, 0x0A, 9]);
+        assert_eq!(le, be);
+        assert_eq!(Record::read_bytes_le(&le), record);
+        assert_eq!(Record::read_bytes_be(&le).3.into_inner(), 0x090A0B0C);
+    }
+}
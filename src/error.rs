@@ -0,0 +1,182 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter};
+use std::io::{ErrorKind, Read};
+
+/// Details about a value which could not be decoded because its input ended too early.
+///
+/// A [DecodeError] is carried as the payload of an [Error](std::io::Error) of kind
+/// [UnexpectedEof](ErrorKind::UnexpectedEof) returned by [ReadFrom](crate::ReadFrom)
+/// implementations and [ReadExt](crate::ReadExt). Use [downcast](DecodeError::downcast) to
+/// retrieve it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    type_name: &'static str,
+    required: Option<usize>,
+    available: Option<usize>,
+    completed: Option<usize>
+}
+
+impl DecodeError {
+    /// Creates a new [DecodeError] for the type `T`.
+    ///
+    /// # Arguments
+    ///
+    /// * `required`: the number of bytes needed to decode `T`, if known.
+    /// * `available`: the number of bytes which were available, if known.
+    pub fn new<T: ?Sized>(required: Option<usize>, available: Option<usize>) -> DecodeError {
+        Self {
+            type_name: core::any::type_name::<T>(),
+            required,
+            available,
+            completed: None
+        }
+    }
+
+    /// Records the number of elements which were fully decoded before this error, when reading
+    /// a slice.
+    pub fn with_completed(mut self, completed: usize) -> DecodeError {
+        self.completed = Some(completed);
+        self
+    }
+
+    /// Returns the name of the type which could not be decoded.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the number of bytes needed to decode the value, if known.
+    pub fn required(&self) -> Option<usize> {
+        self.required
+    }
+
+    /// Returns the number of bytes which were available, if known.
+    pub fn available(&self) -> Option<usize> {
+        self.available
+    }
+
+    /// Returns the number of elements of a slice which were fully decoded before this error, if
+    /// the value was read as part of a slice.
+    pub fn completed(&self) -> Option<usize> {
+        self.completed
+    }
+
+    /// Extracts the [DecodeError] carried by the given [Error](std::io::Error), if any.
+    pub fn downcast(error: &std::io::Error) -> Option<&DecodeError> {
+        error.get_ref()?.downcast_ref()
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to decode {}", self.type_name)?;
+        match (self.required, self.available) {
+            (Some(required), Some(available)) => write!(f, ": needed {} bytes, only {} available", required, available),
+            (Some(required), None) => write!(f, ": needed {} bytes", required),
+            (None, Some(available)) => write!(f, ": only {} bytes available", available),
+            (None, None) => write!(f, ": unexpected end of stream")
+        }?;
+        match self.completed {
+            Some(completed) => write!(f, " after {} complete elements", completed),
+            None => Ok(())
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for std::io::Error {
+    fn from(value: DecodeError) -> Self {
+        std::io::Error::new(ErrorKind::UnexpectedEof, value)
+    }
+}
+
+/// Fills `block` from `src`, reporting a [DecodeError] for `T` if the stream ends early.
+pub(crate) fn read_exact_for<T: ?Sized, R: Read + ?Sized>(src: &mut R, block: &mut [u8]) -> std::io::Result<()> {
+    let mut filled = 0;
+    while filled < block.len() {
+        match src.read(&mut block[filled..]) {
+            Ok(0) => return Err(DecodeError::new::<T>(Some(block.len()), Some(filled)).into()),
+            Ok(len) => filled += len,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e)
+        }
+    }
+    Ok(())
+}
+
+/// Attaches a [DecodeError] for `T` to an [UnexpectedEof](ErrorKind::UnexpectedEof) error which
+/// does not already carry one.
+pub(crate) fn attach<T: ?Sized>(error: std::io::Error) -> std::io::Error {
+    if error.kind() == ErrorKind::UnexpectedEof && DecodeError::downcast(&error).is_none() {
+        DecodeError::new::<T>(None, None).into()
+    } else {
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use crate::{DecodeError, ReadExt, StaticByteBuf};
+
+    #[test]
+    fn short_read() {
+        let mut cursor = Cursor::new([1, 2, 3]);
+        let err = cursor.read_le::<u64>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let details = DecodeError::downcast(&err).unwrap();
+        assert_eq!(details.type_name(), "u64");
+        assert_eq!(details.required(), Some(8));
+        assert_eq!(details.available(), Some(3));
+        assert_eq!(err.to_string(), "failed to decode u64: needed 8 bytes, only 3 available");
+    }
+
+    #[test]
+    fn composite() {
+        let mut cursor = Cursor::new([1, 2]);
+        let err = cursor.read_be::<(u16, bool)>().unwrap_err();
+        let details = DecodeError::downcast(&err).unwrap();
+        assert_eq!((details.type_name(), details.required(), details.available()), ("bool", Some(1), Some(0)));
+        let mut cursor = Cursor::new([1, 2, 3]);
+        let err = cursor.read_be::<StaticByteBuf<4>>().unwrap_err();
+        assert_eq!(DecodeError::downcast(&err).unwrap().available(), Some(3));
+    }
+
+    #[test]
+    fn other_errors() {
+        let mut cursor = Cursor::new([0xFF; 6]);
+        let err = cursor.read_le::<crate::VarU32>().unwrap_err();
+        assert!(DecodeError::downcast(&err).is_none());
+        let mut cursor = Cursor::new([0x80]);
+        let err = cursor.read_le::<crate::VarU32>().unwrap_err();
+        assert_eq!(DecodeError::downcast(&err).unwrap().type_name(), "bytesutil::varint::VarU32");
+    }
+}
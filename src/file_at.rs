@@ -0,0 +1,248 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fs::File;
+use std::io::Result;
+
+use crate::{ReadBytes, WriteBytes};
+
+/// Positioned reads and writes on a [File] which take `&self` and do not depend on the file
+/// cursor.
+///
+/// *This allows several threads, or several components sharing the same [File], to access
+/// different regions of a file concurrently without seeking.*
+///
+/// On unix this uses `pread` and `pwrite`, which never move the file cursor. On windows this
+/// uses `seek_read` and `seek_write`, which leave the file cursor at an unspecified position.
+/// Other platforms fall back to seeking under a process-wide lock and restoring the cursor
+/// afterwards.
+pub trait FileExtAt {
+    /// Reads exactly `buf.len()` bytes starting at `offset` into `buf`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf`: the buffer to fill.
+    /// * `offset`: the position in the file of the first byte to read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be read. If the file ends
+    /// before `buf` is filled, the error kind is [UnexpectedEof](std::io::ErrorKind::UnexpectedEof).
+    fn read_exact_at_pos(&self, buf: &mut [u8], offset: u64) -> Result<()>;
+
+    /// Writes all of `buf` starting at `offset`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf`: the bytes to write.
+    /// * `offset`: the position in the file of the first byte to write.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be written.
+    fn write_all_at_pos(&self, buf: &[u8], offset: u64) -> Result<()>;
+
+    /// Reads a value in little endian order starting at `offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be read.
+    fn read_le_at_pos<T: ReadBytes>(&self, offset: u64) -> Result<T> {
+        let mut block = vec![0; T::SIZE];
+        self.read_exact_at_pos(&mut block, offset)?;
+        Ok(T::read_bytes_le(&block))
+    }
+
+    /// Reads a value in big endian order starting at `offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be read.
+    fn read_be_at_pos<T: ReadBytes>(&self, offset: u64) -> Result<T> {
+        let mut block = vec![0; T::SIZE];
+        self.read_exact_at_pos(&mut block, offset)?;
+        Ok(T::read_bytes_be(&block))
+    }
+
+    /// Writes the [SIZE](crate::FixedSize::SIZE) bytes of `value` in little endian order
+    /// starting at `offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be written.
+    fn write_le_at_pos<T: WriteBytes>(&self, offset: u64, value: T) -> Result<()> {
+        let mut block = vec![0; T::SIZE];
+        value.write_bytes_le(&mut block);
+        self.write_all_at_pos(&block, offset)
+    }
+
+    /// Writes the [SIZE](crate::FixedSize::SIZE) bytes of `value` in big endian order starting
+    /// at `offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be written.
+    fn write_be_at_pos<T: WriteBytes>(&self, offset: u64, value: T) -> Result<()> {
+        let mut block = vec![0; T::SIZE];
+        value.write_bytes_be(&mut block);
+        self.write_all_at_pos(&block, offset)
+    }
+}
+
+#[cfg(unix)]
+impl FileExtAt for File {
+    fn read_exact_at_pos(&self, buf: &mut [u8], offset: u64) -> Result<()> {
+        std::os::unix::fs::FileExt::read_exact_at(self, buf, offset)
+    }
+
+    fn write_all_at_pos(&self, buf: &[u8], offset: u64) -> Result<()> {
+        std::os::unix::fs::FileExt::write_all_at(self, buf, offset)
+    }
+}
+
+#[cfg(windows)]
+impl FileExtAt for File {
+    fn read_exact_at_pos(&self, mut buf: &mut [u8], mut offset: u64) -> Result<()> {
+        use std::os::windows::fs::FileExt;
+        while !buf.is_empty() {
+            match self.seek_read(buf, offset) {
+                Ok(0) => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+                Ok(len) => {
+                    buf = &mut buf[len..];
+                    offset += len as u64;
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e)
+            }
+        }
+        Ok(())
+    }
+
+    fn write_all_at_pos(&self, mut buf: &[u8], mut offset: u64) -> Result<()> {
+        use std::os::windows::fs::FileExt;
+        while !buf.is_empty() {
+            match self.seek_write(buf, offset) {
+                Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+                Ok(len) => {
+                    buf = &buf[len..];
+                    offset += len as u64;
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e)
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serializes the positioned accesses of platforms without native positioned IO, so that the
+/// seek and the access of a call are not interleaved with those of another call.
+#[cfg(not(any(unix, windows)))]
+static SEEK_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[cfg(not(any(unix, windows)))]
+fn with_seek<T, F: FnOnce(&mut &File) -> Result<T>>(mut file: &File, offset: u64, f: F) -> Result<T> {
+    use std::io::{Seek, SeekFrom};
+    let _guard = SEEK_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let pos = file.stream_position()?;
+    file.seek(SeekFrom::Start(offset))?;
+    let res = f(&mut file);
+    file.seek(SeekFrom::Start(pos))?;
+    res
+}
+
+#[cfg(not(any(unix, windows)))]
+impl FileExtAt for File {
+    fn read_exact_at_pos(&self, buf: &mut [u8], offset: u64) -> Result<()> {
+        with_seek(self, offset, |file| std::io::Read::read_exact(file, buf))
+    }
+
+    fn write_all_at_pos(&self, buf: &[u8], offset: u64) -> Result<()> {
+        with_seek(self, offset, |file| std::io::Write::write_all(file, buf))
+    }
+}
+
+impl FileExtAt for &File {
+    fn read_exact_at_pos(&self, buf: &mut [u8], offset: u64) -> Result<()> {
+        (**self).read_exact_at_pos(buf, offset)
+    }
+
+    fn write_all_at_pos(&self, buf: &[u8], offset: u64) -> Result<()> {
+        (**self).write_all_at_pos(buf, offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::{ErrorKind, Read, Seek, SeekFrom};
+    use std::sync::Arc;
+
+    use crate::FileExtAt;
+
+    fn patch<F: FileExtAt>(file: F) {
+        file.write_le_at_pos(256, 0xDEADu16).unwrap();
+    }
+
+    #[test]
+    fn concurrent() {
+        let path = std::env::temp_dir().join(format!("bytesutil-file-at-{}", std::process::id()));
+        let file = File::options().read(true).write(true).create(true).truncate(true).open(&path).unwrap();
+        for i in 0..64u32 {
+            file.write_be_at_pos(i as u64 * 4, i * 3).unwrap();
+        }
+        patch(&file);
+        let mut file = file;
+        file.seek(SeekFrom::Start(100)).unwrap();
+        let file = Arc::new(file);
+        let threads: Vec<_> = (0..4u32)
+            .map(|t| {
+                let file = file.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..50 {
+                        for i in (t..64).step_by(4) {
+                            assert_eq!(file.read_be_at_pos::<u32>(i as u64 * 4).unwrap(), i * 3);
+                        }
+                    }
+                })
+            })
+            .collect();
+        threads.into_iter().for_each(|thread| thread.join().unwrap());
+        assert_eq!(file.read_le_at_pos::<u16>(256).unwrap(), 0xDEAD);
+        assert_eq!(file.read_le_at_pos::<u32>(256).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        let mut file = Arc::into_inner(file).unwrap();
+        #[cfg(not(windows))]
+        assert_eq!(file.stream_position().unwrap(), 100);
+        let mut content = Vec::new();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.read_to_end(&mut content).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(content.len(), 258);
+        assert_eq!(content[4..8], [0, 0, 0, 3]);
+    }
+}
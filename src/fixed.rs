@@ -0,0 +1,204 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{ByteSize, FixedSize, ReadBytes, WriteBytes};
+
+macro_rules! impl_fixed {
+    ($($(#[$doc: meta])* $name: ident: $t: ty, $bits: literal;)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+            pub struct $name<const FRAC: u32>(pub $t);
+
+            impl<const FRAC: u32> $name<FRAC> {
+                /// The value of the least significant bit of the raw integer, as its inverse.
+                ///
+                /// *Using a `FRAC` which is not less than the width of the raw integer is a
+                /// compile time error.*
+                pub const SCALE: f64 = {
+                    assert!(FRAC < $bits, "FRAC must be less than the width of the raw integer");
+                    (1u64 << FRAC) as f64
+                };
+
+                /// Returns the value as an [f64].
+                ///
+                /// *This is exact unless the raw integer needs more than 53 significant bits.*
+                pub fn to_f64(self) -> f64 {
+                    self.0 as f64 / Self::SCALE
+                }
+
+                /// Converts an [f64] to the nearest fixed-point value.
+                ///
+                /// Halfway values are rounded away from zero. Values out of range saturate to the
+                /// minimum or the maximum raw integer, and NaN gives 0.
+                pub fn from_f64(value: f64) -> Self {
+                    let scaled = value * Self::SCALE;
+                    let whole = scaled as $t;
+                    let rest = scaled - whole as f64;
+                    if rest >= 0.5 {
+                        Self(whole.saturating_add(1))
+                    } else if rest <= -0.5 {
+                        Self(whole.saturating_sub(1))
+                    } else {
+                        Self(whole)
+                    }
+                }
+            }
+
+            #[cfg(feature = "arbitrary")]
+            impl<'a, const FRAC: u32> arbitrary::Arbitrary<'a> for $name<FRAC> {
+                fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                    <$t>::arbitrary(u).map(Self)
+                }
+
+                fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                    <$t>::size_hint(depth)
+                }
+            }
+
+            impl<const FRAC: u32> FixedSize for $name<FRAC> {
+                const SIZE: usize = <$t>::SIZE;
+            }
+
+            impl<const FRAC: u32> ByteSize for $name<FRAC> {
+                fn byte_size(&self) -> usize {
+                    <$t>::SIZE
+                }
+            }
+
+            impl<const FRAC: u32> WriteBytes for $name<FRAC> {
+                fn write_bytes_le(&self, bytes: &mut [u8]) {
+                    self.0.write_bytes_le(bytes)
+                }
+
+                fn write_bytes_be(&self, bytes: &mut [u8]) {
+                    self.0.write_bytes_be(bytes)
+                }
+            }
+
+            impl<const FRAC: u32> ReadBytes for $name<FRAC> {
+                fn read_bytes_le(bytes: &[u8]) -> Self {
+                    Self(<$t>::read_bytes_le(bytes))
+                }
+
+                fn read_bytes_be(bytes: &[u8]) -> Self {
+                    Self(<$t>::read_bytes_be(bytes))
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl<const FRAC: u32> crate::WriteTo for $name<FRAC> {
+                fn write_to_le<W: std::io::Write>(&self, dst: W) -> std::io::Result<()> {
+                    self.0.write_to_le(dst)
+                }
+
+                fn write_to_be<W: std::io::Write>(&self, dst: W) -> std::io::Result<()> {
+                    self.0.write_to_be(dst)
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl<const FRAC: u32> crate::ReadFrom for $name<FRAC> {
+                fn read_from_le<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+                    <$t>::read_from_le(src).map(Self)
+                }
+
+                fn read_from_be<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+                    <$t>::read_from_be(src).map(Self)
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed! {
+    /// A signed fixed-point number stored as an [i32] with `FRAC` fractional bits, such as
+    /// `Fixed32<16>` for the Q16.16 format.
+    ///
+    /// The raw integer is written as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytesutil::{Fixed32, ReadBytes};
+    ///
+    /// let value = Fixed32::<16>::read_bytes_be(&[0x00, 0x01, 0x80, 0x00]);
+    /// assert_eq!(value.to_f64(), 1.5);
+    /// assert_eq!(Fixed32::<16>::from_f64(-0.25).0, -0x4000);
+    /// ```
+    Fixed32: i32, 32;
+
+    /// A signed fixed-point number stored as an [i64] with `FRAC` fractional bits, such as
+    /// `Fixed64<32>` for the Q32.32 format.
+    ///
+    /// The raw integer is written as is.
+    Fixed64: i64, 64;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ByteBuf, FixedSize, Fixed32, Fixed64};
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Fixed32::<16>(0x00018000).to_f64(), 1.5);
+        assert_eq!(Fixed32::<16>(-1).to_f64(), -1.0 / 65536.0);
+        assert_eq!(Fixed32::<0>::from_f64(2.0).0, 2);
+        assert_eq!(Fixed64::<32>::from_f64(-3.75).0, -0x3_C000_0000);
+        assert_eq!(Fixed64::<63>::SCALE, 9223372036854775808.0);
+        for raw in [0, 1, -1, 12345, i32::MAX, i32::MIN] {
+            assert_eq!(Fixed32::<16>::from_f64(Fixed32::<16>(raw).to_f64()).0, raw);
+        }
+    }
+
+    #[test]
+    fn rounding() {
+        let half = 0.5 / 65536.0;
+        assert_eq!(Fixed32::<16>::from_f64(half).0, 1);
+        assert_eq!(Fixed32::<16>::from_f64(-half).0, -1);
+        assert_eq!(Fixed32::<16>::from_f64(half * 0.99).0, 0);
+        assert_eq!(Fixed32::<16>::from_f64(3.0 * half).0, 2);
+        assert_eq!(Fixed32::<16>::from_f64(1e10).0, i32::MAX);
+        assert_eq!(Fixed32::<16>::from_f64(f64::NEG_INFINITY).0, i32::MIN);
+        assert_eq!(Fixed64::<8>::from_f64(f64::MAX).0, i64::MAX);
+        assert_eq!(Fixed32::<16>::from_f64(f64::NAN).0, 0);
+    }
+
+    #[test]
+    fn serialization() {
+        assert_eq!(Fixed32::<16>::SIZE, 4);
+        assert_eq!(Fixed64::<32>::SIZE, 8);
+        let mut buffer = ByteBuf::new([0; 12]);
+        buffer.set_be(0, Fixed32::<16>::from_f64(1.5)).set_le(4, Fixed64::<32>::from_f64(-1.0));
+        assert_eq!(buffer.as_ref()[..4], [0x00, 0x01, 0x80, 0x00]);
+        assert_eq!(buffer.get_le::<i64>(4), -0x1_0000_0000);
+        assert_eq!(buffer.get_le::<Fixed64<32>>(4).to_f64(), -1.0);
+        #[cfg(feature = "std")]
+        crate::testutil::assert_roundtrip_io(Fixed32::<16>::from_f64(-2.125));
+    }
+}
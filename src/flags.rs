@@ -0,0 +1,416 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use core::fmt::{Debug, Formatter, LowerHex};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{BitAnd, BitOr, BitXor, Not};
+
+use crate::{ByteSize, FixedSize, ReadBytes, WriteBytes};
+
+/// An unsigned integer type which stores the bits of a [Flags].
+pub trait FlagBits:
+    Copy
+    + Eq
+    + Hash
+    + Debug
+    + LowerHex
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + ReadBytes
+    + WriteBytes
+{
+    /// The value with no bit set.
+    const ZERO: Self;
+}
+
+/// A set of named flags, usually declared with [flags](crate::flags).
+///
+/// *Every unsigned integer type from [u8] to [u64] is also a [FlagSet] where every bit is
+/// known, so that `Flags<u32>` can be used for untyped flag words.*
+pub trait FlagSet {
+    /// The integer type storing the flags.
+    type Bits: FlagBits;
+
+    /// The union of every known flag.
+    const ALL: Self::Bits;
+}
+
+macro_rules! impl_flag_bits {
+    ($($t: ty)*) => {
+        $(
+            impl FlagBits for $t {
+                const ZERO: Self = 0;
+            }
+
+            impl FlagSet for $t {
+                type Bits = $t;
+                const ALL: Self::Bits = <$t>::MAX;
+            }
+        )*
+    };
+}
+
+impl_flag_bits!(u8 u16 u32 u64);
+
+/// A typed flag word, encoded as its underlying integer.
+///
+/// The type parameter `F` names the set of known flags, such that flags of unrelated sets
+/// cannot be mixed. Unknown bits are kept as is by [from_bits_retain](Flags::from_bits_retain)
+/// and by the read traits, so that a flag word read from a newer producer is written back
+/// unchanged; use [from_bits_checked](Flags::from_bits_checked) or
+/// [read_checked_le](Flags::read_checked_le) to reject them instead.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::{flags, Flags};
+///
+/// flags! {
+///     pub struct SectionFlags: u16 {
+///         const COMPRESSED = 0x1;
+///         const ENCRYPTED = 0x4;
+///     }
+/// }
+///
+/// let mut flags = SectionFlags::COMPRESSED | SectionFlags::ENCRYPTED;
+/// flags.remove(SectionFlags::COMPRESSED);
+/// assert!(flags.contains(SectionFlags::ENCRYPTED));
+/// assert_eq!(flags.bits(), 0x4);
+/// assert_eq!(Flags::<SectionFlags>::from_bits_checked(0x8), None);
+/// ```
+pub struct Flags<F: FlagSet> {
+    bits: F::Bits,
+    marker: PhantomData<F>
+}
+
+impl<F: FlagSet> Flags<F> {
+    /// Creates a new [Flags] from the given bits, keeping unknown bits.
+    pub const fn from_bits_retain(bits: F::Bits) -> Self {
+        Self {
+            bits,
+            marker: PhantomData
+        }
+    }
+
+    /// Creates a new [Flags] from the given bits, clearing unknown bits.
+    pub fn from_bits_truncate(bits: F::Bits) -> Self {
+        Self::from_bits_retain(bits & F::ALL)
+    }
+
+    /// Creates a new [Flags] from the given bits.
+    ///
+    /// returns: None if some bits are not known flags.
+    pub fn from_bits_checked(bits: F::Bits) -> Option<Self> {
+        match bits & !F::ALL == F::Bits::ZERO {
+            true => Some(Self::from_bits_retain(bits)),
+            false => None
+        }
+    }
+
+    /// Creates a new [Flags] with no flag set.
+    pub fn empty() -> Self {
+        Self::from_bits_retain(F::Bits::ZERO)
+    }
+
+    /// Creates a new [Flags] with every known flag set.
+    pub fn all() -> Self {
+        Self::from_bits_retain(F::ALL)
+    }
+
+    /// Returns the underlying bits, including unknown bits.
+    pub fn bits(&self) -> F::Bits {
+        self.bits
+    }
+
+    /// Returns true if no bit is set.
+    pub fn is_empty(&self) -> bool {
+        self.bits == F::Bits::ZERO
+    }
+
+    /// Returns true if every flag of `other` is set in self.
+    pub fn contains(&self, other: Self) -> bool {
+        self.bits & other.bits == other.bits
+    }
+
+    /// Returns true if at least one flag of `other` is set in self.
+    pub fn intersects(&self, other: Self) -> bool {
+        self.bits & other.bits != F::Bits::ZERO
+    }
+
+    /// Sets the flags of `other` in self.
+    pub fn insert(&mut self, other: Self) {
+        self.bits = self.bits | other.bits;
+    }
+
+    /// Clears the flags of `other` in self.
+    pub fn remove(&mut self, other: Self) {
+        self.bits = self.bits & !other.bits;
+    }
+
+    /// Inverts the flags of `other` in self.
+    pub fn toggle(&mut self, other: Self) {
+        self.bits = self.bits ^ other.bits;
+    }
+
+    /// Sets or clears the flags of `other` in self.
+    pub fn set(&mut self, other: Self, value: bool) {
+        match value {
+            true => self.insert(other),
+            false => self.remove(other)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: FlagSet> Flags<F>
+where
+    F::Bits: crate::ReadFrom
+{
+    /// Reads a flag word in little endian order, rejecting unknown bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be read. If some bits are not
+    /// known flags, an error of kind [InvalidData](std::io::ErrorKind::InvalidData) is returned.
+    pub fn read_checked_le<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+        check_known(<F::Bits as crate::ReadFrom>::read_from_le(src)?)
+    }
+
+    /// Reads a flag word in big endian order, rejecting unknown bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be read. If some bits are not
+    /// known flags, an error of kind [InvalidData](std::io::ErrorKind::InvalidData) is returned.
+    pub fn read_checked_be<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+        check_known(<F::Bits as crate::ReadFrom>::read_from_be(src)?)
+    }
+}
+
+#[cfg(feature = "std")]
+fn check_known<F: FlagSet>(bits: F::Bits) -> std::io::Result<Flags<F>> {
+    Flags::from_bits_checked(bits).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("unknown flag bits {:#x}", bits & !F::ALL)
+        )
+    })
+}
+
+impl<F: FlagSet> Default for Flags<F> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<F: FlagSet> Clone for Flags<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: FlagSet> Copy for Flags<F> {}
+
+impl<F: FlagSet> PartialEq for Flags<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl<F: FlagSet> Eq for Flags<F> {}
+
+impl<F: FlagSet> Hash for Flags<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits.hash(state)
+    }
+}
+
+impl<F: FlagSet> Debug for Flags<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Flags<{}>({:#x})", core::any::type_name::<F>(), self.bits)
+    }
+}
+
+impl<F: FlagSet> BitOr for Flags<F> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self::from_bits_retain(self.bits | rhs.bits)
+    }
+}
+
+impl<F: FlagSet> BitAnd for Flags<F> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self::from_bits_retain(self.bits & rhs.bits)
+    }
+}
+
+impl<F: FlagSet> BitXor for Flags<F> {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        Self::from_bits_retain(self.bits ^ rhs.bits)
+    }
+}
+
+/// Returns the known flags which are not set in self.
+impl<F: FlagSet> Not for Flags<F> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::from_bits_truncate(!self.bits)
+    }
+}
+
+impl<F: FlagSet> FixedSize for Flags<F> {
+    const SIZE: usize = F::Bits::SIZE;
+}
+
+impl<F: FlagSet> ByteSize for Flags<F> {
+    fn byte_size(&self) -> usize {
+        Self::SIZE
+    }
+}
+
+impl<F: FlagSet> WriteBytes for Flags<F> {
+    fn write_bytes_le(&self, bytes: &mut [u8]) {
+        self.bits.write_bytes_le(bytes)
+    }
+
+    fn write_bytes_be(&self, bytes: &mut [u8]) {
+        self.bits.write_bytes_be(bytes)
+    }
+}
+
+impl<F: FlagSet> ReadBytes for Flags<F> {
+    fn read_bytes_le(bytes: &[u8]) -> Self {
+        Self::from_bits_retain(F::Bits::read_bytes_le(bytes))
+    }
+
+    fn read_bytes_be(bytes: &[u8]) -> Self {
+        Self::from_bits_retain(F::Bits::read_bytes_be(bytes))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: FlagSet> crate::WriteTo for Flags<F>
+where
+    F::Bits: crate::WriteTo
+{
+    fn write_to_le<W: std::io::Write>(&self, dst: W) -> std::io::Result<()> {
+        crate::WriteTo::write_to_le(&self.bits, dst)
+    }
+
+    fn write_to_be<W: std::io::Write>(&self, dst: W) -> std::io::Result<()> {
+        crate::WriteTo::write_to_be(&self.bits, dst)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: FlagSet> crate::ReadFrom for Flags<F>
+where
+    F::Bits: crate::ReadFrom
+{
+    fn read_from_le<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+        <F::Bits as crate::ReadFrom>::read_from_le(src).map(Self::from_bits_retain)
+    }
+
+    fn read_from_be<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+        <F::Bits as crate::ReadFrom>::read_from_be(src).map(Self::from_bits_retain)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use crate::{Flags, ReadExt, WriteExt};
+
+    flags! {
+        struct SectionFlags: u16 {
+            const COMPRESSED = 0x1;
+            const ENCRYPTED = 0x2;
+            const SIGNED = 0x100;
+        }
+    }
+
+    #[test]
+    fn operations() {
+        let mut flags = SectionFlags::COMPRESSED | SectionFlags::SIGNED;
+        assert_eq!(flags.bits(), 0x101);
+        assert!(flags.contains(SectionFlags::SIGNED));
+        assert!(!flags.contains(SectionFlags::SIGNED | SectionFlags::ENCRYPTED));
+        assert!(flags.intersects(SectionFlags::SIGNED | SectionFlags::ENCRYPTED));
+        flags.toggle(SectionFlags::COMPRESSED | SectionFlags::ENCRYPTED);
+        assert_eq!(flags, SectionFlags::ENCRYPTED | SectionFlags::SIGNED);
+        flags.remove(SectionFlags::SIGNED);
+        flags.insert(SectionFlags::COMPRESSED);
+        assert_eq!(flags.bits(), 0x3);
+        assert_eq!((!flags).bits(), 0x100);
+        assert_eq!(Flags::<SectionFlags>::all().bits(), 0x103);
+        assert!(Flags::<SectionFlags>::default().is_empty());
+        assert_eq!(Flags::<SectionFlags>::from_bits_truncate(0xFFFF).bits(), 0x103);
+        assert_eq!(Flags::<SectionFlags>::from_bits_checked(0x102), Some(SectionFlags::ENCRYPTED | SectionFlags::SIGNED));
+        assert_eq!(Flags::<SectionFlags>::from_bits_checked(0x8), None);
+        assert_eq!(Flags::<u8>::from_bits_checked(0xFF).unwrap().bits(), 0xFF);
+    }
+
+    #[test]
+    fn serialization() {
+        let flags = SectionFlags::COMPRESSED | SectionFlags::SIGNED;
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_le(flags).unwrap();
+        cursor.write_be(flags).unwrap();
+        cursor.write_be(Flags::<u32>::from_bits_retain(0x01020304)).unwrap();
+        assert_eq!(cursor.get_ref()[..4], [0x01, 0x01, 0x01, 0x01]);
+        cursor.set_position(0);
+        assert_eq!(cursor.read_le::<Flags<SectionFlags>>().unwrap(), flags);
+        assert_eq!(cursor.read_be::<Flags<SectionFlags>>().unwrap(), flags);
+        assert_eq!(cursor.read_le::<u32>().unwrap(), 0x04030201);
+        let mut buffer = crate::ByteBuf::new([0; 2]);
+        buffer.set_be(0, SectionFlags::SIGNED);
+        assert_eq!(buffer.get_le::<Flags<SectionFlags>>(0), SectionFlags::COMPRESSED);
+    }
+
+    #[test]
+    fn unknown_bits() {
+        let mut cursor = Cursor::new([0x09, 0x00, 0x00, 0x01]);
+        let retained = cursor.read_le::<Flags<SectionFlags>>().unwrap();
+        assert_eq!(retained.bits(), 0x9);
+        assert_eq!(Flags::<SectionFlags>::read_checked_be(&mut cursor).unwrap(), SectionFlags::COMPRESSED);
+        cursor.set_position(0);
+        let err = Flags::<SectionFlags>::read_checked_le(&mut cursor).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "unknown flag bits 0x8");
+    }
+}
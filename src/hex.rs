@@ -0,0 +1,268 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// The number of bytes encoded or decoded per call to the wrapped stream.
+const CHUNK: usize = 256;
+
+fn digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None
+    }
+}
+
+/// A [Write] wrapper which encodes every byte written as two lowercase hexadecimal digits,
+/// optionally starting a new line every fixed number of bytes.
+///
+/// *Each call to [write](Write::write) encodes the whole buffer and writes it entirely to the
+/// wrapped stream, so an error may leave a partially written chunk behind.*
+pub struct HexWriter<W> {
+    inner: W,
+    line: Option<usize>,
+    column: usize
+}
+
+impl<W> HexWriter<W> {
+    /// Creates a new [HexWriter] which writes a single line.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the [Write] to wrap.
+    pub fn new(inner: W) -> HexWriter<W> {
+        Self {
+            inner,
+            line: None,
+            column: 0
+        }
+    }
+
+    /// Creates a new [HexWriter] which writes a newline after every `bytes_per_line` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the [Write] to wrap.
+    /// * `bytes_per_line`: the number of bytes to encode on each line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_line` is 0.
+    pub fn with_line_width(inner: W, bytes_per_line: usize) -> HexWriter<W> {
+        assert!(bytes_per_line > 0, "bytes_per_line must not be 0");
+        Self {
+            inner,
+            line: Some(bytes_per_line),
+            column: 0
+        }
+    }
+
+    /// Returns a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped stream; bytes written through it are not
+    /// encoded.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Extracts the wrapped stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for HexWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        // Two digits per byte, and at most one newline per byte.
+        let mut out = [0; CHUNK * 3];
+        for chunk in buf.chunks(CHUNK) {
+            let mut len = 0;
+            for &byte in chunk {
+                out[len] = DIGITS[(byte >> 4) as usize];
+                out[len + 1] = DIGITS[(byte & 0xF) as usize];
+                len += 2;
+                if let Some(line) = self.line {
+                    self.column += 1;
+                    if self.column == line {
+                        out[len] = b'\n';
+                        len += 1;
+                        self.column = 0;
+                    }
+                }
+            }
+            self.inner.write_all(&out[..len])?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A [Read] wrapper which decodes hexadecimal digits from the wrapped stream, in either case,
+/// skipping ASCII whitespace.
+///
+/// This is the counterpart of [HexWriter]. Reading fails with an error of kind
+/// [InvalidData](ErrorKind::InvalidData) if a character is neither a hexadecimal digit nor ASCII
+/// whitespace, or if the stream ends after an odd number of digits.
+pub struct HexReader<R> {
+    inner: R,
+    high: Option<u8>,
+    offset: u64
+}
+
+impl<R> HexReader<R> {
+    /// Creates a new [HexReader].
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the [Read] to wrap.
+    pub fn new(inner: R) -> HexReader<R> {
+        Self {
+            inner,
+            high: None,
+            offset: 0
+        }
+    }
+
+    /// Returns a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped stream; bytes read through it are not decoded.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Extracts the wrapped stream.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for HexReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut raw = [0; CHUNK * 2];
+        let mut len = 0;
+        while len == 0 && !buf.is_empty() {
+            let count = self.inner.read(&mut raw[..(buf.len() * 2).min(CHUNK * 2)])?;
+            if count == 0 {
+                if self.high.is_some() {
+                    return Err(Error::new(ErrorKind::InvalidData, "odd number of hex digits"));
+                }
+                break;
+            }
+            for &c in &raw[..count] {
+                self.offset += 1;
+                if c.is_ascii_whitespace() {
+                    continue;
+                }
+                let value = digit(c).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("invalid hex character {:#04x} at offset {}", c, self.offset - 1)
+                    )
+                })?;
+                match self.high.take() {
+                    Some(high) => {
+                        buf[len] = high << 4 | value;
+                        len += 1;
+                    },
+                    None => self.high = Some(value)
+                }
+            }
+        }
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{copy, Cursor, ErrorKind, Read, Write};
+
+    use crate::testutil::{ShortReader, ShortWriter};
+    use crate::{HexReader, HexWriter, ReadExt, WriteExt};
+
+    #[test]
+    fn roundtrip() {
+        let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let mut writer = HexWriter::with_line_width(ShortWriter::new(Vec::new()), 16);
+        copy(&mut &data[..], &mut writer).unwrap();
+        writer.write_le(0x0102u16).unwrap();
+        writer.write_be(-2i32).unwrap();
+        let text = writer.into_inner().into_inner();
+        assert_eq!(text.len(), 1006 * 2 + 1006 / 16);
+        assert_eq!(&text[..33], b"000102030405060708090a0b0c0d0e0f\n");
+        assert!(text.ends_with(b"0201fffffffe"));
+
+        let mut reader = HexReader::new(ShortReader::new(&text[..]));
+        let mut decoded = vec![0; 1000];
+        reader.read_exact(&mut decoded).unwrap();
+        assert_eq!(decoded, data);
+        assert_eq!(reader.read_le::<u16>().unwrap(), 0x0102);
+        assert_eq!(reader.read_be::<i32>().unwrap(), -2);
+        assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+    }
+
+    #[test]
+    fn single_line() {
+        let mut writer = HexWriter::new(Vec::new());
+        writer.write_all(&[0xDE, 0xAD]).unwrap();
+        writer.write_all(&[0xBE, 0xEF]).unwrap();
+        assert_eq!(writer.get_ref(), b"deadbeef");
+    }
+
+    #[test]
+    fn whitespace() {
+        let text = b"  DE ad\r\n\tb\ne EF\n\n";
+        let mut decoded = Vec::new();
+        copy(&mut HexReader::new(Cursor::new(text)), &mut decoded).unwrap();
+        assert_eq!(decoded, [0xDE, 0xAD, 0xBE, 0xEF]);
+    }
+
+    #[test]
+    fn errors() {
+        let mut decoded = Vec::new();
+        let err = HexReader::new(&b"0102\n030"[..]).read_to_end(&mut decoded).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "odd number of hex digits");
+        assert_eq!(decoded, [1, 2, 3]);
+        let err = HexReader::new(&b"01 0g"[..]).read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "invalid hex character 0x67 at offset 4");
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::marker::PhantomData;
+
+use crate::{Endian, ReadBytes, WriteBytes};
+
+/// Decodes a fixed size value from bytes arriving in arbitrary chunks, such as the partial reads
+/// of a non-blocking socket.
+///
+/// *At most [SIZE](crate::FixedSize::SIZE) bytes are buffered; once a value has been polled the
+/// decoder starts over with the next one.*
+#[derive(Debug, Clone)]
+pub struct Decoder<T: ReadBytes> {
+    buffer: Vec<u8>,
+    endian: Endian,
+    marker: PhantomData<T>
+}
+
+impl<T: ReadBytes> Decoder<T> {
+    /// Creates a new decoder.
+    ///
+    /// # Arguments
+    ///
+    /// * `endian`: the byte order of the value to decode.
+    ///
+    /// returns: Decoder<T>
+    pub fn new(endian: Endian) -> Self {
+        Self {
+            buffer: Vec::with_capacity(T::SIZE),
+            endian,
+            marker: PhantomData
+        }
+    }
+
+    /// Returns the number of bytes still required before a value can be polled.
+    pub fn needed(&self) -> usize {
+        T::SIZE - self.buffer.len()
+    }
+
+    /// Buffers the start of `bytes`, up to the number of bytes still needed.
+    ///
+    /// returns: the number of bytes consumed; the remaining bytes belong to whatever follows the
+    /// value.
+    pub fn feed(&mut self, bytes: &[u8]) -> usize {
+        let len = self.needed().min(bytes.len());
+        self.buffer.extend_from_slice(&bytes[..len]);
+        len
+    }
+
+    /// Returns the decoded value if enough bytes have been fed, resetting the decoder.
+    pub fn poll(&mut self) -> Option<T> {
+        if self.needed() > 0 {
+            return None;
+        }
+        let value = match self.endian {
+            Endian::Little => T::read_bytes_le(&self.buffer),
+            Endian::Big => T::read_bytes_be(&self.buffer)
+        };
+        self.buffer.clear();
+        Some(value)
+    }
+}
+
+/// Encodes a fixed size value and hands its bytes out in chunks sized by the caller, such as the
+/// free space of a non-blocking socket.
+#[derive(Debug, Clone)]
+pub struct Encoder {
+    buffer: Vec<u8>,
+    pos: usize
+}
+
+impl Encoder {
+    /// Creates a new encoder holding the bytes of a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the value to encode.
+    /// * `endian`: the byte order to encode the value in.
+    ///
+    /// returns: Encoder
+    pub fn new<T: WriteBytes>(value: &T, endian: Endian) -> Self {
+        let mut buffer = vec![0; T::SIZE];
+        match endian {
+            Endian::Little => value.write_bytes_le(&mut buffer),
+            Endian::Big => value.write_bytes_be(&mut buffer)
+        }
+        Self {
+            buffer,
+            pos: 0
+        }
+    }
+
+    /// Returns the number of bytes which have not been emitted yet.
+    pub fn remaining(&self) -> usize {
+        self.buffer.len() - self.pos
+    }
+
+    /// Returns true if every byte of the value has been emitted.
+    pub fn is_done(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Copies the next bytes of the value into `out`, up to its length.
+    ///
+    /// returns: the number of bytes copied.
+    pub fn emit(&mut self, out: &mut [u8]) -> usize {
+        let len = self.remaining().min(out.len());
+        out[..len].copy_from_slice(&self.buffer[self.pos..self.pos + len]);
+        self.pos += len;
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Decoder, Encoder, Endian};
+
+    const VALUE: u64 = 0x0102030405060708;
+
+    #[test]
+    fn byte_at_a_time() {
+        let mut decoder = Decoder::<u64>::new(Endian::Big);
+        for (i, byte) in VALUE.to_be_bytes().iter().enumerate() {
+            assert_eq!(decoder.poll(), None);
+            assert_eq!(decoder.needed(), 8 - i);
+            assert_eq!(decoder.feed(std::slice::from_ref(byte)), 1);
+        }
+        assert_eq!(decoder.needed(), 0);
+        assert_eq!(decoder.poll(), Some(VALUE));
+        assert_eq!(decoder.needed(), 8);
+        assert_eq!(decoder.poll(), None);
+    }
+
+    #[test]
+    fn uneven_chunks() {
+        let mut encoder = Encoder::new(&VALUE, Endian::Little);
+        let mut decoder = Decoder::<u64>::new(Endian::Little);
+        for size in [3, 1, 0, 5] {
+            let mut chunk = vec![0; size];
+            let len = encoder.emit(&mut chunk);
+            assert_eq!(decoder.feed(&chunk[..len]), len);
+        }
+        assert!(encoder.is_done());
+        assert_eq!(encoder.emit(&mut [0; 4]), 0);
+        assert_eq!(decoder.poll(), Some(VALUE));
+    }
+
+    #[test]
+    fn trailing_bytes() {
+        let bytes = [VALUE.to_le_bytes(), [0xAA; 8]].concat();
+        let mut decoder = Decoder::<u64>::new(Endian::Little);
+        assert_eq!(decoder.feed(&bytes[..5]), 5);
+        assert_eq!(decoder.feed(&bytes[5..12]), 3);
+        assert_eq!(decoder.feed(&bytes[8..]), 0);
+        assert_eq!(decoder.poll(), Some(VALUE));
+        assert_eq!(decoder.feed(&bytes[8..]), 8);
+        assert_eq!(decoder.poll(), Some(0xAAAAAAAAAAAAAAAA));
+    }
+}
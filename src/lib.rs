@@ -32,23 +32,270 @@
 
 //! This library is a byte utility which provides simplified APIs over to_le_bytes, from_le_bytes, to_be_bytes, from_be_bytes and a java-like ByteBuf.
 //! The library also works in no_std mode.
+//!
+//! # Fixed size types
+//!
+//! [WriteBytes] and [ReadBytes] require [FixedSize], which carries the number of bytes a value
+//! occupies as [SIZE](FixedSize::SIZE). This is a breaking change from 0.7: types outside this
+//! crate implementing [WriteBytes] or [ReadBytes] must also implement [FixedSize].
+//!
+//! ```
+//! use bytesutil::{FixedSize, ReadBytes, WriteBytes};
+//!
+//! struct Rgb([u8; 3]);
+//!
+//! impl FixedSize for Rgb {
+//!     const SIZE: usize = 3;
+//! }
+//!
+//! impl WriteBytes for Rgb {
+//!     fn write_bytes_le(&self, bytes: &mut [u8]) {
+//!         bytes[..3].copy_from_slice(&self.0);
+//!     }
+//!
+//!     fn write_bytes_be(&self, bytes: &mut [u8]) {
+//!         self.write_bytes_le(bytes);
+//!     }
+//! }
+//!
+//! impl ReadBytes for Rgb {
+//!     fn read_bytes_le(bytes: &[u8]) -> Self {
+//!         Rgb([bytes[0], bytes[1], bytes[2]])
+//!     }
+//!
+//!     fn read_bytes_be(bytes: &[u8]) -> Self {
+//!         Self::read_bytes_le(bytes)
+//!     }
+//! }
+//!
+//! let mut bytes = [0; Rgb::SIZE];
+//! Rgb([1, 2, 3]).write_bytes_be(&mut bytes);
+//! assert_eq!(Rgb::read_bytes_be(&bytes).0, [1, 2, 3]);
+//! ```
+//!
+//! # Debug checks
+//!
+//! The `debug-checks` feature makes the field accessors of [ByteBuf] validate their offset and
+//! size before touching the buffer, panicking with the offset, the required size, the buffer
+//! length and the type name of the field. The checks only run in builds with debug assertions,
+//! so release builds are unaffected.
+//!
+//! # Code size
+//!
+//! Every method comes in a little endian and a big endian variant. Generic variants are only
+//! instantiated when called and unused ones are discarded at link time, so firmware which only
+//! reads and writes little endian values carries no big endian code: no feature is needed to
+//! remove it.
+//!
+//! # Timestamps
+//!
+//! The `time` and `chrono` features implement the serialization traits for
+//! `time::OffsetDateTime` and `chrono::DateTime<Utc>`. Both use the same 12 bytes layout: an
+//! [i64] number of seconds since the unix epoch followed by a [u32] number of nanoseconds, in UTC.
+//!
+//! # Math types
+//!
+//! The `glam` feature implements the serialization traits for the `f32` and `f64` vector,
+//! quaternion and matrix types of glam, as tightly packed components in declaration order.
+//! Matrices are stored in column-major order and padding lanes are never written.
+//!
+//! # defmt
+//!
+//! The `defmt` feature implements `defmt::Format` for [ByteBuf] (its length followed by at most
+//! its first 32 bytes in hexadecimal), [Endian], [BoundsError] and [LengthError], without
+//! requiring std.
+//!
+//! # bytes
+//!
+//! The `bytes` feature adds constructors and conversions for a [ByteBuf] backed by `bytes::Bytes`
+//! or `bytes::BytesMut`, such as `ByteBuf::from_bytes_mut` and `ByteBuf::freeze`, so that fields
+//! can be read and written directly in buffers of the tokio ecosystem.
+//!
+//! # Fuzzing
+//!
+//! The `arbitrary` feature implements `arbitrary::Arbitrary` for [ByteBuf], [Endian], the
+//! varint, big integer, canonical float and fixed-point types so that downstream parsers can be
+//! fuzzed with structured input. Combined with `test-util`, `testutil::fuzz_roundtrip` checks
+//! that a fixed size type decodes and re-encodes the same way in both byte orders.
+
+#[macro_use]
+mod macros;
 
 mod bytes;
 
+mod endian;
+
+mod varint;
+
+mod bigint;
+
+mod canonical;
+
+mod fixed;
+
+mod composite;
+
 #[cfg(feature = "std")]
 mod traits;
 
+#[cfg(feature = "std")]
+mod error;
+
 mod buffer;
 
+mod byte_ring;
+
+mod aligned;
+
+mod cursor;
+
+mod flags;
+
+mod view2d;
+
+#[cfg(any(feature = "time", feature = "chrono"))]
+mod timestamp;
+
+#[cfg(feature = "glam")]
+mod math;
+
 #[cfg(feature = "std")]
 mod combined_io;
 
+#[cfg(feature = "std")]
+mod net;
+
+#[cfg(feature = "std")]
+mod peek;
+
+#[cfg(feature = "std")]
+mod probe;
+
+#[cfg(feature = "std")]
+mod prefix;
+
+#[cfg(feature = "std")]
+mod checksum;
+
+#[cfg(feature = "std")]
+mod swap;
+
+#[cfg(feature = "std")]
+mod tracking;
+
+#[cfg(feature = "std")]
+mod window;
+
+#[cfg(feature = "std")]
+mod transaction;
+
+#[cfg(feature = "std")]
+mod verify;
+
+#[cfg(feature = "std")]
+mod sparse;
+
+#[cfg(feature = "std")]
+mod base64;
+
+#[cfg(feature = "std")]
+mod hex;
+
+#[cfg(feature = "std")]
+mod codec;
+
+#[cfg(feature = "std")]
+mod incremental;
+
+#[cfg(feature = "std")]
+mod ring;
+
+#[cfg(feature = "std")]
+mod file_at;
+
 pub use bytes::*;
 
+pub use endian::*;
+
+pub use varint::*;
+
+pub use bigint::*;
+
+pub use canonical::*;
+
+pub use fixed::*;
+
 #[cfg(feature = "std")]
 pub use traits::*;
 
+#[cfg(feature = "std")]
+pub use error::*;
+
 pub use buffer::*;
 
+pub use byte_ring::*;
+
+pub use aligned::*;
+
+pub use cursor::*;
+
+pub use flags::*;
+
+pub use view2d::*;
+
 #[cfg(feature = "std")]
 pub use combined_io::*;
+
+#[cfg(feature = "std")]
+pub use peek::*;
+
+#[cfg(feature = "std")]
+pub use probe::*;
+
+#[cfg(feature = "std")]
+pub use prefix::*;
+
+#[cfg(feature = "std")]
+pub use checksum::*;
+
+#[cfg(feature = "std")]
+pub use swap::*;
+
+#[cfg(feature = "std")]
+pub use tracking::*;
+
+#[cfg(feature = "std")]
+pub use window::*;
+
+#[cfg(feature = "std")]
+pub use transaction::*;
+
+#[cfg(feature = "std")]
+pub use verify::*;
+
+#[cfg(feature = "std")]
+pub use sparse::*;
+
+#[cfg(feature = "std")]
+pub use base64::*;
+
+#[cfg(feature = "std")]
+pub use hex::*;
+
+#[cfg(feature = "std")]
+pub use codec::*;
+
+#[cfg(feature = "std")]
+pub use incremental::*;
+
+#[cfg(feature = "std")]
+pub use ring::*;
+
+#[cfg(feature = "std")]
+pub use file_at::*;
+
+#[cfg(feature = "std")]
+pub mod schema;
+
+#[cfg(any(feature = "test-util", all(test, feature = "std")))]
+pub mod testutil;
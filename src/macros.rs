@@ -0,0 +1,611 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+/// Implements the byte and IO traits for a newtype by delegating to its inner field.
+///
+/// The newtype must be a tuple struct with a single field whose type already implements the
+/// traits. Values are written through the `.0` accessor and read back by constructing the
+/// newtype from the decoded inner value.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::{impl_newtype_bytes, ReadBytes, WriteBytes};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Offset(u64);
+///
+/// impl_newtype_bytes!(Offset => u64);
+///
+/// let mut bytes = [0; 8];
+/// Offset(42).write_bytes_le(&mut bytes);
+/// assert_eq!(Offset::read_bytes_le(&bytes), Offset(42));
+/// ```
+#[macro_export]
+macro_rules! impl_newtype_bytes {
+    ($($name: ident => $inner: ty),*) => {
+        $(
+            impl $crate::FixedSize for $name {
+                const SIZE: usize = <$inner as $crate::FixedSize>::SIZE;
+            }
+
+            impl $crate::ByteSize for $name {
+                fn byte_size(&self) -> usize {
+                    <$inner as $crate::ByteSize>::byte_size(&self.0)
+                }
+            }
+
+            impl $crate::WriteBytes for $name {
+                fn write_bytes_le(&self, bytes: &mut [u8]) {
+                    <$inner as $crate::WriteBytes>::write_bytes_le(&self.0, bytes)
+                }
+
+                fn write_bytes_be(&self, bytes: &mut [u8]) {
+                    <$inner as $crate::WriteBytes>::write_bytes_be(&self.0, bytes)
+                }
+            }
+
+            impl $crate::ReadBytes for $name {
+                fn read_bytes_le(bytes: &[u8]) -> Self {
+                    $name(<$inner as $crate::ReadBytes>::read_bytes_le(bytes))
+                }
+
+                fn read_bytes_be(bytes: &[u8]) -> Self {
+                    $name(<$inner as $crate::ReadBytes>::read_bytes_be(bytes))
+                }
+
+                fn read_bytes_le_counted(bytes: &[u8]) -> (Self, usize) {
+                    let (value, len) = <$inner as $crate::ReadBytes>::read_bytes_le_counted(bytes);
+                    ($name(value), len)
+                }
+
+                fn read_bytes_be_counted(bytes: &[u8]) -> (Self, usize) {
+                    let (value, len) = <$inner as $crate::ReadBytes>::read_bytes_be_counted(bytes);
+                    ($name(value), len)
+                }
+            }
+
+            $crate::__impl_newtype_io!($name => $inner);
+        )*
+    };
+}
+
+/// Implements the byte and IO traits for a `#[repr(transparent)]` wrapper by reinterpreting it as
+/// its inner field.
+///
+/// Unlike [impl_newtype_bytes], the field does not need to be accessible as `.0` nor the wrapper
+/// constructible from it, and the slice readers of [ReadFrom](crate::ReadFrom) are forwarded as
+/// a whole, so a wrapper over a [Pod](crate::Pod) type keeps its single pass bulk reads.
+///
+/// *The wrapper must implement [TransparentWrapper](crate::TransparentWrapper), whose `unsafe
+/// impl` states that it is `#[repr(transparent)]` over its field and that every value of the
+/// field is a valid wrapper. The size and the alignment of both types are also checked at
+/// compile time.*
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::{impl_transparent_bytes, ReadBytes, TransparentWrapper, WriteBytes};
+///
+/// #[derive(Debug, PartialEq)]
+/// #[repr(transparent)]
+/// struct Meters {
+///     value: f64
+/// }
+///
+/// // SAFETY: Meters is repr(transparent) over an f64 and accepts any f64.
+/// unsafe impl TransparentWrapper for Meters {
+///     type Inner = f64;
+/// }
+///
+/// impl_transparent_bytes!(Meters);
+///
+/// let mut bytes = [0; 8];
+/// Meters { value: 1.5 }.write_bytes_be(&mut bytes);
+/// assert_eq!(bytes, 1.5f64.to_be_bytes());
+/// assert_eq!(Meters::read_bytes_be(&bytes), Meters { value: 1.5 });
+/// ```
+///
+/// A wrapper which has not been declared transparent is rejected, so that a type such as
+/// `struct Flags(bool, bool)` cannot be read from arbitrary bytes:
+///
+/// ```compile_fail
+/// use bytesutil::impl_transparent_bytes;
+///
+/// struct Flags(bool, bool);
+///
+/// impl_transparent_bytes!(Flags);
+/// ```
+#[macro_export]
+macro_rules! impl_transparent_bytes {
+    ($($name: ident),*) => {
+        $(
+            const _: () = assert!(
+                core::mem::size_of::<$name>() == core::mem::size_of::<<$name as $crate::TransparentWrapper>::Inner>()
+                    && core::mem::align_of::<$name>() == core::mem::align_of::<<$name as $crate::TransparentWrapper>::Inner>(),
+                concat!(stringify!($name), " must be a repr(transparent) wrapper of its inner field")
+            );
+
+            impl $crate::FixedSize for $name {
+                const SIZE: usize = <<$name as $crate::TransparentWrapper>::Inner as $crate::FixedSize>::SIZE;
+            }
+
+            impl $crate::ByteSize for $name {
+                fn byte_size(&self) -> usize {
+                    $crate::ByteSize::byte_size($crate::__Transparent::__as_inner(self))
+                }
+            }
+
+            impl $crate::WriteBytes for $name {
+                fn write_bytes_le(&self, bytes: &mut [u8]) {
+                    $crate::WriteBytes::write_bytes_le($crate::__Transparent::__as_inner(self), bytes)
+                }
+
+                fn write_bytes_be(&self, bytes: &mut [u8]) {
+                    $crate::WriteBytes::write_bytes_be($crate::__Transparent::__as_inner(self), bytes)
+                }
+            }
+
+            impl $crate::ReadBytes for $name {
+                fn read_bytes_le(bytes: &[u8]) -> Self {
+                    $crate::__Transparent::__from_inner($crate::ReadBytes::read_bytes_le(bytes))
+                }
+
+                fn read_bytes_be(bytes: &[u8]) -> Self {
+                    $crate::__Transparent::__from_inner($crate::ReadBytes::read_bytes_be(bytes))
+                }
+
+                fn read_bytes_le_counted(bytes: &[u8]) -> (Self, usize) {
+                    let (value, len) = $crate::ReadBytes::read_bytes_le_counted(bytes);
+                    ($crate::__Transparent::__from_inner(value), len)
+                }
+
+                fn read_bytes_be_counted(bytes: &[u8]) -> (Self, usize) {
+                    let (value, len) = $crate::ReadBytes::read_bytes_be_counted(bytes);
+                    ($crate::__Transparent::__from_inner(value), len)
+                }
+            }
+
+            $crate::__impl_transparent_io!($name);
+        )*
+    };
+}
+
+/// Implements the byte and IO traits for a fieldless enum, stored as a discriminant of the given
+/// integer type.
+///
+/// Every listed variant is mapped to its discriminant value, independently of the discriminants
+/// of the enum in memory. Reading an unknown discriminant through [ReadFrom](crate::ReadFrom)
+/// fails with an error of kind [InvalidData](std::io::ErrorKind::InvalidData); as this cannot
+/// be reported by [ReadBytes](crate::ReadBytes), it is only implemented with a fallback.
+///
+/// *A last variant marked `#[fallback]` must hold the discriminant type, as in `Unknown(u32)`.
+/// Unknown discriminants are then read into it and written back unchanged, so that forward
+/// compatible parsers preserve them.*
+///
+/// The fallback should only hold unknown discriminants. A fallback holding the discriminant of
+/// a listed variant, such as `Unknown(0x10)` below, is written as that discriminant and thus
+/// reads back as the listed variant, `Strings`.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::{impl_enum_bytes, ReadFrom, WriteTo};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Section {
+///     Strings,
+///     Symbols,
+///     Unknown(u32)
+/// }
+///
+/// impl_enum_bytes!(Section: u32 {
+///     Strings = 0x10,
+///     Symbols = 0x20,
+///     #[fallback] Unknown
+/// });
+///
+/// let mut bytes = Vec::new();
+/// Section::Symbols.write_to_be(&mut bytes).unwrap();
+/// assert_eq!(bytes, [0, 0, 0, 0x20]);
+/// assert_eq!(Section::read_from_le(&[0x30, 0, 0, 0][..]).unwrap(), Section::Unknown(0x30));
+/// ```
+#[macro_export]
+macro_rules! impl_enum_bytes {
+    ($name: ident: $repr: ty { $($variant: ident = $value: expr),+ $(, #[fallback] $fallback: ident)? $(,)? }) => {
+        impl $name {
+            #[allow(dead_code)]
+            fn __to_discriminant(&self) -> $repr {
+                match self {
+                    $($name::$variant => $value,)+
+                    $($name::$fallback(value) => *value)?
+                }
+            }
+
+            #[allow(dead_code)]
+            fn __from_discriminant(value: $repr) -> Option<Self> {
+                $(
+                    if value == $value {
+                        return Some($name::$variant);
+                    }
+                )+
+                $(return Some($name::$fallback(value));)?
+                #[allow(unreachable_code)]
+                None
+            }
+        }
+
+        impl $crate::FixedSize for $name {
+            const SIZE: usize = <$repr as $crate::FixedSize>::SIZE;
+        }
+
+        impl $crate::ByteSize for $name {
+            fn byte_size(&self) -> usize {
+                <$repr as $crate::FixedSize>::SIZE
+            }
+        }
+
+        impl $crate::WriteBytes for $name {
+            fn write_bytes_le(&self, bytes: &mut [u8]) {
+                <$repr as $crate::WriteBytes>::write_bytes_le(&self.__to_discriminant(), bytes)
+            }
+
+            fn write_bytes_be(&self, bytes: &mut [u8]) {
+                <$repr as $crate::WriteBytes>::write_bytes_be(&self.__to_discriminant(), bytes)
+            }
+        }
+
+        $(
+            impl $crate::ReadBytes for $name {
+                fn read_bytes_le(bytes: &[u8]) -> Self {
+                    let value = <$repr as $crate::ReadBytes>::read_bytes_le(bytes);
+                    $name::__from_discriminant(value).unwrap_or($name::$fallback(value))
+                }
+
+                fn read_bytes_be(bytes: &[u8]) -> Self {
+                    let value = <$repr as $crate::ReadBytes>::read_bytes_be(bytes);
+                    $name::__from_discriminant(value).unwrap_or($name::$fallback(value))
+                }
+            }
+        )?
+
+        $crate::__impl_enum_io!($name: $repr);
+    };
+}
+
+/// Declares a set of named flags for use with [Flags](crate::Flags).
+///
+/// This declares a unit struct implementing [FlagSet](crate::FlagSet) whose known flags are the
+/// given constants, each exposed as an associated constant of type `Flags<Name>`.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::{flags, Flags};
+///
+/// flags! {
+///     pub struct Access: u8 {
+///         const READ = 0x1;
+///         const WRITE = 0x2;
+///     }
+/// }
+///
+/// let access: Flags<Access> = Access::READ | Access::WRITE;
+/// assert_eq!(access, Flags::all());
+/// ```
+#[macro_export]
+macro_rules! flags {
+    ($(
+        $(#[$meta: meta])*
+        $vis: vis struct $name: ident: $bits: ty {
+            $(
+                $(#[$flag_meta: meta])*
+                const $flag: ident = $value: expr;
+            )*
+        }
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            $vis struct $name;
+
+            impl $crate::FlagSet for $name {
+                type Bits = $bits;
+                const ALL: $bits = 0 $(| $value)*;
+            }
+
+            #[allow(dead_code)]
+            impl $name {
+                $(
+                    $(#[$flag_meta])*
+                    pub const $flag: $crate::Flags<$name> = $crate::Flags::from_bits_retain($value);
+                )*
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_newtype_io {
+    ($name: ident => $inner: ty) => {
+        impl $crate::WriteTo for $name {
+            fn write_to_le<T: std::io::Write>(&self, dst: T) -> std::io::Result<()> {
+                <$inner as $crate::WriteTo>::write_to_le(&self.0, dst)
+            }
+
+            fn write_to_be<T: std::io::Write>(&self, dst: T) -> std::io::Result<()> {
+                <$inner as $crate::WriteTo>::write_to_be(&self.0, dst)
+            }
+        }
+
+        impl $crate::ReadFrom for $name {
+            fn read_from_le<T: std::io::Read>(src: T) -> std::io::Result<Self> {
+                <$inner as $crate::ReadFrom>::read_from_le(src).map($name)
+            }
+
+            fn read_from_be<T: std::io::Read>(src: T) -> std::io::Result<Self> {
+                <$inner as $crate::ReadFrom>::read_from_be(src).map($name)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_newtype_io {
+    ($name: ident => $inner: ty) => {};
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_transparent_io {
+    ($name: ident) => {
+        impl $crate::WriteTo for $name {
+            fn write_to_le<T: std::io::Write>(&self, dst: T) -> std::io::Result<()> {
+                $crate::WriteTo::write_to_le($crate::__Transparent::__as_inner(self), dst)
+            }
+
+            fn write_to_be<T: std::io::Write>(&self, dst: T) -> std::io::Result<()> {
+                $crate::WriteTo::write_to_be($crate::__Transparent::__as_inner(self), dst)
+            }
+        }
+
+        impl $crate::ReadFrom for $name {
+            fn read_from_le<T: std::io::Read>(src: T) -> std::io::Result<Self> {
+                $crate::ReadFrom::read_from_le(src).map($crate::__Transparent::__from_inner)
+            }
+
+            fn read_from_be<T: std::io::Read>(src: T) -> std::io::Result<Self> {
+                $crate::ReadFrom::read_from_be(src).map($crate::__Transparent::__from_inner)
+            }
+
+            fn read_slice_from_le<T: std::io::Read>(src: T, out: &mut [Self]) -> std::io::Result<usize> {
+                <<$name as $crate::TransparentWrapper>::Inner as $crate::ReadFrom>::read_slice_from_le(
+                    src,
+                    $crate::__Transparent::__as_inner_slice(out)
+                )
+            }
+
+            fn read_slice_from_be<T: std::io::Read>(src: T, out: &mut [Self]) -> std::io::Result<usize> {
+                <<$name as $crate::TransparentWrapper>::Inner as $crate::ReadFrom>::read_slice_from_be(
+                    src,
+                    $crate::__Transparent::__as_inner_slice(out)
+                )
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_transparent_io {
+    ($name: ident) => {};
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_enum_io {
+    ($name: ident: $repr: ty) => {
+        impl $crate::WriteTo for $name {
+            fn write_to_le<T: std::io::Write>(&self, dst: T) -> std::io::Result<()> {
+                <$repr as $crate::WriteTo>::write_to_le(&self.__to_discriminant(), dst)
+            }
+
+            fn write_to_be<T: std::io::Write>(&self, dst: T) -> std::io::Result<()> {
+                <$repr as $crate::WriteTo>::write_to_be(&self.__to_discriminant(), dst)
+            }
+        }
+
+        impl $name {
+            fn __check_discriminant(value: $repr) -> std::io::Result<Self> {
+                $name::__from_discriminant(value).ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("unknown {} discriminant {:#x}", stringify!($name), value)
+                    )
+                })
+            }
+        }
+
+        impl $crate::ReadFrom for $name {
+            fn read_from_le<T: std::io::Read>(src: T) -> std::io::Result<Self> {
+                <$repr as $crate::ReadFrom>::read_from_le(src).and_then($name::__check_discriminant)
+            }
+
+            fn read_from_be<T: std::io::Read>(src: T) -> std::io::Result<Self> {
+                <$repr as $crate::ReadFrom>::read_from_be(src).and_then($name::__check_discriminant)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_enum_io {
+    ($name: ident: $repr: ty) => {};
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{ByteBuf, FixedSize, ReadExt, WriteExt};
+
+    #[derive(Debug, PartialEq)]
+    struct Offset(u64);
+
+    #[derive(Debug, PartialEq)]
+    struct Flag(bool);
+
+    impl_newtype_bytes!(Offset => u64, Flag => bool);
+
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    #[repr(transparent)]
+    struct Sample {
+        value: i32
+    }
+
+    // SAFETY: Sample is repr(transparent) over an i32 and accepts any i32.
+    unsafe impl crate::TransparentWrapper for Sample {
+        type Inner = i32;
+    }
+
+    impl_transparent_bytes!(Sample);
+
+    #[derive(Debug, PartialEq)]
+    enum Opcode {
+        Nop,
+        Jump,
+        Call
+    }
+
+    impl_enum_bytes!(Opcode: u16 {
+        Nop = 0,
+        Jump = 0x0100,
+        Call = 0x0200
+    });
+
+    #[derive(Debug, PartialEq)]
+    enum Chunk {
+        Header,
+        Data,
+        Unknown(u32)
+    }
+
+    impl_enum_bytes!(Chunk: u32 {
+        Header = 0x48445200,
+        Data = 0x44415400,
+        #[fallback] Unknown,
+    });
+
+    #[test]
+    fn bytes() {
+        let mut buffer = ByteBuf::new([0; 9]);
+        buffer.set_be(0, Offset(0x0102030405060708)).set_le(8, Flag(true));
+        assert_eq!(buffer.get_be::<u64>(0), 0x0102030405060708);
+        assert_eq!(buffer.get_be::<Offset>(0), Offset(0x0102030405060708));
+        assert_eq!(buffer.get_le::<Flag>(8), Flag(true));
+        assert_eq!(Offset::SIZE, 8);
+    }
+
+    #[test]
+    fn io() {
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_le(Offset(42)).unwrap();
+        cursor.write_be(Offset(42)).unwrap();
+        cursor.set_position(0);
+        assert_eq!(cursor.read_le::<Offset>().unwrap(), Offset(42));
+        assert_eq!(cursor.read_be::<u64>().unwrap(), 42);
+    }
+
+    #[test]
+    fn transparent() {
+        let values = [1, -2, 0x01020304, i32::MIN];
+        let samples = values.map(|value| Sample { value });
+        let mut cursor = Cursor::new(Vec::new());
+        let mut expected = Cursor::new(Vec::new());
+        for (sample, value) in samples.iter().zip(values) {
+            cursor.write_le(sample).unwrap();
+            cursor.write_be(sample).unwrap();
+            expected.write_le(value).unwrap();
+            expected.write_be(value).unwrap();
+        }
+        assert_eq!(cursor.get_ref(), expected.get_ref());
+        let mut buffer = ByteBuf::new([0; 4]);
+        buffer.set_be(0, Sample { value: 7 });
+        assert_eq!(buffer.get_be::<i32>(0), 7);
+        assert_eq!(buffer.get_be::<Sample>(0), Sample { value: 7 });
+        assert_eq!(Sample::SIZE, 4);
+
+        let mut cursor = Cursor::new(values.iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<u8>>());
+        let mut out = [Sample { value: 0 }; 4];
+        cursor.read_le_into(&mut out).unwrap();
+        assert_eq!(out, samples);
+        cursor.set_position(8);
+        assert_eq!(cursor.read_le::<Sample>().unwrap(), samples[2]);
+    }
+
+    #[test]
+    fn enums() {
+        use std::io::ErrorKind;
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_be(Opcode::Jump).unwrap();
+        cursor.write_le(Opcode::Call).unwrap();
+        cursor.write_be(0x0300u16).unwrap();
+        assert_eq!(cursor.get_ref(), &[1, 0, 0, 2, 3, 0]);
+        cursor.set_position(0);
+        assert_eq!(cursor.read_be::<Opcode>().unwrap(), Opcode::Jump);
+        assert_eq!(cursor.read_le::<Opcode>().unwrap(), Opcode::Call);
+        let err = cursor.read_be::<Opcode>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains("unknown Opcode discriminant 0x300"));
+        assert_eq!(Opcode::SIZE, 2);
+        assert_eq!(ByteBuf::new([0; 2]).set_le(0, Opcode::Nop).get_le::<u16>(0), 0);
+
+        let mut buffer = ByteBuf::new([0; 12]);
+        buffer.set_be(0, Chunk::Header).set_le(4, Chunk::Data).set_be(8, Chunk::Unknown(7));
+        assert_eq!(buffer.get_be::<u32>(0), 0x48445200);
+        assert_eq!(buffer.get_le::<Chunk>(4), Chunk::Data);
+        assert_eq!(buffer.get_be::<Chunk>(8), Chunk::Unknown(7));
+        let mut cursor = Cursor::new(buffer.as_ref());
+        assert_eq!(cursor.read_be::<Chunk>().unwrap(), Chunk::Header);
+        assert_eq!(cursor.read_be::<Chunk>().unwrap(), Chunk::Unknown(0x00544144));
+        assert_eq!(cursor.read_be::<Chunk>().unwrap(), Chunk::Unknown(7));
+        // A fallback holding a known discriminant does not round-trip.
+        buffer.set_le(0, Chunk::Unknown(0x44415400));
+        assert_eq!(buffer.get_le::<Chunk>(0), Chunk::Data);
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Vectors, quaternions and matrices are encoded as a tightly packed sequence of their
+//! components, in declaration order (`x`, `y`, `z`, `w`). Matrices are encoded column by
+//! column. Padding components, such as the hidden fourth lane of [Vec3A](glam::Vec3A), are never
+//! written.
+
+use crate::{FixedSize, ReadBytes, WriteBytes};
+
+macro_rules! impl_math {
+    ($($t: ty => [$c: ty; $n: literal], |$v: ident| $to_array: expr, |$a: ident| $from_array: expr;)*) => {
+        $(
+            impl FixedSize for $t {
+                const SIZE: usize = $n * <$c>::SIZE;
+            }
+
+            impl crate::ByteSize for $t {
+                fn byte_size(&self) -> usize {
+                    <$t>::SIZE
+                }
+            }
+
+            impl WriteBytes for $t {
+                fn write_bytes_le(&self, bytes: &mut [u8]) {
+                    let $v = self;
+                    let components: [$c; $n] = $to_array;
+                    for (i, component) in components.iter().enumerate() {
+                        component.write_bytes_le(&mut bytes[i * <$c>::SIZE..]);
+                    }
+                }
+
+                fn write_bytes_be(&self, bytes: &mut [u8]) {
+                    let $v = self;
+                    let components: [$c; $n] = $to_array;
+                    for (i, component) in components.iter().enumerate() {
+                        component.write_bytes_be(&mut bytes[i * <$c>::SIZE..]);
+                    }
+                }
+            }
+
+            impl ReadBytes for $t {
+                fn read_bytes_le(bytes: &[u8]) -> Self {
+                    let $a: [$c; $n] = core::array::from_fn(|i| <$c>::read_bytes_le(&bytes[i * <$c>::SIZE..]));
+                    $from_array
+                }
+
+                fn read_bytes_be(bytes: &[u8]) -> Self {
+                    let $a: [$c; $n] = core::array::from_fn(|i| <$c>::read_bytes_be(&bytes[i * <$c>::SIZE..]));
+                    $from_array
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl crate::WriteTo for $t {
+                fn write_to_le<W: std::io::Write>(&self, mut dst: W) -> std::io::Result<()> {
+                    let mut block = [0; $n * core::mem::size_of::<$c>()];
+                    self.write_bytes_le(&mut block);
+                    dst.write_all(&block)
+                }
+
+                fn write_to_be<W: std::io::Write>(&self, mut dst: W) -> std::io::Result<()> {
+                    let mut block = [0; $n * core::mem::size_of::<$c>()];
+                    self.write_bytes_be(&mut block);
+                    dst.write_all(&block)
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl crate::ReadFrom for $t {
+                fn read_from_le<R: std::io::Read>(mut src: R) -> std::io::Result<Self> {
+                    let mut block = [0; $n * core::mem::size_of::<$c>()];
+                    crate::error::read_exact_for::<Self, _>(&mut src, &mut block)?;
+                    Ok(Self::read_bytes_le(&block))
+                }
+
+                fn read_from_be<R: std::io::Read>(mut src: R) -> std::io::Result<Self> {
+                    let mut block = [0; $n * core::mem::size_of::<$c>()];
+                    crate::error::read_exact_for::<Self, _>(&mut src, &mut block)?;
+                    Ok(Self::read_bytes_be(&block))
+                }
+            }
+        )*
+    };
+}
+
+impl_math! {
+    glam::Vec2 => [f32; 2], |v| v.to_array(), |a| glam::Vec2::from_array(a);
+    glam::Vec3 => [f32; 3], |v| v.to_array(), |a| glam::Vec3::from_array(a);
+    glam::Vec3A => [f32; 3], |v| v.to_array(), |a| glam::Vec3A::from_array(a);
+    glam::Vec4 => [f32; 4], |v| v.to_array(), |a| glam::Vec4::from_array(a);
+    glam::Quat => [f32; 4], |v| v.to_array(), |a| glam::Quat::from_array(a);
+    glam::Mat2 => [f32; 4], |v| v.to_cols_array(), |a| glam::Mat2::from_cols_array(&a);
+    glam::Mat3 => [f32; 9], |v| v.to_cols_array(), |a| glam::Mat3::from_cols_array(&a);
+    glam::Mat3A => [f32; 9], |v| v.to_cols_array(), |a| glam::Mat3A::from_cols_array(&a);
+    glam::Mat4 => [f32; 16], |v| v.to_cols_array(), |a| glam::Mat4::from_cols_array(&a);
+    glam::DVec2 => [f64; 2], |v| v.to_array(), |a| glam::DVec2::from_array(a);
+    glam::DVec3 => [f64; 3], |v| v.to_array(), |a| glam::DVec3::from_array(a);
+    glam::DVec4 => [f64; 4], |v| v.to_array(), |a| glam::DVec4::from_array(a);
+    glam::DQuat => [f64; 4], |v| v.to_array(), |a| glam::DQuat::from_array(a);
+    glam::DMat2 => [f64; 4], |v| v.to_cols_array(), |a| glam::DMat2::from_cols_array(&a);
+    glam::DMat3 => [f64; 9], |v| v.to_cols_array(), |a| glam::DMat3::from_cols_array(&a);
+    glam::DMat4 => [f64; 16], |v| v.to_cols_array(), |a| glam::DMat4::from_cols_array(&a);
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use glam::{DMat3, DQuat, Mat4, Quat, Vec2, Vec3, Vec3A, Vec4};
+
+    use crate::testutil::{assert_roundtrip_bytes, assert_roundtrip_io};
+    use crate::{ByteBuf, FixedSize};
+
+    /// Packs components by hand, in little endian and big endian order.
+    fn pack(components: &[f32]) -> (Vec<u8>, Vec<u8>) {
+        let le = components.iter().flat_map(|c| c.to_le_bytes()).collect();
+        let be = components.iter().flat_map(|c| c.to_be_bytes()).collect();
+        (le, be)
+    }
+
+    /// A small xorshift generator which only produces finite values.
+    struct Random(u32);
+
+    impl Random {
+        fn next(&mut self) -> f32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            (self.0 as i32) as f32 / 65536.0
+        }
+
+        fn array<const N: usize>(&mut self) -> [f32; N] {
+            core::array::from_fn(|_| self.next())
+        }
+    }
+
+    #[test]
+    fn layout() {
+        let (le, be) = pack(&[1.0, -2.5]);
+        assert_roundtrip_bytes(Vec2::new(1.0, -2.5), &le, &be);
+        let (le, be) = pack(&[1.0, 2.0, 3.0]);
+        assert_roundtrip_bytes(Vec3::new(1.0, 2.0, 3.0), &le, &be);
+        assert_roundtrip_bytes(Vec3A::new(1.0, 2.0, 3.0), &le, &be);
+        assert_eq!(Vec3A::SIZE, 12);
+        let (le, be) = pack(&[0.5, 0.25, 0.125, 1.0]);
+        assert_roundtrip_bytes(Vec4::new(0.5, 0.25, 0.125, 1.0), &le, &be);
+        assert_roundtrip_bytes(Quat::from_xyzw(0.5, 0.25, 0.125, 1.0), &le, &be);
+        let columns: [f32; 16] = core::array::from_fn(|i| i as f32);
+        let (le, be) = pack(&columns);
+        let matrix = Mat4::from_cols_array(&columns);
+        assert_eq!(matrix.x_axis, Vec4::new(0.0, 1.0, 2.0, 3.0));
+        assert_roundtrip_bytes(matrix, &le, &be);
+        let mut buffer = ByteBuf::new(vec![0; 64]);
+        buffer.set_be(0, matrix);
+        assert_eq!(buffer.as_ref(), &be[..]);
+        assert_eq!(buffer.get_be::<Mat4>(0), matrix);
+        assert_eq!(DMat3::SIZE, 72);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut random = Random(0x2545F491);
+        for _ in 0..32 {
+            assert_roundtrip_io(Vec2::from_array(random.array()));
+            assert_roundtrip_io(Vec3::from_array(random.array()));
+            assert_roundtrip_io(Vec3A::from_array(random.array()));
+            assert_roundtrip_io(Vec4::from_array(random.array()));
+            assert_roundtrip_io(Quat::from_array(random.array()));
+            assert_roundtrip_io(Mat4::from_cols_array(&random.array()));
+            assert_roundtrip_io(DQuat::from_array(random.array::<4>().map(f64::from)));
+        }
+    }
+}
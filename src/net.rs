@@ -0,0 +1,238 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//! Network addresses are written in network order regardless of the requested byte order: the
+//! little and big endian variants of every method produce the same bytes. A [SocketAddrV4] is
+//! its 4 address bytes followed by the port, a [SocketAddrV6] is its 16 address bytes followed by
+//! the port, the flow info and the scope id. [IpAddr] and [SocketAddr] are prefixed with a tag
+//! byte holding the IP version, 4 or 6.
+
+use std::io::{Read, Result, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use crate::bytes::check_len;
+use crate::{unknown_tag, ByteSize, FixedSize, ReadBytes, ReadFrom, WriteBytes, WriteTo};
+
+const TAG_V4: u8 = 4;
+const TAG_V6: u8 = 6;
+
+fn encode_v4(addr: &SocketAddrV4) -> [u8; 6] {
+    let mut block = [0; 6];
+    block[..4].copy_from_slice(&addr.ip().octets());
+    block[4..].copy_from_slice(&addr.port().to_be_bytes());
+    block
+}
+
+fn decode_v4(block: [u8; 6]) -> SocketAddrV4 {
+    let ip: [u8; 4] = block[..4].try_into().unwrap();
+    SocketAddrV4::new(ip.into(), u16::from_be_bytes([block[4], block[5]]))
+}
+
+fn encode_v6(addr: &SocketAddrV6) -> [u8; 26] {
+    let mut block = [0; 26];
+    block[..16].copy_from_slice(&addr.ip().octets());
+    block[16..18].copy_from_slice(&addr.port().to_be_bytes());
+    block[18..22].copy_from_slice(&addr.flowinfo().to_be_bytes());
+    block[22..].copy_from_slice(&addr.scope_id().to_be_bytes());
+    block
+}
+
+fn decode_v6(block: [u8; 26]) -> SocketAddrV6 {
+    let ip: [u8; 16] = block[..16].try_into().unwrap();
+    SocketAddrV6::new(
+        ip.into(),
+        u16::from_be_bytes(block[16..18].try_into().unwrap()),
+        u32::from_be_bytes(block[18..22].try_into().unwrap()),
+        u32::from_be_bytes(block[22..].try_into().unwrap())
+    )
+}
+
+macro_rules! impl_fixed_net {
+    ($($t: ty: $size: literal, $encode: expr, $decode: expr;)*) => {
+        $(
+            impl FixedSize for $t {
+                const SIZE: usize = $size;
+            }
+
+            impl ByteSize for $t {
+                fn byte_size(&self) -> usize {
+                    $size
+                }
+            }
+
+            impl WriteBytes for $t {
+                #[track_caller]
+                fn write_bytes_le(&self, bytes: &mut [u8]) {
+                    self.write_bytes_be(bytes)
+                }
+
+                #[track_caller]
+                fn write_bytes_be(&self, bytes: &mut [u8]) {
+                    check_len($size, bytes.len());
+                    bytes[..$size].copy_from_slice(&$encode(self));
+                }
+            }
+
+            impl ReadBytes for $t {
+                #[track_caller]
+                fn read_bytes_le(bytes: &[u8]) -> Self {
+                    Self::read_bytes_be(bytes)
+                }
+
+                #[track_caller]
+                fn read_bytes_be(bytes: &[u8]) -> Self {
+                    check_len($size, bytes.len());
+                    let block: [u8; $size] = bytes[..$size].try_into().unwrap();
+                    $decode(block)
+                }
+            }
+
+            impl WriteTo for $t {
+                fn write_to_le<T: Write>(&self, dst: T) -> Result<()> {
+                    self.write_to_be(dst)
+                }
+
+                fn write_to_be<T: Write>(&self, mut dst: T) -> Result<()> {
+                    dst.write_all(&$encode(self))
+                }
+            }
+
+            impl ReadFrom for $t {
+                fn read_from_le<T: Read>(src: T) -> Result<Self> {
+                    Self::read_from_be(src)
+                }
+
+                fn read_from_be<T: Read>(mut src: T) -> Result<Self> {
+                    let mut block = [0; $size];
+                    crate::error::read_exact_for::<Self, _>(&mut src, &mut block)?;
+                    Ok($decode(block))
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_net! {
+    Ipv4Addr: 4, Ipv4Addr::octets, Ipv4Addr::from;
+    Ipv6Addr: 16, Ipv6Addr::octets, Ipv6Addr::from;
+    SocketAddrV4: 6, encode_v4, decode_v4;
+    SocketAddrV6: 26, encode_v6, decode_v6;
+}
+
+macro_rules! impl_tagged_net {
+    ($($t: ident($v4: ty, $v6: ty);)*) => {
+        $(
+            impl ByteSize for $t {
+                fn byte_size(&self) -> usize {
+                    match self {
+                        $t::V4(_) => 1 + <$v4>::SIZE,
+                        $t::V6(_) => 1 + <$v6>::SIZE
+                    }
+                }
+            }
+
+            impl WriteTo for $t {
+                fn write_to_le<T: Write>(&self, dst: T) -> Result<()> {
+                    self.write_to_be(dst)
+                }
+
+                fn write_to_be<T: Write>(&self, mut dst: T) -> Result<()> {
+                    match self {
+                        $t::V4(addr) => {
+                            TAG_V4.write_to_be(&mut dst)?;
+                            addr.write_to_be(dst)
+                        },
+                        $t::V6(addr) => {
+                            TAG_V6.write_to_be(&mut dst)?;
+                            addr.write_to_be(dst)
+                        }
+                    }
+                }
+            }
+
+            /// *If the tag is neither 4 nor 6, an error of kind
+            /// [InvalidData](std::io::ErrorKind::InvalidData) is returned.*
+            impl ReadFrom for $t {
+                fn read_from_le<T: Read>(src: T) -> Result<Self> {
+                    Self::read_from_be(src)
+                }
+
+                fn read_from_be<T: Read>(mut src: T) -> Result<Self> {
+                    match u8::read_from_be(&mut src)? {
+                        TAG_V4 => <$v4>::read_from_be(src).map($t::V4),
+                        TAG_V6 => <$v6>::read_from_be(src).map($t::V6),
+                        tag => Err(unknown_tag(tag))
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_tagged_net! {
+    IpAddr(Ipv4Addr, Ipv6Addr);
+    SocketAddr(SocketAddrV4, SocketAddrV6);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+    use crate::testutil::assert_roundtrip_io;
+    use crate::{ByteSize, ReadFrom, WriteTo};
+
+    #[test]
+    fn socket_addrs() {
+        let v4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 2), 0x1F90);
+        let mut bytes = Vec::new();
+        v4.write_to_le(&mut bytes).unwrap();
+        assert_eq!(bytes, [192, 168, 1, 2, 0x1F, 0x90]);
+        let v6 = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0x0A0B0C0D, 3);
+        let mut bytes = Vec::new();
+        v6.write_to_le(&mut bytes).unwrap();
+        assert_eq!(bytes[..16], Ipv6Addr::LOCALHOST.octets());
+        assert_eq!(bytes[16..], [1, 187, 10, 11, 12, 13, 0, 0, 0, 3]);
+        let tagged = SocketAddr::V6(v6);
+        assert_eq!(tagged.byte_size(), 27);
+        let mut bytes = Vec::new();
+        tagged.write_to_be(&mut bytes).unwrap();
+        assert_eq!(bytes[0], 6);
+        assert_eq!(SocketAddr::read_from_le(&bytes[..]).unwrap(), tagged);
+        assert_roundtrip_io(SocketAddr::V4(v4));
+        assert_roundtrip_io(v6);
+        assert_roundtrip_io(IpAddr::V4(*v4.ip()));
+    }
+
+    #[test]
+    fn invalid_tag() {
+        let err = SocketAddr::read_from_be(&[5u8, 0, 0, 0, 0, 0, 0][..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let err = IpAddr::read_from_be(&[4u8, 127, 0][..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
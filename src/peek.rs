@@ -0,0 +1,344 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::io::{BufRead, Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+use crate::ReadBytes;
+
+/// Allows to look at upcoming bytes of a [BufRead] without consuming them.
+///
+/// *This is typically used to detect a format by its magic number before handing the untouched
+/// stream to the right parser.*
+///
+/// It is implemented for every [BufRead] and serves peeks from the data returned by
+/// [fill_buf](BufRead::fill_buf). `&[u8]` and [Cursor](std::io::Cursor) expose all their
+/// remaining bytes, but a [BufReader](std::io::BufReader) cannot grow its buffered data without
+/// consuming it: to peek more bytes than its capacity, wrap it in a [PeekBuf], whose inherent
+/// [peek_bytes](PeekBuf::peek_bytes) fills a side buffer instead.
+pub trait PeekExt: BufRead {
+    /// Returns the next `n` bytes of this stream without consuming them.
+    ///
+    /// # Arguments
+    ///
+    /// * `n`: the number of bytes to look at.
+    ///
+    /// returns: Result<&[u8], Error>
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) of kind [UnexpectedEof](ErrorKind::UnexpectedEof) if
+    /// fewer than `n` bytes are buffered, either because the stream ends or because `n` exceeds
+    /// the capacity of the reader.
+    fn peek_bytes(&mut self, n: usize) -> Result<&[u8]> {
+        let data = self.fill_buf()?;
+        match data.len() >= n {
+            true => Ok(&data[..n]),
+            false => Err(std::io::Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("cannot peek {} bytes, only {} buffered; wrap the reader in a PeekBuf to peek further", n, data.len())
+            ))
+        }
+    }
+
+    /// Reads a value in little endian order without consuming it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be read.
+    fn peek_le<T: ReadBytes>(&mut self) -> Result<T> {
+        self.peek_bytes(T::SIZE).map(T::read_bytes_le)
+    }
+
+    /// Reads a value in big endian order without consuming it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be read.
+    fn peek_be<T: ReadBytes>(&mut self) -> Result<T> {
+        self.peek_bytes(T::SIZE).map(T::read_bytes_be)
+    }
+}
+
+fn unexpected_eof(n: usize, available: usize) -> std::io::Error {
+    std::io::Error::new(
+        ErrorKind::UnexpectedEof,
+        format!("cannot peek {} bytes, only {} available", n, available)
+    )
+}
+
+impl<R: BufRead + ?Sized> PeekExt for R {}
+
+/// A [BufRead] wrapper which supports peeking more bytes than the wrapped [BufRead] can buffer.
+///
+/// When a peek request exceeds the data buffered by the inner [BufRead], the missing bytes are
+/// moved into a side buffer which is then drained by subsequent reads. When the inner [BufRead]
+/// is [Seek], the position reported by this wrapper accounts for the side buffer, so that peeking
+/// never moves the logical position.
+pub struct PeekBuf<R> {
+    inner: R,
+    side: Vec<u8>,
+    pos: usize
+}
+
+impl<R> PeekBuf<R> {
+    /// Creates a new [PeekBuf] wrapping the given [BufRead].
+    pub fn new(inner: R) -> PeekBuf<R> {
+        Self {
+            inner,
+            side: Vec::new(),
+            pos: 0
+        }
+    }
+
+    /// Returns a reference to the wrapped [BufRead].
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped [BufRead].
+    ///
+    /// *Bytes read through it skip the bytes which were peeked but not yet consumed.*
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the wrapped [BufRead].
+    ///
+    /// *Any byte which was peeked but not yet consumed is lost.*
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> PeekBuf<R> {
+    /// Returns up to `n` upcoming bytes without consuming them.
+    ///
+    /// # Arguments
+    ///
+    /// * `n`: the number of bytes to look at.
+    ///
+    /// returns: the next `n` bytes, or fewer if the stream ends before.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be read. Bytes read before the
+    /// error are kept for the next peek or read.
+    pub fn peek(&mut self, n: usize) -> Result<&[u8]> {
+        if self.pos >= self.side.len() {
+            self.side.clear();
+            self.pos = 0;
+            if self.inner.fill_buf()?.len() >= n {
+                return Ok(&self.inner.fill_buf()?[..n]);
+            }
+        } else {
+            self.side.drain(..self.pos);
+            self.pos = 0;
+        }
+        while self.side.len() < n {
+            let data = self.inner.fill_buf()?;
+            if data.is_empty() {
+                break;
+            }
+            let len = data.len().min(n - self.side.len());
+            self.side.extend_from_slice(&data[..len]);
+            self.inner.consume(len);
+        }
+        Ok(&self.side[..n.min(self.side.len())])
+    }
+
+    /// Returns the next `n` bytes without consuming them, buffering as many bytes as needed.
+    ///
+    /// *This shadows [PeekExt::peek_bytes], which is limited to the bytes already buffered.*
+    ///
+    /// # Arguments
+    ///
+    /// * `n`: the number of bytes to look at.
+    ///
+    /// returns: Result<&[u8], Error>
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) of kind [UnexpectedEof](ErrorKind::UnexpectedEof) if
+    /// the stream ends before `n` bytes could be buffered.
+    pub fn peek_bytes(&mut self, n: usize) -> Result<&[u8]> {
+        let bytes = self.peek(n)?;
+        if bytes.len() < n {
+            return Err(unexpected_eof(n, bytes.len()));
+        }
+        Ok(bytes)
+    }
+
+    /// Reads a value in little endian order without consuming it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be read.
+    pub fn peek_le<T: ReadBytes>(&mut self) -> Result<T> {
+        self.peek_bytes(T::SIZE).map(T::read_bytes_le)
+    }
+
+    /// Reads a value in big endian order without consuming it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be read.
+    pub fn peek_be<T: ReadBytes>(&mut self) -> Result<T> {
+        self.peek_bytes(T::SIZE).map(T::read_bytes_be)
+    }
+}
+
+impl<R: BufRead> Read for PeekBuf<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = {
+            let data = self.fill_buf()?;
+            let len = data.len().min(buf.len());
+            buf[..len].copy_from_slice(&data[..len]);
+            len
+        };
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for PeekBuf<R> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.pos < self.side.len() {
+            Ok(&self.side[self.pos..])
+        } else {
+            self.inner.fill_buf()
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if self.pos < self.side.len() {
+            self.pos = (self.pos + amt).min(self.side.len());
+        } else {
+            self.inner.consume(amt)
+        }
+    }
+}
+
+impl<R: BufRead + Seek> Seek for PeekBuf<R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let pos = match pos {
+            SeekFrom::Current(offset) => {
+                let current = self.stream_position()?;
+                let target = current.checked_add_signed(offset).ok_or_else(|| {
+                    Error::new(ErrorKind::InvalidInput, "invalid seek to a negative or overflowing position")
+                })?;
+                SeekFrom::Start(target)
+            },
+            pos => pos
+        };
+        let pos = self.inner.seek(pos)?;
+        self.side.clear();
+        self.pos = 0;
+        Ok(pos)
+    }
+
+    fn stream_position(&mut self) -> Result<u64> {
+        Ok(self.inner.stream_position()? - (self.side.len() - self.pos) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor, ErrorKind, Read, Seek, SeekFrom};
+
+    use crate::{PeekBuf, PeekExt, ReadExt};
+
+    #[test]
+    fn peek_then_read() {
+        let mut cursor = Cursor::new([0x42, 0x50, 0x58, 0x50, 1, 0]);
+        assert_eq!(cursor.peek_be::<u32>().unwrap(), 0x42505850);
+        assert_eq!(cursor.read_be::<u32>().unwrap(), 0x42505850);
+        assert_eq!(cursor.peek_le::<u16>().unwrap(), 1);
+        assert_eq!(cursor.read_le::<u16>().unwrap(), 1);
+        let mut slice: &[u8] = &[1, 2];
+        assert_eq!(slice.peek_bytes(2).unwrap(), &[1, 2]);
+        assert_eq!(slice.read_le::<u16>().unwrap(), 0x0201);
+    }
+
+    #[test]
+    fn peek_past_capacity() {
+        let data: Vec<u8> = (0..32).collect();
+        let mut reader = PeekBuf::new(BufReader::with_capacity(4, &*data));
+        assert_eq!(reader.peek_le::<u16>().unwrap(), 0x0100);
+        assert_eq!(reader.peek_bytes(20).unwrap(), &data[..20]);
+        assert_eq!(reader.peek_le::<u64>().unwrap(), 0x0706050403020100);
+        assert_eq!(reader.read_le::<u32>().unwrap(), 0x03020100);
+        assert_eq!(reader.peek_bytes(24).unwrap(), &data[4..28]);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, &data[4..]);
+    }
+
+    #[test]
+    fn peek_eof() {
+        let mut cursor = Cursor::new([1, 2, 3]);
+        assert_eq!(cursor.peek_le::<u32>().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        let mut reader = PeekBuf::new(BufReader::with_capacity(2, &[1u8, 2, 3][..]));
+        assert_eq!(reader.peek_le::<u32>().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(reader.read_le::<u16>().unwrap(), 0x0201);
+    }
+
+    #[test]
+    fn peek_bufreader() {
+        let data: Vec<u8> = (0..32).collect();
+        let mut reader = BufReader::with_capacity(8, &*data);
+        assert_eq!(reader.peek_le::<u32>().unwrap(), 0x03020100);
+        assert_eq!(reader.read_le::<u16>().unwrap(), 0x0100);
+        assert_eq!(reader.peek_be::<u32>().unwrap(), 0x02030405);
+        assert_eq!(reader.peek_le::<u64>().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(reader.read_le::<u64>().unwrap(), 0x0908070605040302);
+    }
+
+    #[test]
+    fn peek_seek() {
+        let data: Vec<u8> = (0..100).collect();
+        let mut reader = PeekBuf::new(BufReader::with_capacity(16, Cursor::new(data.clone())));
+        assert_eq!(reader.peek(64).unwrap(), &data[..64]);
+        assert_eq!(reader.stream_position().unwrap(), 0);
+        assert_eq!(reader.peek_le::<u16>().unwrap(), 0x0100);
+        let mut block = [0; 64];
+        reader.read_exact(&mut block).unwrap();
+        assert_eq!(block, data[..64]);
+        assert_eq!(reader.stream_position().unwrap(), 64);
+        assert_eq!(reader.peek(8).unwrap(), &data[64..72]);
+        assert_eq!(reader.read_be::<u16>().unwrap(), 0x4041);
+        assert_eq!(reader.stream_position().unwrap(), 66);
+        assert_eq!(reader.peek(50).unwrap(), &data[66..]);
+        assert_eq!(reader.peek_bytes(50).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(reader.seek(SeekFrom::Current(-6)).unwrap(), 60);
+        assert_eq!(reader.read_le::<u8>().unwrap(), 60);
+        assert!(reader.seek(SeekFrom::Current(-62)).is_err());
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, &data[61..]);
+    }
+}
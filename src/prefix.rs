@@ -0,0 +1,150 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::io::{Read, Result, Write};
+use std::marker::PhantomData;
+
+use crate::bytes::count_prefix;
+use crate::{ByteOrder, Endian, LittleEndian, ReadFrom, WriteTo};
+
+/// A length prefix which can be written in front of a collection.
+pub trait WritePrefix {
+    /// Writes a length prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `len`: the number of elements which follow the prefix.
+    /// * `dst`: the destination stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be written. If `len` does not
+    /// fit in the prefix, an error of kind [InvalidInput](std::io::ErrorKind::InvalidInput) is
+    /// returned.
+    fn write_len<W: Write>(len: usize, dst: W) -> Result<()>;
+}
+
+/// A length prefix which can be read in front of a collection.
+pub trait ReadPrefix {
+    /// Reads a length prefix.
+    ///
+    /// *A prefix which does not fit in a [usize] is returned as [usize::MAX] so that it is
+    /// rejected by any element count limit.*
+    ///
+    /// # Arguments
+    ///
+    /// * `src`: the source stream.
+    ///
+    /// returns: Result<usize, Error>
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if some bytes could not be read.
+    fn read_len<R: Read>(src: R) -> Result<usize>;
+}
+
+/// A length prefix encoded as an integer of type `P` in the byte order `E`.
+///
+/// This type is never constructed: it only selects the wire layout of the prefix through
+/// [write_vec_le](crate::WriteExt::write_vec_le),
+/// [read_vec_le](crate::ReadExt::read_vec_le) and their big endian counterparts.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::{BigEndian, LengthPrefix, ReadPrefix, WritePrefix};
+///
+/// type Prefix = LengthPrefix<u16, BigEndian>;
+///
+/// let mut out = Vec::new();
+/// Prefix::write_len(258, &mut out).unwrap();
+/// assert_eq!(out, [1, 2]);
+/// assert_eq!(Prefix::read_len(&out[..]).unwrap(), 258);
+/// assert!(Prefix::write_len(1 << 16, &mut out).is_err());
+/// ```
+pub struct LengthPrefix<P, E> {
+    marker: PhantomData<(P, E)>
+}
+
+/// The little endian [u32] length prefix, used by the string and byte collection types.
+pub type DefaultPrefix = LengthPrefix<u32, LittleEndian>;
+
+impl<P: WriteTo + TryFrom<usize>, E: ByteOrder> WritePrefix for LengthPrefix<P, E> {
+    fn write_len<W: Write>(len: usize, dst: W) -> Result<()> {
+        match E::ENDIAN {
+            Endian::Little => count_prefix::<P>(len)?.write_to_le(dst),
+            Endian::Big => count_prefix::<P>(len)?.write_to_be(dst)
+        }
+    }
+}
+
+impl<P: ReadFrom + TryInto<usize>, E: ByteOrder> ReadPrefix for LengthPrefix<P, E> {
+    fn read_len<R: Read>(src: R) -> Result<usize> {
+        let len = match E::ENDIAN {
+            Endian::Little => P::read_from_le(src)?,
+            Endian::Big => P::read_from_be(src)?
+        };
+        Ok(len.try_into().unwrap_or(usize::MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use crate::{BigEndian, LengthPrefix, LittleEndian, ReadExt, WriteExt};
+
+    #[test]
+    fn layout() {
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_vec_be::<LengthPrefix<u8, LittleEndian>, u16>(&[0x102]).unwrap();
+        cursor.write_vec_le::<LengthPrefix<u32, BigEndian>, u8>(&[7]).unwrap();
+        cursor.write_vec_le::<LengthPrefix<u64, LittleEndian>, u8>(&[]).unwrap();
+        assert_eq!(cursor.get_ref()[..8], [1, 1, 2, 0, 0, 0, 1, 7]);
+        assert_eq!(cursor.get_ref().len(), 16);
+        cursor.set_position(0);
+        assert_eq!(cursor.read_vec_be::<LengthPrefix<u8, LittleEndian>, u16>(1).unwrap(), [0x102]);
+        assert_eq!(cursor.read_vec_le::<LengthPrefix<u32, BigEndian>, u8>(1).unwrap(), [7]);
+        assert_eq!(cursor.read_vec_le::<LengthPrefix<u64, LittleEndian>, u8>(0).unwrap(), []);
+    }
+
+    #[test]
+    fn limits() {
+        let mut cursor = Cursor::new(Vec::new());
+        let err = cursor.write_vec_le::<LengthPrefix<u8, BigEndian>, u8>(&[0; 256]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(cursor.get_ref().is_empty());
+        let mut over_cap = Cursor::new([0, 3, 1, 2, 3]);
+        let err = over_cap.read_vec_le::<LengthPrefix<u16, BigEndian>, u8>(2).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(over_cap.position(), 2);
+        let mut truncated = Cursor::new([0, 0, 0, 0, 0, 0, 0, 9, 1, 2]);
+        let err = truncated.read_vec_le::<LengthPrefix<u64, BigEndian>, u8>(1 << 30).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
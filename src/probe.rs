@@ -0,0 +1,188 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::io::{Read, Result};
+
+/// A [Read] wrapper which records everything read so that the stream can be rewound, even when
+/// the wrapped [Read] does not support seeking.
+///
+/// *This is typically used to sniff the format of a non-seekable stream such as a pipe, before
+/// handing the stream from its beginning to the right parser.*
+///
+/// Once [commit](ProbeReader::commit) is called, bytes are no longer recorded and the
+/// [ProbeReader] becomes a thin passthrough to the wrapped [Read].
+pub struct ProbeReader<R> {
+    inner: R,
+    buffer: Vec<u8>,
+    pos: usize,
+    committed: bool
+}
+
+impl<R> ProbeReader<R> {
+    /// Creates a new [ProbeReader] wrapping the given [Read].
+    pub fn new(inner: R) -> ProbeReader<R> {
+        Self {
+            inner,
+            buffer: Vec::new(),
+            pos: 0,
+            committed: false
+        }
+    }
+
+    /// Moves back to the start of the recorded region so that the recorded bytes are read
+    /// again.
+    ///
+    /// *This has no effect once the [ProbeReader] is committed.*
+    pub fn rewind(&mut self) {
+        if !self.committed {
+            self.pos = 0;
+        }
+    }
+
+    /// Pushes bytes back into the stream so that they are returned first by the next reads.
+    pub fn unread(&mut self, bytes: &[u8]) {
+        self.buffer.splice(self.pos..self.pos, bytes.iter().copied());
+    }
+
+    /// Stops recording: everything already consumed is dropped and the [ProbeReader] can no
+    /// longer be rewound.
+    ///
+    /// Bytes which were recorded but not yet consumed (for example after a
+    /// [rewind](ProbeReader::rewind)) are still returned by the next reads.
+    pub fn commit(&mut self) {
+        self.buffer.drain(..self.pos);
+        self.pos = 0;
+        self.committed = true;
+    }
+
+    /// Returns true if this [ProbeReader] has been committed.
+    pub fn is_committed(&self) -> bool {
+        self.committed
+    }
+
+    /// Returns the number of bytes currently held in memory.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns a reference to the wrapped [Read].
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped [Read].
+    ///
+    /// *Bytes read through it skip the bytes held in memory and are not recorded for
+    /// [rewind](ProbeReader::rewind).*
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the wrapped [Read].
+    ///
+    /// *Any byte held in memory and not yet consumed is lost.*
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ProbeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.pos < self.buffer.len() {
+            let len = buf.len().min(self.buffer.len() - self.pos);
+            buf[..len].copy_from_slice(&self.buffer[self.pos..self.pos + len]);
+            self.pos += len;
+            if self.committed && self.pos == self.buffer.len() {
+                self.buffer = Vec::new();
+                self.pos = 0;
+            }
+            return Ok(len);
+        }
+        let len = self.inner.read(buf)?;
+        if !self.committed {
+            self.buffer.extend_from_slice(&buf[..len]);
+            self.pos += len;
+        }
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use crate::{ProbeReader, ReadExt};
+
+    #[test]
+    fn rewind_and_commit() {
+        let data: Vec<u8> = (0..64).collect();
+        let mut reader = ProbeReader::new(&*data);
+        assert_eq!(reader.read_le::<u32>().unwrap(), 0x03020100);
+        reader.rewind();
+        let mut header = [0; 8];
+        reader.read_exact(&mut header).unwrap();
+        assert_eq!(header, data[..8]);
+        reader.rewind();
+        reader.read_exact(&mut header).unwrap();
+        assert_eq!(header, data[..8]);
+        assert_eq!(reader.buffered_len(), 8);
+        reader.commit();
+        assert_eq!(reader.buffered_len(), 0);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, data[8..]);
+        assert_eq!(reader.buffered_len(), 0);
+    }
+
+    #[test]
+    fn commit_after_rewind() {
+        let data: Vec<u8> = (0..16).collect();
+        let mut reader = ProbeReader::new(&*data);
+        let mut header = [0; 4];
+        reader.read_exact(&mut header).unwrap();
+        reader.rewind();
+        reader.commit();
+        assert!(reader.is_committed());
+        reader.rewind();
+        let mut all = Vec::new();
+        reader.read_to_end(&mut all).unwrap();
+        assert_eq!(all, data);
+    }
+
+    #[test]
+    fn unread() {
+        let mut reader = ProbeReader::new(&[1u8, 2, 3][..]);
+        assert_eq!(reader.read_le::<u8>().unwrap(), 1);
+        reader.unread(&[9, 8]);
+        assert_eq!(reader.read_be::<u16>().unwrap(), 0x0908);
+        reader.rewind();
+        let mut all = Vec::new();
+        reader.read_to_end(&mut all).unwrap();
+        assert_eq!(all, [1, 9, 8, 2, 3]);
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::cell::RefCell;
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::rc::Rc;
+
+/// A bounded in-memory FIFO of bytes which implements both [Read] and [Write].
+///
+/// Reading from an empty [RingBuffer] returns 0. Writing to a [RingBuffer] stores as many bytes
+/// as fit; if it is already full, an error of kind [WouldBlock](ErrorKind::WouldBlock) is
+/// returned.
+pub struct RingBuffer {
+    data: Box<[u8]>,
+    head: usize,
+    len: usize
+}
+
+impl RingBuffer {
+    /// Creates a new empty [RingBuffer] able to hold `capacity` bytes.
+    pub fn new(capacity: usize) -> RingBuffer {
+        Self {
+            data: vec![0; capacity].into_boxed_slice(),
+            head: 0,
+            len: 0
+        }
+    }
+
+    /// Returns the number of bytes waiting to be read.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the maximum number of bytes this [RingBuffer] can hold.
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns true if there is no byte to read.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if no more bytes can be written.
+    pub fn is_full(&self) -> bool {
+        self.len == self.data.len()
+    }
+
+    /// Splits this [RingBuffer] into a [Producer] writing to it and a [Consumer] reading from it.
+    pub fn split(self) -> (Producer, Consumer) {
+        let shared = Rc::new(RefCell::new(self));
+        (Producer(shared.clone()), Consumer(shared))
+    }
+}
+
+impl Read for RingBuffer {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut count = 0;
+        while count < buf.len() && self.len > 0 {
+            let chunk = (buf.len() - count).min(self.len).min(self.data.len() - self.head);
+            buf[count..count + chunk].copy_from_slice(&self.data[self.head..self.head + chunk]);
+            self.head = (self.head + chunk) % self.data.len();
+            self.len -= chunk;
+            count += chunk;
+        }
+        Ok(count)
+    }
+}
+
+impl Write for RingBuffer {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if self.is_full() && !buf.is_empty() {
+            return Err(Error::new(ErrorKind::WouldBlock, "ring buffer is full"));
+        }
+        let mut count = 0;
+        while count < buf.len() && !self.is_full() {
+            let tail = (self.head + self.len) % self.data.len();
+            let free = if tail >= self.head { self.data.len() - tail } else { self.head - tail };
+            let chunk = (buf.len() - count).min(free);
+            self.data[tail..tail + chunk].copy_from_slice(&buf[count..count + chunk]);
+            self.len += chunk;
+            count += chunk;
+        }
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The writing end of a split [RingBuffer].
+pub struct Producer(Rc<RefCell<RingBuffer>>);
+
+impl Producer {
+    /// Returns the number of bytes waiting to be read.
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Returns true if there is no byte to read.
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    /// Returns true if no more bytes can be written.
+    pub fn is_full(&self) -> bool {
+        self.0.borrow().is_full()
+    }
+}
+
+impl Write for Producer {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The reading end of a split [RingBuffer].
+pub struct Consumer(Rc<RefCell<RingBuffer>>);
+
+impl Consumer {
+    /// Returns the number of bytes waiting to be read.
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Returns true if there is no byte to read.
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+}
+
+impl Read for Consumer {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.0.borrow_mut().read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{ErrorKind, Read, Write};
+
+    use crate::{ReadExt, RingBuffer, WriteExt};
+
+    #[test]
+    fn wrap_around() {
+        let mut ring = RingBuffer::new(12);
+        ring.write_le(0x0102u16).unwrap();
+        ring.write_be(0x03040506u32).unwrap();
+        assert_eq!(ring.read_le::<u16>().unwrap(), 0x0102);
+        assert_eq!(ring.read_be::<u32>().unwrap(), 0x03040506);
+        assert!(ring.is_empty());
+        ring.write_le(0x1122334455667788u64).unwrap();
+        assert_eq!(ring.len(), 8);
+        assert_eq!(ring.read_le::<u64>().unwrap(), 0x1122334455667788);
+        let mut block = [0; 4];
+        assert_eq!(ring.read(&mut block).unwrap(), 0);
+    }
+
+    #[test]
+    fn full() {
+        let mut ring = RingBuffer::new(4);
+        assert_eq!(ring.write(&[1, 2, 3, 4, 5]).unwrap(), 4);
+        assert!(ring.is_full());
+        assert_eq!(ring.write(&[6]).unwrap_err().kind(), ErrorKind::WouldBlock);
+        let mut block = [0; 2];
+        ring.read_exact(&mut block).unwrap();
+        assert_eq!(block, [1, 2]);
+        ring.write_all(&[7, 8]).unwrap();
+        assert_eq!(ring.capacity(), 4);
+        let mut block = [0; 8];
+        assert_eq!(ring.read(&mut block).unwrap(), 4);
+        assert_eq!(block[..4], [3, 4, 7, 8]);
+    }
+
+    #[test]
+    fn split() {
+        let (mut producer, mut consumer) = RingBuffer::new(6).split();
+        for i in 0..10u32 {
+            producer.write_le(i).unwrap();
+            producer.write_be(i as u16).unwrap();
+            assert!(producer.is_full());
+            assert_eq!(consumer.read_le::<u32>().unwrap(), i);
+            assert_eq!(consumer.len(), 2);
+            assert_eq!(consumer.read_be::<u16>().unwrap(), i as u16);
+        }
+        assert!(consumer.is_empty() && producer.is_empty());
+    }
+}
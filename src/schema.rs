@@ -0,0 +1,936 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Runtime described record layouts.
+//!
+//! A [Schema] describes a sequence of tightly packed fields whose layout is only known at
+//! runtime (for example when loaded from a description file). Records are read into and written
+//! from a [Record] which maps each field name to a dynamically typed [Value].
+//!
+//! A [LayoutWriter] assembles a fixed size header from named fields placed at explicit
+//! offsets, rejecting fields which overlap or do not fit. A [ReprCWriter] assembles a structure
+//! whose fields are placed at naturally aligned offsets, as `#[repr(C)]` lays them out.
+
+use std::fmt::{Display, Formatter};
+use std::io::{ErrorKind, Read, Write};
+use std::ops::Range;
+
+use crate::{ByteBuf, Endian, ReadBytes, StaticByteBuf, WriteBytes};
+
+/// The type of a field in a [Schema].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// An unsigned 8 bits integer.
+    U8,
+
+    /// An unsigned 16 bits integer.
+    U16,
+
+    /// An unsigned 32 bits integer.
+    U32,
+
+    /// An unsigned 64 bits integer.
+    U64,
+
+    /// A signed 8 bits integer.
+    I8,
+
+    /// A signed 16 bits integer.
+    I16,
+
+    /// A signed 32 bits integer.
+    I32,
+
+    /// A signed 64 bits integer.
+    I64,
+
+    /// A 32 bits floating point number.
+    F32,
+
+    /// A 64 bits floating point number.
+    F64,
+
+    /// A boolean stored as a single byte, 0 or 1.
+    Bool,
+
+    /// A fixed number of opaque bytes.
+    Bytes(usize),
+
+    /// A UTF-8 string stored in a fixed number of bytes, padded with zeros.
+    FixedStr(usize)
+}
+
+impl FieldType {
+    /// Returns the number of bytes occupied by a field of this type.
+    pub fn size(&self) -> usize {
+        match self {
+            FieldType::U8 | FieldType::I8 | FieldType::Bool => 1,
+            FieldType::U16 | FieldType::I16 => 2,
+            FieldType::U32 | FieldType::I32 | FieldType::F32 => 4,
+            FieldType::U64 | FieldType::I64 | FieldType::F64 => 8,
+            FieldType::Bytes(len) | FieldType::FixedStr(len) => *len
+        }
+    }
+}
+
+/// A dynamically typed field value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// An unsigned 8 bits integer.
+    U8(u8),
+
+    /// An unsigned 16 bits integer.
+    U16(u16),
+
+    /// An unsigned 32 bits integer.
+    U32(u32),
+
+    /// An unsigned 64 bits integer.
+    U64(u64),
+
+    /// A signed 8 bits integer.
+    I8(i8),
+
+    /// A signed 16 bits integer.
+    I16(i16),
+
+    /// A signed 32 bits integer.
+    I32(i32),
+
+    /// A signed 64 bits integer.
+    I64(i64),
+
+    /// A 32 bits floating point number.
+    F32(f32),
+
+    /// A 64 bits floating point number.
+    F64(f64),
+
+    /// A boolean.
+    Bool(bool),
+
+    /// Opaque bytes.
+    Bytes(Vec<u8>),
+
+    /// A string.
+    Str(String)
+}
+
+/// An error produced while reading or writing a record.
+#[derive(Debug)]
+pub enum Error {
+    /// An IO error occurred while reading or writing a field.
+    Io {
+        /// The name of the field.
+        field: String,
+
+        /// The underlying IO error.
+        error: std::io::Error
+    },
+
+    /// A buffer is too small to contain a field.
+    OutOfBounds {
+        /// The name of the field.
+        field: String,
+
+        /// The offset of the field in bytes.
+        pos: usize,
+
+        /// The size of the field in bytes.
+        size: usize,
+
+        /// The length of the buffer in bytes.
+        len: usize
+    },
+
+    /// A string field does not contain valid UTF-8.
+    InvalidUtf8 {
+        /// The name of the field.
+        field: String
+    },
+
+    /// A boolean field holds a byte other than 0 or 1.
+    InvalidBool {
+        /// The name of the field.
+        field: String,
+
+        /// The byte found in the field.
+        value: u8
+    },
+
+    /// A record does not contain a value for a field.
+    Missing {
+        /// The name of the field.
+        field: String
+    },
+
+    /// A record value does not match the type of its field.
+    TypeMismatch {
+        /// The name of the field.
+        field: String,
+
+        /// The type of the field.
+        expected: FieldType
+    },
+
+    /// A record value is too long to fit in its field.
+    TooLong {
+        /// The name of the field.
+        field: String,
+
+        /// The size of the field in bytes.
+        size: usize,
+
+        /// The length of the value in bytes.
+        len: usize
+    },
+
+    /// Two fields of a [LayoutWriter] share some bytes.
+    Overlap {
+        /// The name of the field being added.
+        field: String,
+
+        /// The name of the field already occupying some of its bytes.
+        other: String
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { field, error } => write!(f, "field '{}': io error: {}", field, error),
+            Error::OutOfBounds { field, pos, size, len } => write!(f, "field '{}': {} bytes at offset {} exceed the buffer length of {}", field, size, pos, len),
+            Error::InvalidUtf8 { field } => write!(f, "field '{}': invalid UTF-8", field),
+            Error::InvalidBool { field, value } => write!(f, "field '{}': invalid boolean byte {:#04x}", field, value),
+            Error::Missing { field } => write!(f, "field '{}': missing value", field),
+            Error::TypeMismatch { field, expected } => write!(f, "field '{}': expected a value of type {:?}", field, expected),
+            Error::TooLong { field, size, len } => write!(f, "field '{}': {} bytes do not fit in {} bytes", field, len, size),
+            Error::Overlap { field, other } => write!(f, "field '{}': overlaps field '{}'", field, other)
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { error, .. } => Some(error),
+            _ => None
+        }
+    }
+}
+
+/// A record read according to a [Schema], mapping field names to values in declaration order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Record {
+    values: Vec<(String, Value)>
+}
+
+impl Record {
+    /// Creates a new empty record.
+    pub fn new() -> Record {
+        Self::default()
+    }
+
+    /// Returns the value of the field with the given `name`.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    /// Returns a mutable reference to the value of the field with the given `name`.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Value> {
+        self.values.iter_mut().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    /// Sets the value of the field with the given `name`, returning the previous value if any.
+    pub fn insert(&mut self, name: impl Into<String>, value: Value) -> Option<Value> {
+        let name = name.into();
+        match self.get_mut(&name) {
+            Some(v) => Some(std::mem::replace(v, value)),
+            None => {
+                self.values.push((name, value));
+                None
+            }
+        }
+    }
+
+    /// Returns an iterator over the field names and values of this record.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.values.iter().map(|(n, v)| (n.as_str(), v))
+    }
+}
+
+/// A runtime description of a record layout.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    fields: Vec<(String, FieldType)>
+}
+
+impl Schema {
+    /// Creates a new schema with no fields.
+    pub fn new() -> Schema {
+        Self::default()
+    }
+
+    /// Appends a field to this schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: the name of the field.
+    /// * `ty`: the type of the field.
+    ///
+    /// returns: Schema
+    pub fn field(mut self, name: impl Into<String>, ty: FieldType) -> Schema {
+        self.fields.push((name.into(), ty));
+        self
+    }
+
+    /// Returns an iterator over the field names and types of this schema.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, FieldType)> {
+        self.fields.iter().map(|(n, t)| (n.as_str(), *t))
+    }
+
+    /// Returns the total size in bytes of a record, or [None] if it does not fit in a [usize].
+    pub fn size(&self) -> Option<usize> {
+        self.fields.iter().try_fold(0usize, |size, (_, t)| size.checked_add(t.size()))
+    }
+
+    /// Returns the byte range and name of every field, in declaration order, or [None] if the
+    /// total size of a record does not fit in a [usize].
+    ///
+    /// *Fields are tightly packed, so the ranges cover `0..size()` without gaps.*
+    pub fn coverage(&self) -> Option<Vec<(Range<usize>, &str)>> {
+        let mut pos = 0usize;
+        self.fields
+            .iter()
+            .map(|(name, ty)| {
+                let start = pos;
+                pos = pos.checked_add(ty.size())?;
+                Some((start..pos, name.as_str()))
+            })
+            .collect()
+    }
+
+    /// Reads a record from the given [Read], in little endian order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error] if a field could not be read or decoded.
+    pub fn read_le<R: Read>(&self, src: R) -> Result<Record, Error> {
+        self.read(src, Endian::Little)
+    }
+
+    /// Reads a record from the given [Read], in big endian order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error] if a field could not be read or decoded.
+    pub fn read_be<R: Read>(&self, src: R) -> Result<Record, Error> {
+        self.read(src, Endian::Big)
+    }
+
+    /// Reads a record from the given buffer at the given `pos` offset in bytes, in little
+    /// endian order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error] if a field is out of the bounds of the buffer or could not be decoded.
+    pub fn read_from_buf_le<T: AsRef<[u8]>>(&self, buf: &ByteBuf<T>, pos: usize) -> Result<Record, Error> {
+        self.read_from_buf(buf.as_ref(), pos, Endian::Little)
+    }
+
+    /// Reads a record from the given buffer at the given `pos` offset in bytes, in big endian
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error] if a field is out of the bounds of the buffer or could not be decoded.
+    pub fn read_from_buf_be<T: AsRef<[u8]>>(&self, buf: &ByteBuf<T>, pos: usize) -> Result<Record, Error> {
+        self.read_from_buf(buf.as_ref(), pos, Endian::Big)
+    }
+
+    /// Writes a record into the given [Write], in little endian order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error] if a field is missing from the record, has the wrong type, or could
+    /// not be written.
+    pub fn write_le<W: Write>(&self, record: &Record, dst: W) -> Result<(), Error> {
+        self.write(record, dst, Endian::Little)
+    }
+
+    /// Writes a record into the given [Write], in big endian order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error] if a field is missing from the record, has the wrong type, or could
+    /// not be written.
+    pub fn write_be<W: Write>(&self, record: &Record, dst: W) -> Result<(), Error> {
+        self.write(record, dst, Endian::Big)
+    }
+
+    fn read<R: Read>(&self, mut src: R, endian: Endian) -> Result<Record, Error> {
+        let mut record = Record::new();
+        let mut block = Vec::new();
+        for (name, ty) in &self.fields {
+            // Grow the block as bytes arrive, so that a huge field fails at the end of the stream
+            // instead of allocating its whole size up front.
+            block.clear();
+            let len = (&mut src)
+                .take(ty.size() as u64)
+                .read_to_end(&mut block)
+                .map_err(|error| Error::Io { field: name.clone(), error })?;
+            if len < ty.size() {
+                return Err(Error::Io {
+                    field: name.clone(),
+                    error: ErrorKind::UnexpectedEof.into()
+                });
+            }
+            record.values.push((name.clone(), decode(name, *ty, &block, endian)?));
+        }
+        Ok(record)
+    }
+
+    fn read_from_buf(&self, bytes: &[u8], mut pos: usize, endian: Endian) -> Result<Record, Error> {
+        let mut record = Record::new();
+        for (name, ty) in &self.fields {
+            let size = ty.size();
+            let block = pos.checked_add(size).and_then(|end| bytes.get(pos..end)).ok_or_else(|| Error::OutOfBounds {
+                field: name.clone(),
+                pos,
+                size,
+                len: bytes.len()
+            })?;
+            record.values.push((name.clone(), decode(name, *ty, block, endian)?));
+            pos += size;
+        }
+        Ok(record)
+    }
+
+    fn write<W: Write>(&self, record: &Record, mut dst: W, endian: Endian) -> Result<(), Error> {
+        let mut block = Vec::new();
+        for (name, ty) in &self.fields {
+            let value = record.get(name).ok_or_else(|| Error::Missing { field: name.clone() })?;
+            match (*ty, value) {
+                (FieldType::Bytes(size), Value::Bytes(v)) => write_padded(name, size, v, &mut dst)?,
+                (FieldType::FixedStr(size), Value::Str(v)) => write_padded(name, size, v.as_bytes(), &mut dst)?,
+                (FieldType::Bytes(_) | FieldType::FixedStr(_), _) => {
+                    return Err(Error::TypeMismatch { field: name.clone(), expected: *ty });
+                },
+                _ => {
+                    block.clear();
+                    block.resize(ty.size(), 0);
+                    encode(name, *ty, value, &mut block, endian)?;
+                    dst.write_all(&block).map_err(|error| Error::Io { field: name.clone(), error })?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A builder which writes named fields at explicit offsets of a fixed size buffer.
+///
+/// *This is typically used by tools generating fixed headers from a description.* Each field is
+/// encoded as by the field setters of [ByteBuf] and occupies [SIZE](crate::FixedSize::SIZE)
+/// bytes. Bytes not covered by any field are left as zeros.
+///
+/// The first field which does not fit in the buffer or overlaps a previous field is reported
+/// by [build](LayoutWriter::build); later fields are then ignored.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::schema::LayoutWriter;
+///
+/// let header = LayoutWriter::<8>::new()
+///     .field_le("version", 0, 2u16)
+///     .field_be("flags", 4, 0x0102u16)
+///     .build()
+///     .unwrap();
+/// assert_eq!(header.into_inner(), [2, 0, 0, 0, 1, 2, 0, 0]);
+/// ```
+pub struct LayoutWriter<const N: usize> {
+    buffer: StaticByteBuf<N>,
+    fields: Vec<(String, Range<usize>)>,
+    error: Option<Error>
+}
+
+impl<const N: usize> LayoutWriter<N> {
+    /// Creates a new [LayoutWriter] over a zeroed buffer of `N` bytes.
+    pub fn new() -> LayoutWriter<N> {
+        Self {
+            buffer: StaticByteBuf::new([0; N]),
+            fields: Vec::new(),
+            error: None
+        }
+    }
+
+    /// Writes a little endian field at the given `pos` offset in bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: the name of the field, used in errors.
+    /// * `pos`: the offset of the field in bytes.
+    /// * `value`: the value of the field.
+    ///
+    /// returns: LayoutWriter
+    pub fn field_le<V: WriteBytes>(self, name: impl Into<String>, pos: usize, value: V) -> Self {
+        self.field(name.into(), pos, value, Endian::Little)
+    }
+
+    /// Writes a big endian field at the given `pos` offset in bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: the name of the field, used in errors.
+    /// * `pos`: the offset of the field in bytes.
+    /// * `value`: the value of the field.
+    ///
+    /// returns: LayoutWriter
+    pub fn field_be<V: WriteBytes>(self, name: impl Into<String>, pos: usize, value: V) -> Self {
+        self.field(name.into(), pos, value, Endian::Big)
+    }
+
+    /// Returns the byte range and name of every field written so far, sorted by offset.
+    ///
+    /// *Fields which were rejected, and those following them, are not listed.*
+    pub fn coverage(&self) -> Vec<(Range<usize>, &str)> {
+        let mut coverage: Vec<_> = self.fields.iter().map(|(name, range)| (range.clone(), name.as_str())).collect();
+        coverage.sort_by_key(|(range, _)| range.start);
+        coverage
+    }
+
+    /// Returns the byte ranges of the buffer which are not covered by any field, sorted.
+    pub fn gaps(&self) -> Vec<Range<usize>> {
+        let mut gaps = Vec::new();
+        let mut start = 0;
+        for (range, _) in self.coverage() {
+            if range.start > start {
+                gaps.push(start..range.start);
+            }
+            start = start.max(range.end);
+        }
+        if start < N {
+            gaps.push(start..N);
+        }
+        gaps
+    }
+
+    /// Returns the assembled buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [OutOfBounds](Error::OutOfBounds) error if a field does not fit in the buffer
+    /// or an [Overlap](Error::Overlap) error if a field shares some bytes with a previous field.
+    pub fn build(self) -> Result<StaticByteBuf<N>, Error> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.buffer)
+        }
+    }
+
+    fn field<V: WriteBytes>(mut self, name: String, pos: usize, value: V, endian: Endian) -> Self {
+        if self.error.is_none() {
+            match self.check(&name, pos, V::SIZE) {
+                Ok(()) => {
+                    set(&mut self.buffer.as_mut()[pos..], value, endian);
+                    self.fields.push((name, pos..pos + V::SIZE));
+                },
+                Err(e) => self.error = Some(e)
+            }
+        }
+        self
+    }
+
+    fn check(&self, name: &str, pos: usize, size: usize) -> Result<(), Error> {
+        let end = pos.checked_add(size).filter(|end| *end <= N).ok_or_else(|| Error::OutOfBounds {
+            field: name.into(),
+            pos,
+            size,
+            len: N
+        })?;
+        match self.fields.iter().find(|(_, range)| range.start < end && pos < range.end) {
+            Some((other, _)) => Err(Error::Overlap {
+                field: name.into(),
+                other: other.clone()
+            }),
+            None => Ok(())
+        }
+    }
+}
+
+impl<const N: usize> Default for LayoutWriter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A builder which writes fields at the offsets `#[repr(C)]` would give them, zero padding the
+/// bytes between fields.
+///
+/// Each field is placed at the next multiple of the alignment of its type on the target
+/// platform, as reported by [align_of](core::mem::align_of), and the total size is rounded up to
+/// the largest alignment, so that the output matches the in-memory layout of the equivalent C
+/// structure.
+///
+/// # Examples
+///
+/// ```
+/// use bytesutil::schema::ReprCWriter;
+///
+/// let header = ReprCWriter::new().field_le(1u8).field_be(2u16).field_le(3u8);
+/// assert_eq!(header.offsets(), [0, 2, 4]);
+/// assert_eq!(header.build(), [1, 0, 0, 2, 3, 0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReprCWriter {
+    buffer: Vec<u8>,
+    offsets: Vec<usize>,
+    align: usize
+}
+
+impl ReprCWriter {
+    /// Creates a new empty [ReprCWriter].
+    pub fn new() -> ReprCWriter {
+        Self {
+            buffer: Vec::new(),
+            offsets: Vec::new(),
+            align: 1
+        }
+    }
+
+    /// Writes a little endian field at the next offset aligned for `V`.
+    ///
+    /// returns: ReprCWriter
+    pub fn field_le<V: WriteBytes>(self, value: V) -> Self {
+        self.field(value, Endian::Little)
+    }
+
+    /// Writes a big endian field at the next offset aligned for `V`.
+    ///
+    /// returns: ReprCWriter
+    pub fn field_be<V: WriteBytes>(self, value: V) -> Self {
+        self.field(value, Endian::Big)
+    }
+
+    /// Returns the offset of every field written so far, in order.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// Returns the size of the structure, including the trailing padding.
+    pub fn size(&self) -> usize {
+        self.buffer.len().next_multiple_of(self.align)
+    }
+
+    /// Returns the assembled structure, with its trailing padding.
+    pub fn build(mut self) -> Vec<u8> {
+        self.buffer.resize(self.size(), 0);
+        self.buffer
+    }
+
+    fn field<V: WriteBytes>(mut self, value: V, endian: Endian) -> Self {
+        let align = core::mem::align_of::<V>();
+        let pos = self.buffer.len().next_multiple_of(align);
+        self.buffer.resize(pos + V::SIZE, 0);
+        set(&mut self.buffer[pos..], value, endian);
+        self.offsets.push(pos);
+        self.align = self.align.max(align);
+        self
+    }
+}
+
+impl Default for ReprCWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn get<V: ReadBytes>(bytes: &[u8], endian: Endian) -> V {
+    match endian {
+        Endian::Little => V::read_bytes_le(bytes),
+        Endian::Big => V::read_bytes_be(bytes)
+    }
+}
+
+fn set<V: WriteBytes>(bytes: &mut [u8], value: V, endian: Endian) {
+    match endian {
+        Endian::Little => value.write_bytes_le(bytes),
+        Endian::Big => value.write_bytes_be(bytes)
+    }
+}
+
+fn decode(name: &str, ty: FieldType, bytes: &[u8], endian: Endian) -> Result<Value, Error> {
+    Ok(match ty {
+        FieldType::U8 => Value::U8(get(bytes, endian)),
+        FieldType::U16 => Value::U16(get(bytes, endian)),
+        FieldType::U32 => Value::U32(get(bytes, endian)),
+        FieldType::U64 => Value::U64(get(bytes, endian)),
+        FieldType::I8 => Value::I8(get(bytes, endian)),
+        FieldType::I16 => Value::I16(get(bytes, endian)),
+        FieldType::I32 => Value::I32(get(bytes, endian)),
+        FieldType::I64 => Value::I64(get(bytes, endian)),
+        FieldType::F32 => Value::F32(get(bytes, endian)),
+        FieldType::F64 => Value::F64(get(bytes, endian)),
+        FieldType::Bool => match bytes[0] {
+            0 => Value::Bool(false),
+            1 => Value::Bool(true),
+            value => return Err(Error::InvalidBool { field: name.into(), value })
+        },
+        FieldType::Bytes(_) => Value::Bytes(bytes.to_vec()),
+        FieldType::FixedStr(_) => {
+            let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            let s = std::str::from_utf8(&bytes[..len]).map_err(|_| Error::InvalidUtf8 { field: name.into() })?;
+            Value::Str(s.into())
+        }
+    })
+}
+
+fn encode(name: &str, ty: FieldType, value: &Value, bytes: &mut [u8], endian: Endian) -> Result<(), Error> {
+    match (ty, value) {
+        (FieldType::U8, Value::U8(v)) => set(bytes, *v, endian),
+        (FieldType::U16, Value::U16(v)) => set(bytes, *v, endian),
+        (FieldType::U32, Value::U32(v)) => set(bytes, *v, endian),
+        (FieldType::U64, Value::U64(v)) => set(bytes, *v, endian),
+        (FieldType::I8, Value::I8(v)) => set(bytes, *v, endian),
+        (FieldType::I16, Value::I16(v)) => set(bytes, *v, endian),
+        (FieldType::I32, Value::I32(v)) => set(bytes, *v, endian),
+        (FieldType::I64, Value::I64(v)) => set(bytes, *v, endian),
+        (FieldType::F32, Value::F32(v)) => set(bytes, *v, endian),
+        (FieldType::F64, Value::F64(v)) => set(bytes, *v, endian),
+        (FieldType::Bool, Value::Bool(v)) => set(bytes, *v, endian),
+        _ => return Err(Error::TypeMismatch { field: name.into(), expected: ty })
+    }
+    Ok(())
+}
+
+/// Writes `value` followed by zeros up to `size` bytes, without buffering the padding.
+fn write_padded<W: Write>(name: &str, size: usize, value: &[u8], mut dst: W) -> Result<(), Error> {
+    if value.len() > size {
+        return Err(Error::TooLong { field: name.into(), size, len: value.len() });
+    }
+    let io = |error| Error::Io { field: name.into(), error };
+    dst.write_all(value).map_err(io)?;
+    std::io::copy(&mut std::io::repeat(0).take((size - value.len()) as u64), &mut dst).map_err(io)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::ByteBuf;
+    use crate::schema::{Error, FieldType, LayoutWriter, Record, ReprCWriter, Schema, Value};
+
+    fn schema() -> Schema {
+        Schema::new()
+            .field("magic", FieldType::U32)
+            .field("name", FieldType::FixedStr(8))
+            .field("version", FieldType::U16)
+            .field("scale", FieldType::F32)
+            .field("compressed", FieldType::Bool)
+            .field("hash", FieldType::Bytes(2))
+    }
+
+    fn fixture() -> ByteBuf<Vec<u8>> {
+        let mut buffer = ByteBuf::new(vec![0; 21]);
+        buffer.set_be(0, 0x42505846u32).set_be(12, 3u16).set_be(14, 1.5f32).set_be(18, true);
+        buffer.as_mut()[4..7].copy_from_slice(b"bpx");
+        buffer.as_mut()[19..21].copy_from_slice(&[0xAB, 0xCD]);
+        buffer
+    }
+
+    #[test]
+    fn read_modify_write() {
+        let schema = schema();
+        assert_eq!(schema.size(), Some(21));
+        let buffer = fixture();
+        let mut record = schema.read_be(Cursor::new(buffer.as_ref())).unwrap();
+        assert_eq!(record, schema.read_from_buf_be(&buffer, 0).unwrap());
+        assert_eq!(record.get("magic"), Some(&Value::U32(0x42505846)));
+        assert_eq!(record.get("name"), Some(&Value::Str("bpx".into())));
+        assert_eq!(record.get("scale"), Some(&Value::F32(1.5)));
+        assert_eq!(record.get("compressed"), Some(&Value::Bool(true)));
+        assert_eq!(record.get("hash"), Some(&Value::Bytes(vec![0xAB, 0xCD])));
+        assert_eq!(record.insert("version", Value::U16(4)), Some(Value::U16(3)));
+        let mut out = Vec::new();
+        schema.write_be(&record, &mut out).unwrap();
+        let mut expected = fixture();
+        expected.set_be(12, 4u16);
+        assert_eq!(out, expected.into_inner());
+        let record = schema.read_le(Cursor::new(&out)).unwrap();
+        assert_eq!(record.get("version"), Some(&Value::U16(0x0400)));
+    }
+
+    #[test]
+    fn errors() {
+        let schema = schema();
+        let mut buffer = fixture();
+        buffer.as_mut()[5] = 0xFF;
+        match schema.read_from_buf_be(&buffer, 0) {
+            Err(Error::InvalidUtf8 { field }) => assert_eq!(field, "name"),
+            _ => panic!("expected an invalid UTF-8 error")
+        }
+        match schema.read_from_buf_be(&buffer, 4) {
+            Err(Error::OutOfBounds { field, pos, .. }) => assert_eq!((field.as_str(), pos), ("scale", 18)),
+            _ => panic!("expected an out of bounds error")
+        }
+        match schema.read_le(Cursor::new(&buffer.as_ref()[..10])) {
+            Err(Error::Io { field, .. }) => assert_eq!(field, "name"),
+            _ => panic!("expected an io error")
+        }
+        let mut record = Record::new();
+        record.insert("magic", Value::U16(1));
+        match schema.write_le(&record, Vec::new()) {
+            Err(Error::TypeMismatch { field, expected }) => assert_eq!((field.as_str(), expected), ("magic", FieldType::U32)),
+            _ => panic!("expected a type mismatch error")
+        }
+        record.insert("magic", Value::U32(1));
+        record.insert("name", Value::Str("too long a name".into()));
+        match schema.write_le(&record, Vec::new()) {
+            Err(Error::TooLong { field, .. }) => assert_eq!(field, "name"),
+            _ => panic!("expected a too long error")
+        }
+        record.insert("name", Value::Str("ok".into()));
+        match schema.write_le(&record, Vec::new()) {
+            Err(e @ Error::Missing { .. }) => assert_eq!(e.to_string(), "field 'version': missing value"),
+            _ => panic!("expected a missing field error")
+        }
+        buffer.as_mut()[5] = b'p';
+        buffer.as_mut()[18] = 2;
+        match schema.read_le(Cursor::new(buffer.as_ref())) {
+            Err(e @ Error::InvalidBool { .. }) => assert_eq!(e.to_string(), "field 'compressed': invalid boolean byte 0x02"),
+            _ => panic!("expected an invalid boolean error")
+        }
+    }
+
+    #[test]
+    fn huge_fields() {
+        let schema = Schema::new().field("blob", FieldType::Bytes(usize::MAX)).field("name", FieldType::FixedStr(usize::MAX / 2));
+        match schema.read_le(Cursor::new([1, 2, 3])) {
+            Err(Error::Io { field, error }) => assert_eq!((field.as_str(), error.kind()), ("blob", std::io::ErrorKind::UnexpectedEof)),
+            _ => panic!("expected an io error")
+        }
+        let mut record = Record::new();
+        record.insert("blob", Value::U8(1));
+        match schema.write_le(&record, Vec::new()) {
+            Err(Error::TypeMismatch { field, .. }) => assert_eq!(field, "blob"),
+            _ => panic!("expected a type mismatch error")
+        }
+        let schema = Schema::new().field("name", FieldType::FixedStr(6));
+        let mut record = Record::new();
+        record.insert("name", Value::Str("bpx".into()));
+        let mut out = Vec::new();
+        schema.write_le(&record, &mut out).unwrap();
+        assert_eq!(out, b"bpx\0\0\0");
+    }
+
+    #[test]
+    fn layout_writer() {
+        let header = LayoutWriter::<21>::new()
+            .field_be("magic", 0, 0x42505846u32)
+            .field_be("version", 12, 3u16)
+            .field_be("scale", 14, 1.5f32)
+            .field_be("compressed", 18, true)
+            .field_le("hash", 19, 0xCDABu16)
+            .build()
+            .unwrap();
+        let mut expected = fixture();
+        expected.as_mut()[4..7].fill(0);
+        assert_eq!(header.as_ref(), expected.as_ref());
+        let header = LayoutWriter::<8>::new().field_le("a", 0, 1u32).field_le("b", 4, 2u32).build().unwrap();
+        assert_eq!(header.get_le::<u64>(0), 0x0000000200000001);
+        match LayoutWriter::<8>::new().field_le("a", 0, 1u32).field_le("b", 3, 2u16).field_le("c", 9, 0u8).build() {
+            Err(e @ Error::Overlap { .. }) => assert_eq!(e.to_string(), "field 'b': overlaps field 'a'"),
+            _ => panic!("expected an overlap error")
+        }
+        match LayoutWriter::<8>::new().field_le("a", 6, 1u32).build() {
+            Err(Error::OutOfBounds { field, pos, size, len }) => assert_eq!((field.as_str(), pos, size, len), ("a", 6, 4, 8)),
+            _ => panic!("expected an out of bounds error")
+        }
+        assert!(LayoutWriter::<8>::new().field_le("a", usize::MAX, 0u8).build().is_err());
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn coverage() {
+        let schema = schema();
+        let coverage = schema.coverage().unwrap();
+        assert_eq!(coverage[1..3], [(4..12, "name"), (12..14, "version")]);
+        assert_eq!(coverage.last(), Some(&(19..21, "hash")));
+        assert_eq!(Schema::new().coverage(), Some(vec![]));
+        let huge = Schema::new().field("a", FieldType::Bytes(usize::MAX)).field("b", FieldType::U8);
+        assert_eq!(huge.size(), None);
+        assert_eq!(huge.coverage(), None);
+        let layout = LayoutWriter::<16>::new()
+            .field_be("version", 4, 1u16)
+            .field_be("magic", 0, 0x42505846u32)
+            .field_le("size", 8, 0u32);
+        assert_eq!(layout.coverage(), [(0..4, "magic"), (4..6, "version"), (8..12, "size")]);
+        assert_eq!(layout.gaps(), [6..8, 12..16]);
+        let full = LayoutWriter::<4>::new().field_le("a", 2, 0u16).field_le("b", 0, 0u16);
+        assert_eq!(full.gaps(), []);
+        assert_eq!(LayoutWriter::<3>::new().gaps(), [0..3]);
+    }
+
+    #[test]
+    fn repr_c() {
+        #[repr(C)]
+        struct Native {
+            kind: u8,
+            len: u32,
+            flags: u16,
+            offset: u64,
+            tail: u8
+        }
+
+        let writer = ReprCWriter::new()
+            .field_le(7u8)
+            .field_le(0x01020304u32)
+            .field_be(0x0506u16)
+            .field_le(u64::MAX)
+            .field_le(true);
+        assert_eq!(
+            writer.offsets(),
+            [
+                core::mem::offset_of!(Native, kind),
+                core::mem::offset_of!(Native, len),
+                core::mem::offset_of!(Native, flags),
+                core::mem::offset_of!(Native, offset),
+                core::mem::offset_of!(Native, tail)
+            ]
+        );
+        assert_eq!(writer.size(), core::mem::size_of::<Native>());
+        let bytes = ByteBuf::new(writer.build());
+        assert_eq!(bytes.len(), core::mem::size_of::<Native>());
+        assert_eq!(bytes.get_le::<u32>(core::mem::offset_of!(Native, len)), 0x01020304);
+        assert_eq!(bytes.get_be::<u16>(core::mem::offset_of!(Native, flags)), 0x0506);
+        assert_eq!(bytes.as_ref()[1..4], [0; 3]);
+        assert_eq!(ReprCWriter::new().build(), []);
+    }
+}
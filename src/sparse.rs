@@ -0,0 +1,197 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::io::{Error, ErrorKind, Result, Seek, SeekFrom, Write};
+
+/// A [Write] wrapper which seeks over long runs of zeros instead of writing them, producing
+/// sparse files on filesystems which support them.
+///
+/// A hole left at the end of the stream is only materialized by [finish](SparseWriter::finish),
+/// which must be called to guarantee the length of the output.
+pub struct SparseWriter<W> {
+    inner: W,
+    threshold: usize,
+    hole: u64
+}
+
+impl<W: Write + Seek> SparseWriter<W> {
+    /// Creates a new [SparseWriter].
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the [Write] to wrap.
+    /// * `threshold`: the minimum length of a run of zeros to skip; shorter runs are written
+    ///   normally.
+    pub fn new(inner: W, threshold: usize) -> SparseWriter<W> {
+        Self {
+            inner,
+            threshold,
+            hole: 0
+        }
+    }
+
+    /// Returns a reference to the wrapped [Write].
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped [Write].
+    ///
+    /// *A pending hole is not yet skipped in the wrapped stream, whose position may be behind
+    /// the logical end of the output.*
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    fn skip_hole(&mut self) -> Result<()> {
+        if self.hole > 0 {
+            let offset = i64::try_from(self.hole)
+                .map_err(|_| Error::new(ErrorKind::InvalidInput, "run of zeros is too long to seek over"))?;
+            self.inner.seek(SeekFrom::Current(offset))?;
+            self.hole = 0;
+        }
+        Ok(())
+    }
+
+    fn write_data(&mut self, data: &[u8]) -> Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.skip_hole()?;
+        self.inner.write_all(data)
+    }
+
+    /// Materializes any trailing run of zeros, flushes and returns the wrapped [Write].
+    ///
+    /// The trailing run is materialized by seeking to its last byte and writing a single zero,
+    /// so that the stream ends at the right length.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the trailing byte could not be written or the
+    /// wrapped [Write] failed to flush.
+    pub fn finish(mut self) -> Result<W> {
+        if self.hole > 0 {
+            self.hole -= 1;
+            self.skip_hole()?;
+            self.inner.write_all(&[0])?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write + Seek> Write for SparseWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let mut start = 0;
+        let mut pos = 0;
+        while pos < buf.len() {
+            if buf[pos] != 0 {
+                pos += 1;
+                continue;
+            }
+            let run = buf[pos..].iter().position(|b| *b != 0).unwrap_or(buf.len() - pos);
+            if run >= self.threshold {
+                self.write_data(&buf[start..pos])?;
+                self.hole += run as u64;
+                start = pos + run;
+            }
+            pos += run;
+        }
+        self.write_data(&buf[start..])?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Result, Seek, SeekFrom, Write};
+
+    use crate::SparseWriter;
+
+    struct Counting {
+        inner: Cursor<Vec<u8>>,
+        written: usize
+    }
+
+    impl Write for Counting {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            let len = self.inner.write(buf)?;
+            self.written += len;
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Seek for Counting {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    fn data() -> Vec<u8> {
+        let mut data = vec![0; 10000];
+        data[0] = 1;
+        data[5000..5003].copy_from_slice(&[2, 0, 3]);
+        data
+    }
+
+    #[test]
+    fn skip() {
+        let counting = Counting {
+            inner: Cursor::new(Vec::new()),
+            written: 0
+        };
+        let mut writer = SparseWriter::new(counting, 512);
+        writer.write_all(&data()[..6000]).unwrap();
+        writer.write_all(&data()[6000..]).unwrap();
+        let counting = writer.finish().unwrap();
+        assert_eq!(counting.written, 5);
+        assert_eq!(counting.inner.into_inner(), data());
+    }
+
+    #[test]
+    fn file() {
+        let path = std::env::temp_dir().join(format!("bytesutil-sparse-{}", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        let mut writer = SparseWriter::new(file, 4096);
+        writer.write_all(&data()).unwrap();
+        writer.finish().unwrap();
+        let mut content = Vec::new();
+        std::fs::File::open(&path).unwrap().read_to_end(&mut content).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(content, data());
+    }
+}
@@ -0,0 +1,358 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+/// The maximum number of bytes a single call to [write](Write::write) accepts into the internal
+/// buffer of an [EndianSwapWriter].
+const MAX_CHUNK: usize = 8192;
+
+fn check_width(width: usize) -> usize {
+    assert!(matches!(width, 2 | 4 | 8), "unsupported word width {}", width);
+    width
+}
+
+/// A [Read] wrapper which reverses the byte order of each word of `width` bytes read through
+/// it.
+///
+/// This converts a stream of fixed width values from one byte order to the other without
+/// loading it in memory. Words split across calls to [read](Read::read) are carried over to the
+/// next call.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Read;
+/// use bytesutil::EndianSwapReader;
+///
+/// let mut reader = EndianSwapReader::new(&[0, 0, 0, 1, 0, 0, 0, 2][..], 4);
+/// let mut out = Vec::new();
+/// reader.read_to_end(&mut out).unwrap();
+/// assert_eq!(out, [1, 0, 0, 0, 2, 0, 0, 0]);
+/// ```
+pub struct EndianSwapReader<R> {
+    inner: R,
+    width: usize,
+    word: [u8; 8],
+    partial: usize,
+    ready: usize,
+    served: usize
+}
+
+impl<R> EndianSwapReader<R> {
+    /// Creates a new [EndianSwapReader].
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the [Read] to wrap.
+    /// * `width`: the size in bytes of each word.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is not 2, 4 or 8.
+    pub fn new(inner: R, width: usize) -> EndianSwapReader<R> {
+        Self {
+            inner,
+            width: check_width(width),
+            word: [0; 8],
+            partial: 0,
+            ready: 0,
+            served: 0
+        }
+    }
+
+    /// Returns a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped stream.
+    ///
+    /// *Reading directly from it while a word is split across calls corrupts the output.*
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Extracts the wrapped stream, discarding any partially read word.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn end_of_stream(&self) -> Result<usize> {
+        if self.partial > 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("stream ended after {} bytes of a {} bytes word", self.partial, self.width)
+            ));
+        }
+        Ok(0)
+    }
+}
+
+impl<R: Read> Read for EndianSwapReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let width = self.width;
+        loop {
+            if self.served < self.ready {
+                let len = buf.len().min(self.ready - self.served);
+                buf[..len].copy_from_slice(&self.word[self.served..self.served + len]);
+                self.served += len;
+                return Ok(len);
+            }
+            if buf.len() < width {
+                // Too small for a whole word: assemble it internally and serve it in pieces.
+                let len = self.inner.read(&mut self.word[self.partial..width])?;
+                if len == 0 {
+                    return self.end_of_stream();
+                }
+                self.partial += len;
+                if self.partial == width {
+                    self.word[..width].reverse();
+                    self.partial = 0;
+                    self.ready = width;
+                    self.served = 0;
+                }
+                continue;
+            }
+            let carried = self.partial;
+            buf[..carried].copy_from_slice(&self.word[..carried]);
+            let len = self.inner.read(&mut buf[carried..])?;
+            if len == 0 {
+                return self.end_of_stream();
+            }
+            let total = carried + len;
+            let complete = total - total % width;
+            buf[..complete].chunks_exact_mut(width).for_each(<[u8]>::reverse);
+            self.partial = total - complete;
+            self.word[..self.partial].copy_from_slice(&buf[complete..total]);
+            if complete > 0 {
+                return Ok(complete);
+            }
+        }
+    }
+}
+
+/// A [Write] wrapper which reverses the byte order of each word of `width` bytes written
+/// through it.
+///
+/// This is the counterpart of [EndianSwapReader]. Swapped words are buffered until they can be
+/// written to the wrapped stream, and a word split across calls to [write](Write::write) is
+/// carried over to the next call. [flush](Write::flush) fails if a partial word remains.
+pub struct EndianSwapWriter<W> {
+    inner: W,
+    width: usize,
+    word: [u8; 8],
+    partial: usize,
+    out: Vec<u8>
+}
+
+impl<W: Write> EndianSwapWriter<W> {
+    /// Creates a new [EndianSwapWriter].
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the [Write] to wrap.
+    /// * `width`: the size in bytes of each word.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is not 2, 4 or 8.
+    pub fn new(inner: W, width: usize) -> EndianSwapWriter<W> {
+        Self {
+            inner,
+            width: check_width(width),
+            word: [0; 8],
+            partial: 0,
+            out: Vec::new()
+        }
+    }
+
+    /// Returns a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped stream.
+    ///
+    /// *Swapped words may still be buffered; call [flush](Write::flush) before writing directly
+    /// to it.*
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Flushes this writer and extracts the wrapped stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the buffered words could not be written or if a
+    /// partial word remains.
+    pub fn into_inner(mut self) -> Result<W> {
+        self.flush()?;
+        Ok(self.inner)
+    }
+
+    fn write_out(&mut self) -> Result<()> {
+        let mut written = 0;
+        let res = loop {
+            if written == self.out.len() {
+                break Ok(());
+            }
+            match self.inner.write(&self.out[written..]) {
+                Ok(0) => break Err(Error::new(ErrorKind::WriteZero, "failed to write swapped words")),
+                Ok(len) => written += len,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => break Err(e)
+            }
+        };
+        self.out.drain(..written);
+        res
+    }
+}
+
+impl<W: Write> Write for EndianSwapWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.write_out()?;
+        let width = self.width;
+        let buf = &buf[..buf.len().min(MAX_CHUNK)];
+        let mut consumed = 0;
+        if self.partial > 0 {
+            consumed = buf.len().min(width - self.partial);
+            self.word[self.partial..self.partial + consumed].copy_from_slice(&buf[..consumed]);
+            self.partial += consumed;
+            if self.partial < width {
+                return Ok(consumed);
+            }
+            self.word[..width].reverse();
+            self.out.extend_from_slice(&self.word[..width]);
+            self.partial = 0;
+        }
+        let rest = &buf[consumed..];
+        let complete = rest.len() - rest.len() % width;
+        for word in rest[..complete].chunks_exact(width) {
+            self.out.extend(word.iter().rev());
+        }
+        self.partial = rest.len() - complete;
+        self.word[..self.partial].copy_from_slice(&rest[complete..]);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.write_out()?;
+        if self.partial > 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} bytes of a {} bytes word remain unwritten", self.partial, self.width)
+            ));
+        }
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind, Read, Write};
+
+    use crate::testutil::{ScriptedReader, ShortReader, ShortWriter, Step};
+    use crate::{EndianSwapReader, EndianSwapWriter};
+
+    fn swapped(data: &[u8], width: usize) -> Vec<u8> {
+        let mut out = data.to_vec();
+        out.chunks_exact_mut(width).for_each(<[u8]>::reverse);
+        out
+    }
+
+    #[test]
+    fn equivalence() {
+        let data: Vec<u8> = (0..=255).collect();
+        for width in [2, 4, 8] {
+            let expected = swapped(&data, width);
+            let mut out = Vec::new();
+            EndianSwapReader::new(&*data, width).read_to_end(&mut out).unwrap();
+            assert_eq!(out, expected);
+            out.clear();
+            EndianSwapReader::new(ShortReader::new(&*data), width).read_to_end(&mut out).unwrap();
+            assert_eq!(out, expected);
+            let mut writer = EndianSwapWriter::new(ShortWriter::new(Vec::new()), width);
+            std::io::copy(&mut ShortReader::new(&*data), &mut writer).unwrap();
+            assert_eq!(writer.into_inner().unwrap().into_inner(), expected);
+        }
+    }
+
+    #[test]
+    fn split_words() {
+        let mut reader = EndianSwapReader::new(
+            ScriptedReader::new([Step::Data(&[1, 2, 3]), Step::Data(&[4, 5]), Step::Data(&[6, 7, 8, 9, 10, 11, 12])]),
+            4
+        );
+        let mut block = [0; 8];
+        assert_eq!(reader.read(&mut block).unwrap(), 4);
+        assert_eq!(block[..4], [4, 3, 2, 1]);
+        assert_eq!(reader.read(&mut block).unwrap(), 8);
+        assert_eq!(block, [8, 7, 6, 5, 12, 11, 10, 9]);
+        assert_eq!(reader.read(&mut block).unwrap(), 0);
+        let mut reader = EndianSwapReader::new(Cursor::new([1, 2, 3, 4]), 4);
+        let mut small = [0; 3];
+        assert_eq!(reader.read(&mut small).unwrap(), 3);
+        assert_eq!(small, [4, 3, 2]);
+        assert_eq!(reader.read(&mut small).unwrap(), 1);
+        assert_eq!(small[0], 1);
+
+        let mut writer = EndianSwapWriter::new(Vec::new(), 2);
+        for chunk in [&[1][..], &[2, 3], &[4, 5, 6, 7], &[8]] {
+            writer.write_all(chunk).unwrap();
+        }
+        assert_eq!(writer.into_inner().unwrap(), [2, 1, 4, 3, 6, 5, 8, 7]);
+    }
+
+    #[test]
+    fn partial_word() {
+        let mut reader = EndianSwapReader::new(&[1, 2, 3, 4, 5, 6][..], 4);
+        let mut out = Vec::new();
+        let err = reader.read_to_end(&mut out).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(err.to_string(), "stream ended after 2 bytes of a 4 bytes word");
+        assert_eq!(out, [4, 3, 2, 1]);
+
+        let mut writer = EndianSwapWriter::new(Vec::new(), 8);
+        writer.write_all(&[1; 12]).unwrap();
+        let err = writer.flush().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(writer.get_ref().len(), 8);
+        writer.write_all(&[2; 4]).unwrap();
+        assert_eq!(writer.into_inner().unwrap().len(), 16);
+    }
+
+    #[test]
+    #[should_panic(expected = "unsupported word width 3")]
+    fn bad_width() {
+        EndianSwapReader::new(&[0u8][..], 3);
+    }
+}
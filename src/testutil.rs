@@ -0,0 +1,660 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Round-trip assertions, partial IO wrappers and scripted IO test doubles for testing types
+//! built on top of this crate.
+//!
+//! This module is only available with the `test-util` feature.
+
+use std::fmt::Debug;
+use std::io::{Error, ErrorKind, Read, Seek, SeekFrom, Write};
+
+use crate::{FixedSize, ReadBytes, ReadFrom, WriteBytes, WriteTo};
+
+/// Asserts that a value encodes to the expected bytes in both endians and decodes back to
+/// itself.
+///
+/// Only the first `expected_le.len()` (respectively `expected_be.len()`) bytes of the encoded
+/// value are compared, which allows checking variable length encodings against their
+/// [SIZE](crate::FixedSize::SIZE).
+///
+/// # Arguments
+///
+/// * `value`: the value to test.
+/// * `expected_le`: the expected little endian encoding.
+/// * `expected_be`: the expected big endian encoding.
+///
+/// # Panics
+///
+/// Panics if the encoding does not match or if the decoded value differs from `value`.
+pub fn assert_roundtrip_bytes<T: ReadBytes + WriteBytes + PartialEq + Debug>(
+    value: T,
+    expected_le: &[u8],
+    expected_be: &[u8]
+) {
+    let mut buffer = vec![0; T::SIZE];
+    value.write_bytes_le(&mut buffer);
+    assert_eq!(&buffer[..expected_le.len()], expected_le, "little endian encoding mismatch");
+    assert_eq!(T::read_bytes_le(&buffer), value, "little endian decoding mismatch");
+    buffer.fill(0);
+    value.write_bytes_be(&mut buffer);
+    assert_eq!(&buffer[..expected_be.len()], expected_be, "big endian encoding mismatch");
+    assert_eq!(T::read_bytes_be(&buffer), value, "big endian decoding mismatch");
+}
+
+/// Decodes a value from raw bytes in both endians, re-encodes it and asserts the encoding is
+/// stable.
+///
+/// This is suitable as the body of a fuzz target. Inputs shorter than
+/// [SIZE](crate::FixedSize::SIZE) are ignored. Decoding, encoding and decoding again must yield
+/// the same value, and encoding that value again must yield the same bytes; values which are not
+/// equal to themselves, such as NaN, are only checked through their bytes.
+///
+/// *`T` must accept any sequence of [SIZE](crate::FixedSize::SIZE) bytes: types which panic on
+/// invalid input report that panic.*
+///
+/// # Arguments
+///
+/// * `data`: the raw input.
+///
+/// # Panics
+///
+/// Panics if the encoding of the decoded value is not stable.
+pub fn fuzz_roundtrip<T: ReadBytes + WriteBytes + PartialEq + Debug>(data: &[u8]) {
+    if data.len() < T::SIZE {
+        return;
+    }
+    check_stable("little", data, T::read_bytes_le, T::write_bytes_le);
+    check_stable("big", data, T::read_bytes_be, T::write_bytes_be);
+}
+
+fn check_stable<T: FixedSize + PartialEq + Debug>(endian: &str, data: &[u8], read: fn(&[u8]) -> T, write: fn(&T, &mut [u8])) {
+    let mut first = vec![0; T::SIZE];
+    let mut second = vec![0; T::SIZE];
+    let value = read(data);
+    write(&value, &mut first);
+    let decoded = read(&first);
+    write(&decoded, &mut second);
+    #[allow(clippy::eq_op)]
+    let comparable = value == value;
+    assert!(!comparable || decoded == value, "{} endian decoding is not stable: {:?} != {:?}", endian, decoded, value);
+    assert_eq!(first, second, "{} endian encoding is not stable for {:?}", endian, value);
+}
+
+/// Asserts that a value written to a stream in both endians reads back to itself and that
+/// reading consumes exactly the written bytes.
+///
+/// Both directions go through [ShortWriter] and [ShortReader] in order to catch
+/// implementations which assume a single call to [read](Read::read) or [write](Write::write)
+/// transfers all the data.
+///
+/// # Arguments
+///
+/// * `value`: the value to test.
+///
+/// # Panics
+///
+/// Panics if any IO operation fails or if the decoded value differs from `value`.
+pub fn assert_roundtrip_io<T: ReadFrom + WriteTo + PartialEq + Debug>(value: T) {
+    let mut le = ShortWriter::new(Vec::new());
+    value.write_to_le(&mut le).expect("failed to write little endian value");
+    let le = le.into_inner();
+    let mut reader = ShortReader::new(&le[..]);
+    assert_eq!(T::read_from_le(&mut reader).expect("failed to read little endian value"), value);
+    assert!(reader.into_inner().is_empty(), "little endian value was not fully read");
+    let mut be = ShortWriter::new(Vec::new());
+    value.write_to_be(&mut be).expect("failed to write big endian value");
+    let be = be.into_inner();
+    let mut reader = ShortReader::new(&be[..]);
+    assert_eq!(T::read_from_be(&mut reader).expect("failed to read big endian value"), value);
+    assert!(reader.into_inner().is_empty(), "big endian value was not fully read");
+}
+
+/// A [Read] wrapper which returns at most 1 byte per call to [read](Read::read).
+pub struct ShortReader<R> {
+    inner: R
+}
+
+impl<R> ShortReader<R> {
+    /// Creates a new [ShortReader].
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the [Read] to wrap.
+    pub fn new(inner: R) -> ShortReader<R> {
+        Self { inner }
+    }
+
+    /// Returns a reference to the wrapped [Read].
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped [Read].
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Extracts the wrapped [Read].
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ShortReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(1);
+        self.inner.read(&mut buf[..len])
+    }
+}
+
+/// A [Write] wrapper which accepts at most 1 byte per call to [write](Write::write).
+pub struct ShortWriter<W> {
+    inner: W
+}
+
+impl<W> ShortWriter<W> {
+    /// Creates a new [ShortWriter].
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the [Write] to wrap.
+    pub fn new(inner: W) -> ShortWriter<W> {
+        Self { inner }
+    }
+
+    /// Returns a reference to the wrapped [Write].
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped [Write].
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Extracts the wrapped [Write].
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for ShortWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(1);
+        self.inner.write(&buf[..len])
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A single step of the script played by a [ScriptedReader] or a [ScriptedWriter].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step<'a> {
+    /// Bytes to return from [read](Read::read), or bytes which [write](Write::write) expects to
+    /// receive. Large steps may span several calls.
+    Data(&'a [u8]),
+
+    /// Limits the next call transferring data to at most the given number of bytes.
+    Short(usize),
+
+    /// Fails the next call with an error of the given kind.
+    Error(ErrorKind),
+
+    /// Fails the next call with an error of kind [Interrupted](ErrorKind::Interrupted).
+    Interrupted,
+
+    /// Fails the next call with an error of kind [WouldBlock](ErrorKind::WouldBlock).
+    WouldBlock,
+
+    /// Expects a call to [seek](Seek::seek), which succeeds. Seeks are otherwise only played
+    /// when the current step is an error.
+    Seek
+}
+
+struct Script<'a> {
+    steps: Vec<Step<'a>>,
+    index: usize,
+    limit: Option<usize>
+}
+
+impl<'a> Script<'a> {
+    fn new(steps: impl IntoIterator<Item = Step<'a>>) -> Self {
+        Self {
+            steps: steps.into_iter().collect(),
+            index: 0,
+            limit: None
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.index >= self.steps.len()
+    }
+
+    fn error(kind: ErrorKind) -> Error {
+        Error::new(kind, "scripted error")
+    }
+
+    /// Plays steps until the next data step and returns the bytes which may be transferred.
+    fn next_data(&mut self, max: usize) -> std::io::Result<&'a [u8]> {
+        while let Some(&step) = self.steps.get(self.index) {
+            match step {
+                Step::Data(data) => {
+                    let len = data.len().min(max).min(self.limit.unwrap_or(usize::MAX));
+                    return Ok(&data[..len]);
+                },
+                Step::Short(limit) => self.limit = Some(limit),
+                Step::Error(kind) => {
+                    self.index += 1;
+                    return Err(Self::error(kind));
+                },
+                Step::Interrupted => {
+                    self.index += 1;
+                    return Err(Self::error(ErrorKind::Interrupted));
+                },
+                Step::WouldBlock => {
+                    self.index += 1;
+                    return Err(Self::error(ErrorKind::WouldBlock));
+                },
+                Step::Seek => panic!("expected a call to seek at step {}", self.index)
+            }
+            self.index += 1;
+        }
+        Ok(&[])
+    }
+
+    /// Consumes the first `len` bytes of the current data step.
+    fn advance(&mut self, len: usize) {
+        if let Some(Step::Data(data)) = self.steps.get_mut(self.index) {
+            *data = &data[len..];
+            if data.is_empty() {
+                self.index += 1;
+            }
+        }
+        if len > 0 {
+            self.limit = None;
+        }
+    }
+
+    /// Plays the next step if it is an error.
+    fn next_error(&mut self) -> std::io::Result<()> {
+        let kind = match self.steps.get(self.index) {
+            Some(Step::Error(kind)) => *kind,
+            Some(Step::Interrupted) => ErrorKind::Interrupted,
+            Some(Step::WouldBlock) => ErrorKind::WouldBlock,
+            _ => return Ok(())
+        };
+        self.index += 1;
+        Err(Self::error(kind))
+    }
+
+    /// Plays the next step if it is a seek or an error.
+    fn next_seek(&mut self) -> std::io::Result<()> {
+        if let Some(Step::Seek) = self.steps.get(self.index) {
+            self.index += 1;
+            return Ok(());
+        }
+        self.next_error()
+    }
+}
+
+/// Moves `pos` over an image of `len` bytes, as [Cursor](std::io::Cursor) does.
+fn seek_in(pos: &mut u64, len: u64, target: SeekFrom) -> std::io::Result<u64> {
+    let (base, offset) = match target {
+        SeekFrom::Start(offset) => {
+            *pos = offset;
+            return Ok(offset);
+        },
+        SeekFrom::End(offset) => (len, offset),
+        SeekFrom::Current(offset) => (*pos, offset)
+    };
+    match base.checked_add_signed(offset) {
+        Some(new) => {
+            *pos = new;
+            Ok(new)
+        },
+        None => Err(Error::new(ErrorKind::InvalidInput, "invalid seek to a negative or overflowing position"))
+    }
+}
+
+/// A [Read] which plays back a script of [Step]s, in order.
+///
+/// Each call to [read](Read::read) returns data from the current [Data](Step::Data) step or
+/// fails if the current step is an error. Once the script is exhausted, the reader behaves as
+/// if it reached the end of the stream.
+///
+/// The reader also implements [Seek] over an image made of all the [Data](Step::Data) steps,
+/// which fails when the current step is an error. Seeking only moves the reported position: the
+/// next read still returns the next scripted bytes.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{ErrorKind, Read};
+/// use bytesutil::testutil::{ScriptedReader, Step};
+///
+/// let mut reader = ScriptedReader::new([Step::Short(1), Step::Data(&[1, 2]), Step::Interrupted]);
+/// let mut block = [0; 2];
+/// assert_eq!(reader.read(&mut block).unwrap(), 1);
+/// assert_eq!(reader.read(&mut block).unwrap(), 1);
+/// assert_eq!(reader.read(&mut block).unwrap_err().kind(), ErrorKind::Interrupted);
+/// assert!(reader.is_done());
+/// ```
+pub struct ScriptedReader<'a> {
+    script: Script<'a>,
+    pos: u64,
+    len: u64
+}
+
+impl<'a> ScriptedReader<'a> {
+    /// Creates a new [ScriptedReader] playing the given steps.
+    pub fn new(steps: impl IntoIterator<Item = Step<'a>>) -> ScriptedReader<'a> {
+        let script = Script::new(steps);
+        let len = script.steps.iter()
+            .map(|step| match step {
+                Step::Data(data) => data.len() as u64,
+                _ => 0
+            })
+            .sum();
+        Self {
+            script,
+            pos: 0,
+            len
+        }
+    }
+
+    /// Returns true if every step of the script was played.
+    pub fn is_done(&self) -> bool {
+        self.script.is_done()
+    }
+}
+
+impl Read for ScriptedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let data = self.script.next_data(buf.len())?;
+        buf[..data.len()].copy_from_slice(data);
+        self.script.advance(data.len());
+        self.pos += data.len() as u64;
+        Ok(data.len())
+    }
+}
+
+impl Seek for ScriptedReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.script.next_seek()?;
+        seek_in(&mut self.pos, self.len, pos)
+    }
+}
+
+/// A [Write] which plays back a script of [Step]s, in order.
+///
+/// Each call to [write](Write::write) compares the written bytes with the current
+/// [Data](Step::Data) step or fails if the current step is an error. Once the script is
+/// exhausted, writes return 0. A call to [flush](Write::flush) fails if the current step is an
+/// error and succeeds otherwise.
+///
+/// The writer also implements [Seek] over the image of the bytes accepted so far, which fails
+/// when the current step is an error. Seeking only moves the reported position: the next write
+/// is still compared with the next scripted bytes.
+///
+/// # Panics
+///
+/// [write](Write::write) panics if the written bytes differ from the expected bytes.
+pub struct ScriptedWriter<'a> {
+    script: Script<'a>,
+    written: Vec<u8>,
+    pos: u64
+}
+
+impl<'a> ScriptedWriter<'a> {
+    /// Creates a new [ScriptedWriter] playing the given steps.
+    pub fn new(steps: impl IntoIterator<Item = Step<'a>>) -> ScriptedWriter<'a> {
+        Self {
+            script: Script::new(steps),
+            written: Vec::new(),
+            pos: 0
+        }
+    }
+
+    /// Returns true if every step of the script was played.
+    pub fn is_done(&self) -> bool {
+        self.script.is_done()
+    }
+
+    /// Returns all bytes accepted so far.
+    pub fn written(&self) -> &[u8] {
+        &self.written
+    }
+}
+
+impl Write for ScriptedWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let expected = self.script.next_data(buf.len())?;
+        if let Some(index) = buf.iter().zip(expected).position(|(a, b)| a != b) {
+            panic!(
+                "unexpected bytes written at offset {}: expected {:?}, got {:?}",
+                self.written.len() + index,
+                expected,
+                &buf[..expected.len()]
+            );
+        }
+        self.written.extend_from_slice(expected);
+        self.script.advance(expected.len());
+        self.pos += expected.len() as u64;
+        Ok(expected.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.script.next_error()
+    }
+}
+
+impl Seek for ScriptedWriter<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.script.next_seek()?;
+        seek_in(&mut self.pos, self.written.len() as u64, pos)
+    }
+}
+
+/// Runs `f` and returns the raw bytes it logged through defmt, before any framing.
+///
+/// *Only this crate's tests install the capturing logger, as a binary may have a single defmt
+/// logger.*
+#[cfg(all(test, feature = "defmt"))]
+pub(crate) fn capture_defmt<F: FnOnce()>(f: F) -> Vec<u8> {
+    use std::sync::Mutex;
+
+    static CAPTURE: Mutex<()> = Mutex::new(());
+    static BYTES: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+    #[defmt::global_logger]
+    struct Logger;
+
+    defmt::timestamp!("");
+
+    unsafe impl defmt::Logger for Logger {
+        fn acquire() {}
+
+        unsafe fn flush() {}
+
+        unsafe fn release() {}
+
+        unsafe fn write(bytes: &[u8]) {
+            BYTES.lock().unwrap().extend_from_slice(bytes);
+        }
+    }
+
+    let _guard = CAPTURE.lock().unwrap();
+    BYTES.lock().unwrap().clear();
+    f();
+    std::mem::take(&mut *BYTES.lock().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+
+    use super::{ScriptedReader, ScriptedWriter, ShortReader, ShortWriter, Step};
+
+    #[test]
+    fn short_io() {
+        let mut writer = ShortWriter::new(Vec::new());
+        assert_eq!(writer.write(&[1, 2, 3]).unwrap(), 1);
+        writer.write_all(&[2, 3]).unwrap();
+        let data = writer.into_inner();
+        assert_eq!(data, [1, 2, 3]);
+        let mut reader = ShortReader::new(&data[..]);
+        let mut block = [0; 3];
+        assert_eq!(reader.read(&mut block).unwrap(), 1);
+        reader.read_exact(&mut block[1..]).unwrap();
+        assert_eq!(block, [1, 2, 3]);
+    }
+
+    #[test]
+    fn scripted_reader() {
+        let mut reader = ScriptedReader::new([
+            Step::Data(&[1, 2, 3]),
+            Step::WouldBlock,
+            Step::Short(2),
+            Step::Data(&[4, 5, 6]),
+            Step::Error(ErrorKind::BrokenPipe)
+        ]);
+        let mut block = [0; 2];
+        assert_eq!(reader.read(&mut block).unwrap(), 2);
+        assert_eq!(reader.read(&mut block).unwrap(), 1);
+        assert_eq!(block[0], 3);
+        assert_eq!(reader.read(&mut block).unwrap_err().kind(), ErrorKind::WouldBlock);
+        let mut block = [0; 8];
+        assert_eq!(reader.read(&mut block).unwrap(), 2);
+        assert_eq!(reader.read(&mut block).unwrap(), 1);
+        assert!(!reader.is_done());
+        assert_eq!(reader.read(&mut block).unwrap_err().kind(), ErrorKind::BrokenPipe);
+        assert!(reader.is_done());
+        assert_eq!(reader.read(&mut block).unwrap(), 0);
+    }
+
+    #[test]
+    fn scripted_writer() {
+        let mut writer = ScriptedWriter::new([Step::Short(1), Step::Data(b"abc"), Step::Error(ErrorKind::Other)]);
+        assert_eq!(writer.write(b"abc").unwrap(), 1);
+        writer.write_all(b"bc").unwrap();
+        assert!(!writer.is_done());
+        assert_eq!(writer.flush().unwrap_err().kind(), ErrorKind::Other);
+        assert!(writer.is_done());
+        writer.flush().unwrap();
+        assert_eq!(writer.write_all(b"d").unwrap_err().kind(), ErrorKind::WriteZero);
+        assert_eq!(writer.written(), b"abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected bytes written at offset 1")]
+    fn scripted_writer_mismatch() {
+        let mut writer = ScriptedWriter::new([Step::Data(b"abc")]);
+        writer.write_all(b"axc").unwrap();
+    }
+
+    #[test]
+    fn scripted_seek() {
+        let mut reader = ScriptedReader::new([Step::Data(&[1, 2, 3]), Step::Seek, Step::Error(ErrorKind::Other), Step::Data(&[4])]);
+        let mut block = [0; 3];
+        assert_eq!(reader.seek(SeekFrom::End(-1)).unwrap(), 3);
+        reader.read_exact(&mut block).unwrap();
+        assert_eq!(reader.stream_position().unwrap(), 6);
+        assert_eq!(reader.seek(SeekFrom::Current(-2)).unwrap_err().kind(), ErrorKind::Other);
+        assert_eq!(reader.seek(SeekFrom::Current(-7)).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(reader.read(&mut block).unwrap(), 1);
+        assert!(reader.is_done());
+        let mut writer = ScriptedWriter::new([Step::Data(b"ab"), Step::Error(ErrorKind::Other)]);
+        writer.write_all(b"ab").unwrap();
+        assert_eq!(writer.seek(SeekFrom::Start(1)).unwrap_err().kind(), ErrorKind::Other);
+        assert_eq!(writer.seek(SeekFrom::End(-1)).unwrap(), 1);
+        assert!(writer.is_done());
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a call to seek at step 0")]
+    fn scripted_seek_missing() {
+        let mut reader = ScriptedReader::new([Step::Seek, Step::Data(&[1])]);
+        reader.read_exact(&mut [0]).unwrap();
+    }
+
+    #[test]
+    fn fuzz_roundtrip() {
+        use super::fuzz_roundtrip;
+        use crate::{CanonicalF32, StaticByteBuf, U256};
+
+        let data: Vec<u8> = (0..=255).collect();
+        fuzz_roundtrip::<u64>(&data);
+        fuzz_roundtrip::<U256>(&data[200..]);
+        fuzz_roundtrip::<(u8, f32, StaticByteBuf<3>)>(&data);
+        fuzz_roundtrip::<f32>(&0x7FC00001u32.to_le_bytes());
+        fuzz_roundtrip::<CanonicalF32<true>>(&0xFFC00001u32.to_le_bytes());
+        fuzz_roundtrip::<u128>(&data[..4]);
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        use super::{assert_roundtrip_bytes, assert_roundtrip_io, fuzz_roundtrip};
+        use crate::{ByteBuf, CanonicalF64, Endian, StaticByteBuf, VarU64, U256};
+
+        // A seeded xorshift generator, so that failures are reproducible.
+        let mut state = 0x2545F4914F6CDD1Du64;
+        let data: Vec<u8> = (0..1 << 16)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        let mut u = Unstructured::new(&data);
+        let mut endians = [0; 2];
+        for _ in 0..300 {
+            match Endian::arbitrary(&mut u).unwrap() {
+                Endian::Little => endians[0] += 1,
+                Endian::Big => endians[1] += 1
+            }
+            assert_roundtrip_bytes(U256::arbitrary(&mut u).unwrap(), &[], &[]);
+            assert_roundtrip_io(VarU64::arbitrary(&mut u).unwrap());
+            assert_roundtrip_bytes(StaticByteBuf::<5>::arbitrary(&mut u).unwrap(), &[], &[]);
+            let value = CanonicalF64::<true>::arbitrary(&mut u).unwrap();
+            fuzz_roundtrip::<CanonicalF64<true>>(&value.0.to_le_bytes());
+            let bytes = ByteBuf::<Vec<u8>>::arbitrary(&mut u).unwrap();
+            fuzz_roundtrip::<(u16, U256)>(bytes.as_ref());
+        }
+        assert!(endians[0] > 0 && endians[1] > 0);
+        assert_eq!(U256::size_hint(0), (32, Some(32)));
+        assert_eq!(StaticByteBuf::<5>::size_hint(0), (5, Some(5)));
+        assert_eq!(VarU64::size_hint(0), (8, Some(8)));
+    }
+}
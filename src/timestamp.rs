@@ -0,0 +1,192 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Timestamps are encoded as an [i64] number of seconds since the unix epoch followed by a [u32]
+//! number of nanoseconds in the range `0..1_000_000_000`, both in UTC. The layout is identical
+//! for all supported date types so that files are interchangeable.
+
+use crate::{FixedSize, ReadBytes, WriteBytes};
+
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+fn check_parts<T>(secs: i64, nanos: u32, convert: impl FnOnce(i64, u32) -> Option<T>) -> Option<T> {
+    if nanos >= NANOS_PER_SEC {
+        return None;
+    }
+    convert(secs, nanos)
+}
+
+#[cfg(feature = "std")]
+fn out_of_range() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "timestamp out of range")
+}
+
+macro_rules! impl_timestamp {
+    ($t: ty, |$v: ident| $to_parts: expr, |$secs: ident, $nanos: ident| $from_parts: expr) => {
+        impl FixedSize for $t {
+            const SIZE: usize = 12;
+        }
+
+        impl crate::ByteSize for $t {
+            fn byte_size(&self) -> usize {
+                12
+            }
+        }
+
+        impl WriteBytes for $t {
+            fn write_bytes_le(&self, bytes: &mut [u8]) {
+                let $v = self;
+                let parts: (i64, u32) = $to_parts;
+                parts.write_bytes_le(bytes)
+            }
+
+            fn write_bytes_be(&self, bytes: &mut [u8]) {
+                let $v = self;
+                let parts: (i64, u32) = $to_parts;
+                parts.write_bytes_be(bytes)
+            }
+        }
+
+        /// # Panics
+        ///
+        /// Panics if the timestamp is out of range.
+        impl ReadBytes for $t {
+            fn read_bytes_le(bytes: &[u8]) -> Self {
+                let ($secs, $nanos) = <(i64, u32)>::read_bytes_le(bytes);
+                check_parts($secs, $nanos, |$secs, $nanos| $from_parts).expect("timestamp out of range")
+            }
+
+            fn read_bytes_be(bytes: &[u8]) -> Self {
+                let ($secs, $nanos) = <(i64, u32)>::read_bytes_be(bytes);
+                check_parts($secs, $nanos, |$secs, $nanos| $from_parts).expect("timestamp out of range")
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl crate::WriteTo for $t {
+            fn write_to_le<W: std::io::Write>(&self, dst: W) -> std::io::Result<()> {
+                let $v = self;
+                let parts: (i64, u32) = $to_parts;
+                parts.write_to_le(dst)
+            }
+
+            fn write_to_be<W: std::io::Write>(&self, dst: W) -> std::io::Result<()> {
+                let $v = self;
+                let parts: (i64, u32) = $to_parts;
+                parts.write_to_be(dst)
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl crate::ReadFrom for $t {
+            fn read_from_le<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+                let ($secs, $nanos) = <(i64, u32)>::read_from_le(src)?;
+                check_parts($secs, $nanos, |$secs, $nanos| $from_parts).ok_or_else(out_of_range)
+            }
+
+            fn read_from_be<R: std::io::Read>(src: R) -> std::io::Result<Self> {
+                let ($secs, $nanos) = <(i64, u32)>::read_from_be(src)?;
+                check_parts($secs, $nanos, |$secs, $nanos| $from_parts).ok_or_else(out_of_range)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "time")]
+impl_timestamp!(
+    time::OffsetDateTime,
+    |v| (v.unix_timestamp(), v.nanosecond()),
+    |secs, nanos| {
+        let total = secs as i128 * NANOS_PER_SEC as i128 + nanos as i128;
+        time::OffsetDateTime::from_unix_timestamp_nanos(total).ok()
+    }
+);
+
+// Leap seconds, which chrono represents with a nanosecond count above 1e9, are clamped to the
+// last nanosecond of the second.
+#[cfg(feature = "chrono")]
+impl_timestamp!(
+    chrono::DateTime<chrono::Utc>,
+    |v| (v.timestamp(), v.timestamp_subsec_nanos().min(NANOS_PER_SEC - 1)),
+    |secs, nanos| chrono::DateTime::from_timestamp(secs, nanos)
+);
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use crate::{ReadExt, WriteExt};
+
+    fn encode(secs: i64, nanos: u32) -> Cursor<Vec<u8>> {
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_be((secs, nanos)).unwrap();
+        cursor.set_position(0);
+        cursor
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        use time::OffsetDateTime;
+
+        use crate::testutil::assert_roundtrip_io;
+
+        let before_epoch = OffsetDateTime::from_unix_timestamp_nanos(-1_500_000_001).unwrap();
+        assert_roundtrip_io(before_epoch);
+        assert_roundtrip_io(OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_123_456_789).unwrap());
+        assert_eq!(encode(-2, 499_999_999).read_be::<OffsetDateTime>().unwrap(), before_epoch);
+        assert_eq!(encode(0, 1_000_000_000).read_be::<OffsetDateTime>().unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(encode(i64::MAX, 0).read_be::<OffsetDateTime>().unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::{DateTime, Utc};
+
+        use crate::testutil::assert_roundtrip_io;
+
+        let before_epoch = DateTime::<Utc>::from_timestamp(-2, 499_999_999).unwrap();
+        assert_roundtrip_io(before_epoch);
+        assert_roundtrip_io(DateTime::<Utc>::from_timestamp(1_700_000_000, 123_456_789).unwrap());
+        assert_eq!(encode(-2, 499_999_999).read_be::<DateTime<Utc>>().unwrap(), before_epoch);
+        assert_eq!(encode(0, 1_000_000_000).read_be::<DateTime<Utc>>().unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(encode(i64::MAX, 0).read_be::<DateTime<Utc>>().unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[cfg(all(feature = "time", feature = "chrono"))]
+    #[test]
+    fn interchangeable() {
+        let date = time::OffsetDateTime::from_unix_timestamp_nanos(-86_400_000_000_001).unwrap();
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_le(date).unwrap();
+        cursor.set_position(0);
+        let other = cursor.read_le::<chrono::DateTime<chrono::Utc>>().unwrap();
+        assert_eq!((other.timestamp(), other.timestamp_subsec_nanos()), (-86_401, 999_999_999));
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::io::{Read, Result, Seek, SeekFrom};
+use std::ops::Range;
+
+/// A [Read]+[Seek] wrapper which records which byte ranges of the wrapped stream were read.
+///
+/// Ranges are stored as absolute stream offsets in a sorted list where overlapping and adjacent
+/// ranges are merged. Consecutive reads extend the last range in place and reads elsewhere are
+/// located by binary search, so the cost per read grows with the number of disjoint ranges, not
+/// with the size of the stream.
+pub struct TrackingReader<R> {
+    inner: R,
+    pos: Option<u64>,
+    ranges: Vec<Range<u64>>
+}
+
+impl<R> TrackingReader<R> {
+    /// Creates a new [TrackingReader] wrapping the given stream.
+    pub fn new(inner: R) -> TrackingReader<R> {
+        Self {
+            inner,
+            pos: None,
+            ranges: Vec::new()
+        }
+    }
+
+    /// Returns the ranges read so far, sorted and merged.
+    pub fn consumed_ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+
+    /// Returns the ranges which were never read, sorted.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_len`: the length of the stream; ranges read past it are ignored.
+    pub fn unconsumed_ranges(&self, total_len: u64) -> Vec<Range<u64>> {
+        let mut gaps = Vec::new();
+        let mut start = 0;
+        for range in &self.ranges {
+            if range.start >= total_len {
+                break;
+            }
+            if range.start > start {
+                gaps.push(start..range.start);
+            }
+            start = range.end;
+        }
+        if start < total_len {
+            gaps.push(start..total_len);
+        }
+        gaps
+    }
+
+    /// Returns the fraction of the stream which was read, between 0 and 1.
+    ///
+    /// *An empty stream is considered fully read.*
+    ///
+    /// # Arguments
+    ///
+    /// * `total_len`: the length of the stream; ranges read past it are ignored.
+    pub fn coverage(&self, total_len: u64) -> f64 {
+        if total_len == 0 {
+            return 1.0;
+        }
+        let consumed: u64 = self.ranges
+            .iter()
+            .map(|range| range.end.min(total_len).saturating_sub(range.start))
+            .sum();
+        consumed as f64 / total_len as f64
+    }
+
+    /// Returns a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped stream; bytes read through it are not
+    /// recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{Cursor, Read};
+    /// use bytesutil::{TrackingReader, WindowReader};
+    ///
+    /// let window = WindowReader::new(Cursor::new(vec![0u8; 16]), 4, 8).unwrap();
+    /// let mut reader = TrackingReader::new(window);
+    /// reader.read_exact(&mut [0; 2]).unwrap();
+    /// assert_eq!(reader.get_ref().get_ref().position(), 6);
+    /// reader.get_mut().get_mut().get_mut().push(1);
+    /// let data = reader.into_inner().into_inner().into_inner();
+    /// assert_eq!(data.len(), 17);
+    /// ```
+    pub fn get_mut(&mut self) -> &mut R {
+        // The wrapped stream may be moved through the reference.
+        self.pos = None;
+        &mut self.inner
+    }
+
+    /// Extracts the wrapped stream.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn record(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+        if let Some(last) = self.ranges.last_mut() {
+            if last.start <= range.start && range.start <= last.end {
+                last.end = last.end.max(range.end);
+                return;
+            }
+        }
+        // All ranges in first..last touch the new range and are merged into it.
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let mut merged = range;
+        if first < last {
+            merged.start = merged.start.min(self.ranges[first].start);
+            merged.end = merged.end.max(self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, [merged]);
+    }
+}
+
+impl<R: Read + Seek> Read for TrackingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let pos = match self.pos {
+            Some(pos) => pos,
+            None => self.inner.stream_position()?
+        };
+        let len = self.inner.read(buf)?;
+        self.pos = Some(pos + len as u64);
+        self.record(pos..pos + len as u64);
+        Ok(len)
+    }
+}
+
+impl<R: Seek> Seek for TrackingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        // Forget the position first so that it is queried again if the seek fails midway.
+        self.pos = None;
+        let pos = self.inner.seek(pos)?;
+        self.pos = Some(pos);
+        Ok(pos)
+    }
+
+    fn stream_position(&mut self) -> Result<u64> {
+        match self.pos {
+            Some(pos) => Ok(pos),
+            None => self.inner.stream_position()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Seek, SeekFrom};
+
+    use crate::{ReadExt, TrackingReader};
+
+    #[test]
+    fn scattered() {
+        let data: Vec<u8> = (0..64).collect();
+        let mut reader = TrackingReader::new(Cursor::new(data));
+        assert_eq!(reader.read_le::<u32>().unwrap(), 0x03020100);
+        reader.seek(SeekFrom::Start(40)).unwrap();
+        reader.read_be::<u64>().unwrap();
+        reader.seek(SeekFrom::Start(16)).unwrap();
+        reader.read_le::<u16>().unwrap();
+        assert_eq!(reader.consumed_ranges(), [0..4, 16..18, 40..48]);
+        // Overlaps the end of one range and the start of the next, merging both.
+        reader.seek(SeekFrom::Start(17)).unwrap();
+        let mut block = [0; 24];
+        reader.read_exact(&mut block).unwrap();
+        assert_eq!(reader.consumed_ranges(), [0..4, 16..48]);
+        // Adjacent to the first range and fully inside an existing one.
+        reader.seek(SeekFrom::Current(-37)).unwrap();
+        reader.read_le::<u8>().unwrap();
+        reader.seek(SeekFrom::Start(20)).unwrap();
+        reader.read_le::<u32>().unwrap();
+        assert_eq!(reader.consumed_ranges(), [0..5, 16..48]);
+        assert_eq!(reader.unconsumed_ranges(64), [5..16, 48..64]);
+        assert_eq!(reader.coverage(64), 37.0 / 64.0);
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn bounds() {
+        let mut cursor = Cursor::new(vec![0; 8]);
+        cursor.set_position(6);
+        let mut reader = TrackingReader::new(cursor);
+        reader.read_le::<u16>().unwrap();
+        assert!(reader.read_le::<u8>().is_err());
+        assert_eq!(reader.consumed_ranges(), [6..8]);
+        assert_eq!(reader.unconsumed_ranges(8), [0..6]);
+        assert_eq!(reader.unconsumed_ranges(4), [0..4]);
+        assert_eq!(reader.coverage(4), 0.0);
+        assert_eq!(TrackingReader::new(Cursor::new([0u8; 0])).coverage(0), 1.0);
+        reader.rewind().unwrap();
+        let mut all = Vec::new();
+        reader.read_to_end(&mut all).unwrap();
+        assert_eq!(reader.consumed_ranges(), [0..8]);
+        assert_eq!(reader.unconsumed_ranges(8), []);
+        assert_eq!(reader.coverage(8), 1.0);
+    }
+
+    #[test]
+    fn moved_through_get_mut() {
+        let mut reader = TrackingReader::new(Cursor::new(vec![0; 16]));
+        reader.read_le::<u16>().unwrap();
+        reader.get_mut().set_position(8);
+        reader.read_le::<u16>().unwrap();
+        assert_eq!(reader.consumed_ranges(), [0..2, 8..10]);
+        assert_eq!(reader.get_ref().position(), 10);
+    }
+}
@@ -26,8 +26,10 @@
 // NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::io::Read;
-use std::io::Result;
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+
+use crate::{Endian, FixedSize, ReadFrom, WriteTo};
 
 /// Allows to read into a buffer as much as possible.
 ///
@@ -48,14 +50,62 @@ pub trait ReadFill: Read {
     /// Returns an [Error](std::io::Error) when read has failed.
     fn read_fill(&mut self, buf: &mut [u8]) -> Result<usize> {
         let mut bytes = 0;
-        let mut len = self.read(buf)?;
-        bytes += len;
-        while len > 0 && buf.len() - len > 0 {
-            len = self.read(&mut buf[len..])?;
-            bytes += len;
+        while bytes < buf.len() {
+            match self.read(&mut buf[bytes..]) {
+                Ok(0) => break,
+                Ok(len) => bytes += len,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e)
+            }
         }
         Ok(bytes)
     }
+
+    /// Reads into `buf[*filled..]` as much as possible without blocking.
+    ///
+    /// `filled` is advanced by every byte read, including when an error is returned, so that the
+    /// call can be resumed later with the same `filled` cursor. Interrupted reads are retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf`: the buffer to read into.
+    /// * `filled`: the number of bytes of `buf` which are already filled.
+    ///
+    /// returns: Result<FillStatus, Error>
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) when read has failed for any reason other than
+    /// [WouldBlock](ErrorKind::WouldBlock), which is reported as [Pending](FillStatus::Pending).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `filled` is greater than the length of `buf`.
+    fn read_fill_nonblocking(&mut self, buf: &mut [u8], filled: &mut usize) -> Result<FillStatus> {
+        while *filled < buf.len() {
+            match self.read(&mut buf[*filled..]) {
+                Ok(0) => return Ok(FillStatus::Eof(*filled)),
+                Ok(len) => *filled += len,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(FillStatus::Pending),
+                Err(e) => return Err(e)
+            }
+        }
+        Ok(FillStatus::Full)
+    }
+}
+
+/// The outcome of [read_fill_nonblocking](ReadFill::read_fill_nonblocking).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillStatus {
+    /// The buffer is completely filled.
+    Full,
+
+    /// The stream ended after the given total number of bytes were filled.
+    Eof(usize),
+
+    /// The stream has no data available yet; the call should be repeated later.
+    Pending
 }
 
 //Unfortunately it's impossible in rust to allow an extension of the Read trait with custom
@@ -74,4 +124,616 @@ pub trait ReadToVec: Read {
         self.read_to_end(&mut data)?;
         Ok(data)
     }
+
+    /// Reads the rest of this stream and appends it to `buf`, reusing its spare capacity.
+    ///
+    /// *This is typically used to accumulate several streams into a single buffer without
+    /// reallocating it for each stream.*
+    ///
+    /// returns: the number of bytes appended.
+    ///
+    /// # Errors
+    ///
+    /// An [Error](std::io::Error) is returned if the stream could not be loaded. The bytes read
+    /// before the error are still appended to `buf`.
+    fn read_append(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+        self.read_to_end(buf)
+    }
+}
+
+impl<T: Read + ?Sized> ReadToVec for T {}
+
+/// Allows reading at an absolute offset of a seekable stream without losing the current
+/// position.
+///
+/// *This is typically used to follow the offsets of a pointer table.*
+pub trait ReadAt: Read + Seek + Sized {
+    /// Seeks to `offset`, runs `f` and seeks back to the position the stream had before the call.
+    ///
+    /// The position is restored even if `f` fails or panics.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset`: the absolute offset to seek to.
+    /// * `f`: the function to run at `offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if `f` failed or if the stream could not be seeked.
+    fn read_at_with<T, F: FnOnce(&mut Self) -> Result<T>>(&mut self, offset: u64, f: F) -> Result<T> {
+        let pos = self.stream_position()?;
+        let guard = Restore { inner: self, pos };
+        guard.inner.seek(SeekFrom::Start(offset))?;
+        let res = f(guard.inner);
+        let restored = guard.restore();
+        let value = res?;
+        restored?;
+        Ok(value)
+    }
+
+    /// Reads a value at the absolute offset `offset`, in little endian order, then restores the
+    /// position of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the value could not be read or if the stream could
+    /// not be seeked.
+    fn read_at_le<T: ReadFrom>(&mut self, offset: u64) -> Result<T> {
+        self.read_at_with(offset, |src| crate::ReadExt::read_le(src))
+    }
+
+    /// Reads a value at the absolute offset `offset`, in big endian order, then restores the
+    /// position of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the value could not be read or if the stream could
+    /// not be seeked.
+    fn read_at_be<T: ReadFrom>(&mut self, offset: u64) -> Result<T> {
+        self.read_at_with(offset, |src| crate::ReadExt::read_be(src))
+    }
+
+    /// Fills `buf` with the bytes at the absolute offset `offset`, then restores the position of
+    /// the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if `buf` could not be filled or if the stream could not
+    /// be seeked.
+    fn read_bytes_at(&mut self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        self.read_at_with(offset, |src| src.read_exact(buf))
+    }
+}
+
+impl<T: Read + Seek> ReadAt for T {}
+
+/// A [Read]+[Seek] stream, usable as a trait object such as `Box<dyn ReadSeek>`.
+///
+/// This is implemented for every [Read]+[Seek] type.
+pub trait ReadSeek: Read + Seek {
+    /// Moves self into a boxed trait object.
+    fn into_read_seek<'a>(self) -> Box<dyn ReadSeek + 'a>
+    where
+        Self: Sized + 'a
+    {
+        Box::new(self)
+    }
+}
+
+impl<T: Read + Seek + ?Sized> ReadSeek for T {}
+
+/// A [Write]+[Seek] stream, usable as a trait object such as `Box<dyn WriteSeek>`.
+///
+/// This is implemented for every [Write]+[Seek] type.
+pub trait WriteSeek: Write + Seek {
+    /// Moves self into a boxed trait object.
+    fn into_write_seek<'a>(self) -> Box<dyn WriteSeek + 'a>
+    where
+        Self: Sized + 'a
+    {
+        Box::new(self)
+    }
+}
+
+impl<T: Write + Seek + ?Sized> WriteSeek for T {}
+
+/// A [Read]+[Write] stream, usable as a trait object such as `Box<dyn ReadWrite>`.
+///
+/// This is implemented for every [Read]+[Write] type.
+pub trait ReadWrite: Read + Write {
+    /// Moves self into a boxed trait object.
+    fn into_read_write<'a>(self) -> Box<dyn ReadWrite + 'a>
+    where
+        Self: Sized + 'a
+    {
+        Box::new(self)
+    }
+}
+
+impl<T: Read + Write + ?Sized> ReadWrite for T {}
+
+/// A [Read]+[Write]+[Seek] stream, usable as a trait object such as `Box<dyn ReadWriteSeek>`.
+///
+/// This is implemented for every [Read]+[Write]+[Seek] type.
+///
+/// *A [Combine](crate::Combine) of a [Read]+[Seek] and a [Write]+[Seek] is a [ReadWriteSeek], so
+/// it can be stored behind the same trait object as a file or a cursor.*
+pub trait ReadWriteSeek: Read + Write + Seek {
+    /// Moves self into a boxed trait object.
+    fn into_read_write_seek<'a>(self) -> Box<dyn ReadWriteSeek + 'a>
+    where
+        Self: Sized + 'a
+    {
+        Box::new(self)
+    }
+}
+
+impl<T: Read + Write + Seek + ?Sized> ReadWriteSeek for T {}
+
+/// Allows querying the length of a seekable stream without losing the current position.
+///
+/// *This is typically used to check that a size read from a header fits in the rest of the
+/// stream before reading or allocating for it, or to locate a trailer.* It is implemented for
+/// unsized streams too, such as `dyn ReadSeek`.
+///
+/// [stream_len](SeekExt::stream_len) is a stable equivalent of the unstable
+/// `Seek::stream_len`; when both traits are in scope, call it as `SeekExt::stream_len(&mut s)`
+/// to avoid the name collision.
+pub trait SeekExt: Seek {
+    /// Returns the total length of this stream in bytes.
+    ///
+    /// The position of the stream is restored, even if seeking to the end fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the stream could not be seeked.
+    fn stream_len(&mut self) -> Result<u64> {
+        let pos = self.stream_position()?;
+        let guard = Restore { inner: self, pos };
+        let len = guard.inner.seek(SeekFrom::End(0));
+        let restored = guard.restore();
+        let len = len?;
+        restored?;
+        Ok(len)
+    }
+
+    /// Returns the number of bytes between the current position and the end of this stream.
+    ///
+    /// returns: 0 if the position is at or past the end of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the stream could not be seeked.
+    fn remaining(&mut self) -> Result<u64> {
+        let pos = self.stream_position()?;
+        Ok(SeekExt::stream_len(self)?.saturating_sub(pos))
+    }
+
+    /// Returns true if the current position is at or past the end of this stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the stream could not be seeked.
+    fn is_at_end(&mut self) -> Result<bool> {
+        self.remaining().map(|remaining| remaining == 0)
+    }
+
+    /// Checks that at least `n` bytes remain between the current position and the end of this
+    /// stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the stream could not be seeked. If fewer than `n`
+    /// bytes remain, an error of kind [UnexpectedEof](ErrorKind::UnexpectedEof) stating the
+    /// number of missing bytes is returned.
+    fn ensure_remaining(&mut self, n: u64) -> Result<()> {
+        let remaining = self.remaining()?;
+        if remaining < n {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("stream has {} bytes left, {} bytes short of {}", remaining, n - remaining, n)
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<T: Seek + ?Sized> SeekExt for T {}
+
+/// Allows reserving room for a field of a seekable stream, to be filled once its value is known.
+///
+/// *This is typically used for checksums or offsets which depend on data written later.*
+pub trait WriteReserve: Write + Seek + Sized {
+    /// Writes [SIZE](FixedSize::SIZE) zero bytes in place of a value of type `T`, to be filled
+    /// later in little endian order.
+    ///
+    /// returns: Result<Placeholder<T>, Error>
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the position of the stream could not be obtained or
+    /// if the zero bytes could not be written.
+    fn reserve_le<T: WriteTo + FixedSize>(&mut self) -> Result<Placeholder<T>> {
+        Placeholder::reserve(self, Endian::Little)
+    }
+
+    /// Writes [SIZE](FixedSize::SIZE) zero bytes in place of a value of type `T`, to be filled
+    /// later in big endian order.
+    ///
+    /// returns: Result<Placeholder<T>, Error>
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the position of the stream could not be obtained or
+    /// if the zero bytes could not be written.
+    fn reserve_be<T: WriteTo + FixedSize>(&mut self) -> Result<Placeholder<T>> {
+        Placeholder::reserve(self, Endian::Big)
+    }
+}
+
+impl<T: Write + Seek> WriteReserve for T {}
+
+/// A field reserved by [WriteReserve], waiting for its value.
+#[derive(Debug)]
+#[must_use = "a reserved field is left zeroed unless it is filled"]
+pub struct Placeholder<T> {
+    pos: u64,
+    endian: Endian,
+    marker: PhantomData<T>
+}
+
+impl<T: WriteTo + FixedSize> Placeholder<T> {
+    fn reserve<W: Write + Seek>(dst: &mut W, endian: Endian) -> Result<Self> {
+        let pos = dst.stream_position()?;
+        crate::WriteExt::write_padding(dst, T::SIZE, 0)?;
+        Ok(Self {
+            pos,
+            endian,
+            marker: PhantomData
+        })
+    }
+
+    /// Returns the absolute offset of the reserved field.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Returns the byte order the field is filled with.
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Writes `value` in the reserved field, then restores the position of the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `dst`: the stream the field was reserved in.
+    /// * `value`: the value of the field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the value could not be written or if the stream
+    /// could not be seeked.
+    pub fn fill<W: Write + Seek>(self, dst: &mut W, value: T) -> Result<()> {
+        let pos = dst.stream_position()?;
+        let guard = Restore { inner: dst, pos };
+        guard.inner.seek(SeekFrom::Start(self.pos))?;
+        let res = match self.endian {
+            Endian::Little => value.write_to_le(&mut *guard.inner),
+            Endian::Big => value.write_to_be(&mut *guard.inner)
+        };
+        let restored = guard.restore();
+        res?;
+        restored
+    }
+}
+
+/// Seeks back to a saved position when dropped.
+struct Restore<'a, S: Seek + ?Sized> {
+    inner: &'a mut S,
+    pos: u64
+}
+
+impl<S: Seek + ?Sized> Restore<'_, S> {
+    fn restore(self) -> Result<()> {
+        let res = self.inner.seek(SeekFrom::Start(self.pos));
+        std::mem::forget(self);
+        res.map(|_| ())
+    }
+}
+
+impl<S: Seek + ?Sized> Drop for Restore<'_, S> {
+    fn drop(&mut self) {
+        let _ = self.inner.seek(SeekFrom::Start(self.pos));
+    }
+}
+
+/// The size of the stack buffer used by [copy_n] and [copy_n_with_progress].
+const COPY_BUFFER_SIZE: usize = 8192;
+
+/// Copies exactly `n` bytes from `reader` to `writer`.
+///
+/// # Errors
+///
+/// Returns an [Error](std::io::Error) if the copy has failed. If `reader` ends before `n` bytes
+/// could be copied, an error of kind [UnexpectedEof](ErrorKind::UnexpectedEof) stating the
+/// number of missing bytes is returned.
+pub fn copy_n<R: Read + ?Sized, W: Write + ?Sized>(reader: &mut R, writer: &mut W, n: u64) -> Result<()> {
+    copy_n_with_buf(reader, writer, n, &mut [0; COPY_BUFFER_SIZE])
+}
+
+/// Copies exactly `n` bytes from `reader` to `writer`, using `buf` as intermediate storage.
+///
+/// # Errors
+///
+/// Returns an [Error](std::io::Error) if the copy has failed. If `reader` ends before `n` bytes
+/// could be copied, an error of kind [UnexpectedEof](ErrorKind::UnexpectedEof) stating the
+/// number of missing bytes is returned.
+///
+/// # Panics
+///
+/// Panics if `buf` is empty and `n` is not zero.
+pub fn copy_n_with_buf<R: Read + ?Sized, W: Write + ?Sized>(reader: &mut R, writer: &mut W, n: u64, buf: &mut [u8]) -> Result<()> {
+    copy_n_impl(reader, writer, n, buf, |_| ())
+}
+
+/// Copies exactly `n` bytes from `reader` to `writer`, calling `progress` with the total number
+/// of bytes copied so far after each chunk.
+///
+/// # Errors
+///
+/// Returns an [Error](std::io::Error) if the copy has failed. If `reader` ends before `n` bytes
+/// could be copied, an error of kind [UnexpectedEof](ErrorKind::UnexpectedEof) stating the
+/// number of missing bytes is returned.
+pub fn copy_n_with_progress<R: Read + ?Sized, W: Write + ?Sized, F: FnMut(u64)>(reader: &mut R, writer: &mut W, n: u64, progress: F) -> Result<()> {
+    copy_n_impl(reader, writer, n, &mut [0; COPY_BUFFER_SIZE], progress)
+}
+
+fn copy_n_impl<R: Read + ?Sized, W: Write + ?Sized, F: FnMut(u64)>(reader: &mut R, writer: &mut W, n: u64, buf: &mut [u8], mut progress: F) -> Result<()> {
+    assert!(n == 0 || !buf.is_empty(), "copy buffer must not be empty");
+    let mut copied = 0;
+    while copied < n {
+        let len = buf.len().min((n - copied).try_into().unwrap_or(usize::MAX));
+        let len = match reader.read(&mut buf[..len]) {
+            Ok(0) => {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!("stream ended {} bytes short of {}", n - copied, n)
+                ));
+            },
+            Ok(len) => len,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e)
+        };
+        writer.write_all(&buf[..len])?;
+        copied += len as u64;
+        progress(copied);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use crate::{copy_n, copy_n_with_buf, copy_n_with_progress, ReadToVec, SeekExt};
+
+    #[test]
+    fn exact() {
+        let data: Vec<u8> = (0..100).collect();
+        let mut out = Vec::new();
+        copy_n(&mut &*data, &mut out, 100).unwrap();
+        assert_eq!(out, data);
+        let mut out = Vec::new();
+        let mut reader = &*data;
+        copy_n_with_buf(&mut reader, &mut out, 60, &mut [0; 7]).unwrap();
+        assert_eq!(out, data[..60]);
+        assert_eq!(reader.len(), 40);
+    }
+
+    #[test]
+    fn short() {
+        let mut out = Vec::new();
+        let err = copy_n(&mut &[1u8, 2, 3][..], &mut out, 5).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert!(err.to_string().contains("2 bytes short"));
+        assert_eq!(out, [1, 2, 3]);
+    }
+
+    #[test]
+    fn zero_length() {
+        let mut out = Vec::new();
+        copy_n(&mut &[][..], &mut out, 0).unwrap();
+        copy_n_with_buf(&mut &[1u8][..], &mut out, 0, &mut []).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn progress() {
+        let data = vec![0u8; 20000];
+        let mut out = Vec::new();
+        let mut calls = Vec::new();
+        copy_n_with_progress(&mut &*data, &mut out, 20000, |n| calls.push(n)).unwrap();
+        assert_eq!(calls, [8192, 16384, 20000]);
+        calls.clear();
+        copy_n_with_progress(&mut &*data, &mut out, 0, |n| calls.push(n)).unwrap();
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    fn read_fill() {
+        use crate::testutil::{ScriptedReader, Step};
+        use crate::ReadFill;
+
+        let mut reader = ScriptedReader::new([
+            Step::Short(1),
+            Step::Data(&[1, 2]),
+            Step::Interrupted,
+            Step::Short(2),
+            Step::Data(&[3, 4, 5])
+        ]);
+        let mut block = [0; 4];
+        assert_eq!(reader.read_fill(&mut block).unwrap(), 4);
+        assert_eq!(block, [1, 2, 3, 4]);
+        assert_eq!(reader.read_fill(&mut block).unwrap(), 1);
+        assert_eq!(block[0], 5);
+        assert!(reader.is_done());
+        let mut reader = ScriptedReader::new([Step::Data(&[1]), Step::Error(ErrorKind::BrokenPipe)]);
+        assert_eq!(reader.read_fill(&mut block).unwrap_err().kind(), ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn read_fill_nonblocking() {
+        use crate::testutil::{ScriptedReader, Step};
+        use crate::{FillStatus, ReadFill};
+
+        let mut reader = ScriptedReader::new([
+            Step::Data(&[1, 2]),
+            Step::WouldBlock,
+            Step::Interrupted,
+            Step::Short(1),
+            Step::Data(&[3, 4]),
+            Step::WouldBlock,
+            Step::Data(&[5, 6, 7])
+        ]);
+        let mut block = [0; 6];
+        let mut filled = 0;
+        assert_eq!(reader.read_fill_nonblocking(&mut block, &mut filled).unwrap(), FillStatus::Pending);
+        assert_eq!(filled, 2);
+        assert_eq!(reader.read_fill_nonblocking(&mut block, &mut filled).unwrap(), FillStatus::Pending);
+        assert_eq!(filled, 4);
+        assert_eq!(reader.read_fill_nonblocking(&mut block, &mut filled).unwrap(), FillStatus::Full);
+        assert_eq!(block, [1, 2, 3, 4, 5, 6]);
+        assert_eq!(reader.read_fill_nonblocking(&mut block, &mut filled).unwrap(), FillStatus::Full);
+        let mut filled = 0;
+        assert_eq!(reader.read_fill_nonblocking(&mut block, &mut filled).unwrap(), FillStatus::Eof(1));
+        assert_eq!(block[0], 7);
+        assert!(reader.is_done());
+        let mut reader = ScriptedReader::new([Step::Data(&[1]), Step::Error(ErrorKind::BrokenPipe)]);
+        let mut filled = 0;
+        assert_eq!(reader.read_fill_nonblocking(&mut block, &mut filled).unwrap_err().kind(), ErrorKind::BrokenPipe);
+        assert_eq!(filled, 1);
+    }
+
+    #[test]
+    fn read_at() {
+        use std::io::{Cursor, Read};
+
+        use crate::ReadAt;
+
+        let mut cursor = Cursor::new([0, 0, 0, 4, 1, 2, 3, 4]);
+        cursor.set_position(2);
+        assert_eq!(cursor.read_at_be::<u32>(0).unwrap(), 4);
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.read_at_le::<u16>(4).unwrap(), 0x0201);
+        let mut block = [0; 3];
+        cursor.read_bytes_at(5, &mut block).unwrap();
+        assert_eq!(block, [2, 3, 4]);
+        assert_eq!(cursor.read_at_le::<u32>(6).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(cursor.position(), 2);
+        let mut rest = Vec::new();
+        cursor.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, [0, 4, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn reserve() {
+        use std::io::Cursor;
+
+        use crate::{Endian, WriteExt, WriteReserve};
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_le(1u8).unwrap();
+        let offset = cursor.reserve_le::<u32>().unwrap();
+        let checksum = cursor.reserve_be::<u16>().unwrap();
+        assert_eq!((offset.position(), checksum.position()), (1, 5));
+        assert_eq!(checksum.endian(), Endian::Big);
+        cursor.write_le(2u8).unwrap();
+        assert_eq!(cursor.get_ref(), &[1, 0, 0, 0, 0, 0, 0, 2]);
+        offset.fill(&mut cursor, 7).unwrap();
+        checksum.fill(&mut cursor, 0x0102).unwrap();
+        assert_eq!(cursor.position(), 8);
+        cursor.write_le(3u8).unwrap();
+        assert_eq!(cursor.get_ref(), &[1, 7, 0, 0, 0, 1, 2, 2, 3]);
+    }
+
+    #[test]
+    fn read_append() {
+        let mut buf = Vec::with_capacity(16);
+        let ptr = buf.as_ptr();
+        assert_eq!((&[1u8, 2, 3][..]).read_append(&mut buf).unwrap(), 3);
+        assert_eq!((&[][..]).read_append(&mut buf).unwrap(), 0);
+        assert_eq!((&[4u8, 5][..]).read_append(&mut buf).unwrap(), 2);
+        assert_eq!(buf, [1, 2, 3, 4, 5]);
+        assert_eq!(buf.as_ptr(), ptr);
+        assert_eq!((&[6u8][..]).read_to_vec().unwrap(), [6]);
+    }
+
+    #[test]
+    fn seek_ext() {
+        let mut cursor = std::io::Cursor::new([0u8; 10]);
+        assert_eq!(cursor.stream_len().unwrap(), 10);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.remaining().unwrap(), 10);
+        assert!(!cursor.is_at_end().unwrap());
+        cursor.ensure_remaining(10).unwrap();
+        assert_eq!(cursor.position(), 0);
+        cursor.set_position(7);
+        assert_eq!(cursor.remaining().unwrap(), 3);
+        let err = cursor.ensure_remaining(5).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(err.to_string(), "stream has 3 bytes left, 2 bytes short of 5");
+        assert_eq!(cursor.position(), 7);
+        cursor.set_position(10);
+        assert!(cursor.is_at_end().unwrap());
+        assert_eq!(cursor.stream_len().unwrap(), 10);
+        assert_eq!(cursor.position(), 10);
+        cursor.set_position(12);
+        assert_eq!(cursor.remaining().unwrap(), 0);
+        assert_eq!(cursor.position(), 12);
+    }
+
+    #[test]
+    fn stream_aliases() {
+        use crate::{Combine, ReadExt, ReadSeek, ReadWrite, ReadWriteSeek, WriteExt, WriteSeek};
+        use std::io::{Seek, SeekFrom};
+        let combine = Combine::new(std::io::Cursor::new(vec![0u8; 8]), std::io::Cursor::new(vec![0u8; 8]));
+        let mut streams: Vec<Box<dyn ReadWriteSeek>> = vec![
+            std::io::Cursor::new(vec![0u8; 8]).into_read_write_seek(),
+            combine.into_read_write_seek()
+        ];
+        for stream in &mut streams {
+            stream.write_le(0x0102u16).unwrap();
+            stream.rewind().unwrap();
+            stream.write_be(0x0304u16).unwrap();
+            stream.seek(SeekFrom::Start(0)).unwrap();
+        }
+        assert_eq!(streams[0].read_be::<u16>().unwrap(), 0x0304);
+        assert_eq!(streams[1].read_be::<u16>().unwrap(), 0);
+        let mut reader: Box<dyn ReadSeek> = std::io::Cursor::new([1u8, 2]).into_read_seek();
+        assert_eq!(reader.read_le::<u16>().unwrap(), 0x0201);
+        assert_eq!(reader.stream_position().unwrap(), 2);
+        let mut writer: Box<dyn WriteSeek> = std::io::Cursor::new(Vec::new()).into_write_seek();
+        writer.write_le(1u8).unwrap();
+        assert_eq!(writer.stream_position().unwrap(), 1);
+        let mut both: Box<dyn ReadWrite> = Combine::new(&[5u8][..], Vec::new()).into_read_write();
+        both.write_le(6u8).unwrap();
+        assert_eq!(both.read_le::<u8>().unwrap(), 5);
+    }
+
+    #[test]
+    fn trailer() {
+        use std::io::SeekFrom;
+
+        use crate::{ReadExt, ReadSeek, SeekExt};
+
+        let mut cursor = std::io::Cursor::new([1u8, 2, 3, 4, 5, 6, 0xAA, 0xBB]);
+        cursor.set_position(2);
+        let mut stream: &mut dyn ReadSeek = &mut cursor;
+        let len = SeekExt::stream_len(&mut *stream).unwrap();
+        assert_eq!(stream.stream_position().unwrap(), 2);
+        stream.seek(SeekFrom::Start(len - 2)).unwrap();
+        assert_eq!(stream.read_be::<u16>().unwrap(), 0xAABB);
+        assert_eq!(stream.remaining().unwrap(), 0);
+    }
 }
@@ -0,0 +1,199 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::io::{Result, Write};
+
+/// A [Write] which buffers everything written in memory until it is committed to the wrapped
+/// [Write].
+///
+/// Dropping a [Transaction] without calling [commit](Transaction::commit) discards the buffered
+/// data, leaving the wrapped [Write] untouched. Transactions can be nested: committing an inner
+/// transaction only moves its data into the buffer of the outer one.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use bytesutil::WriteExt;
+///
+/// let mut out = Vec::new();
+/// let mut section = out.begin_transaction();
+/// section.write_all(b"partial").unwrap();
+/// section.rollback();
+/// let mut section = out.begin_transaction();
+/// section.write_all(b"complete").unwrap();
+/// section.commit().unwrap();
+/// assert_eq!(out, b"complete");
+/// ```
+pub struct Transaction<W> {
+    inner: W,
+    buffer: Vec<u8>
+}
+
+impl<W: Write> Transaction<W> {
+    /// Creates a new empty [Transaction] on top of the given [Write].
+    pub fn new(inner: W) -> Transaction<W> {
+        Self {
+            inner,
+            buffer: Vec::new()
+        }
+    }
+
+    /// Returns a reference to the wrapped [Write].
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped [Write]; bytes written through it bypass the
+    /// transaction and are kept even if it is rolled back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use bytesutil::{HexWriter, Transaction};
+    ///
+    /// let mut section = Transaction::new(HexWriter::new(Vec::new()));
+    /// section.write_all(&[0xAB]).unwrap();
+    /// section.get_mut().get_mut().extend_from_slice(b"> ");
+    /// let out = section.commit().unwrap().into_inner();
+    /// assert_eq!(out, b"> ab");
+    /// ```
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the data buffered so far.
+    pub fn pending(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Writes all buffered data to the wrapped [Write] and returns it.
+    ///
+    /// *The wrapped [Write] is not flushed.*
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) if the buffered data could not be written. In that case
+    /// the wrapped [Write] may have received part of the data.
+    pub fn commit(mut self) -> Result<W> {
+        self.inner.write_all(&self.buffer)?;
+        Ok(self.inner)
+    }
+
+    /// Discards all buffered data and returns the untouched wrapped [Write].
+    pub fn rollback(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for Transaction<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        self.buffer.extend_from_slice(buf);
+        Ok(())
+    }
+
+    /// Does nothing: buffered data only reaches the wrapped [Write] on
+    /// [commit](Transaction::commit).
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind, Write};
+
+    use crate::testutil::{ScriptedWriter, Step};
+    use crate::{ReadExt, WriteExt};
+
+    fn section<W: Write>(out: &mut W, values: &[u8]) -> std::io::Result<()> {
+        let mut src = Cursor::new(values);
+        out.write_le(src.read_le::<u16>()?)?;
+        out.write_be(src.read_be::<u32>()?)?;
+        Ok(())
+    }
+
+    #[test]
+    fn commit() {
+        let mut direct = Vec::new();
+        section(&mut direct, &[1, 2, 3, 4, 5, 6]).unwrap();
+        let mut out = ScriptedWriter::new([Step::Data(&direct)]);
+        let mut transaction = out.begin_transaction();
+        section(&mut transaction, &[1, 2, 3, 4, 5, 6]).unwrap();
+        transaction.flush().unwrap();
+        assert!(transaction.get_ref().written().is_empty());
+        assert_eq!(transaction.pending(), direct);
+        transaction.commit().unwrap();
+        assert!(out.is_done());
+        assert_eq!(out.written(), direct);
+    }
+
+    #[test]
+    fn rollback() {
+        let mut out = vec![9, 9];
+        let mut transaction = out.begin_transaction();
+        let err = section(&mut transaction, &[1, 2, 3]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(transaction.pending(), [1, 2]);
+        transaction.rollback();
+        assert_eq!(out, [9, 9]);
+        section(&mut out.begin_transaction(), &[1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(out, [9, 9]);
+    }
+
+    #[test]
+    fn nested() {
+        let mut out = Vec::new();
+        let mut outer = out.begin_transaction();
+        outer.write_le(1u8).unwrap();
+        let mut inner = outer.begin_transaction();
+        inner.write_le(2u8).unwrap();
+        inner.commit().unwrap();
+        let mut discarded = outer.begin_transaction();
+        discarded.write_le(3u8).unwrap();
+        drop(discarded);
+        assert_eq!(outer.pending(), [1, 2]);
+        outer.commit().unwrap();
+        assert_eq!(out, [1, 2]);
+    }
+
+    #[test]
+    fn commit_error() {
+        let mut out = ScriptedWriter::new([Step::Short(1), Step::Data(&[1]), Step::Error(ErrorKind::BrokenPipe)]);
+        let mut transaction = out.begin_transaction();
+        transaction.write_be(0x0102u16).unwrap();
+        assert_eq!(transaction.commit().err().map(|e| e.kind()), Some(ErrorKind::BrokenPipe));
+        assert_eq!(out.written(), [1]);
+    }
+}
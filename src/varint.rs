@@ -0,0 +1,323 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+enum DecodeError {
+    Truncated,
+    Overflow
+}
+
+impl DecodeError {
+    fn message(&self) -> &'static str {
+        match self {
+            DecodeError::Truncated => "truncated variable-length integer",
+            DecodeError::Overflow => "variable-length integer overflow"
+        }
+    }
+}
+
+macro_rules! impl_varint {
+    ($($(#[$meta: meta])* $name: ident: $t: ty)*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+            pub struct $name(pub $t);
+
+            impl $name {
+                /// The maximum number of bytes an encoded value may occupy.
+                pub const MAX_LEN: usize = (<$t>::BITS as usize).div_ceil(7);
+
+                /// Returns the number of bytes needed to encode this value.
+                pub fn encoded_len(&self) -> usize {
+                    let bits = <$t>::BITS - self.0.leading_zeros();
+                    (bits as usize).div_ceil(7).max(1)
+                }
+
+                /// Encodes this value at the start of `block` in its little endian (LEB128) form.
+                ///
+                /// returns: the number of bytes written, which is [encoded_len](Self::encoded_len).
+                ///
+                /// # Panics
+                ///
+                /// Panics if `block` is shorter than [encoded_len](Self::encoded_len).
+                pub fn encode_le(&self, block: &mut [u8]) -> usize {
+                    let len = self.encoded_len();
+                    let mut value = self.0;
+                    for byte in &mut block[..len] {
+                        *byte = (value & 0x7F) as u8 | 0x80;
+                        value >>= 7;
+                    }
+                    block[len - 1] &= 0x7F;
+                    len
+                }
+
+                /// Encodes this value at the start of `block` in its big endian (VLQ) form.
+                ///
+                /// returns: the number of bytes written, which is [encoded_len](Self::encoded_len).
+                ///
+                /// # Panics
+                ///
+                /// Panics if `block` is shorter than [encoded_len](Self::encoded_len).
+                pub fn encode_be(&self, block: &mut [u8]) -> usize {
+                    let len = self.encoded_len();
+                    for (i, byte) in block[..len].iter_mut().enumerate() {
+                        let shift = 7 * (len - 1 - i);
+                        *byte = (self.0 >> shift) as u8 & 0x7F | 0x80;
+                    }
+                    block[len - 1] &= 0x7F;
+                    len
+                }
+
+                /// Decodes a little endian (LEB128) value from the start of `bytes`.
+                ///
+                /// returns: the value and the number of bytes it occupied.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `bytes` ends before the value or if the value overflows.
+                pub fn decode_le(bytes: &[u8]) -> (Self, usize) {
+                    Self::try_decode_le(bytes).unwrap_or_else(|e| panic!("{}", e.message()))
+                }
+
+                /// Decodes a big endian (VLQ) value from the start of `bytes`.
+                ///
+                /// returns: the value and the number of bytes it occupied.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `bytes` ends before the value or if the value overflows.
+                pub fn decode_be(bytes: &[u8]) -> (Self, usize) {
+                    Self::try_decode_be(bytes).unwrap_or_else(|e| panic!("{}", e.message()))
+                }
+
+                fn try_decode_le(bytes: &[u8]) -> Result<(Self, usize), DecodeError> {
+                    let mut value: $t = 0;
+                    for (i, &byte) in bytes.iter().take(Self::MAX_LEN).enumerate() {
+                        let part = (byte & 0x7F) as $t;
+                        let shift = 7 * i as u32;
+                        if part != 0 && (shift >= <$t>::BITS || (part << shift) >> shift != part) {
+                            return Err(DecodeError::Overflow);
+                        }
+                        if part != 0 {
+                            value |= part << shift;
+                        }
+                        if byte & 0x80 == 0 {
+                            return Ok(($name(value), i + 1));
+                        }
+                    }
+                    match bytes.len() >= Self::MAX_LEN {
+                        true => Err(DecodeError::Overflow),
+                        false => Err(DecodeError::Truncated)
+                    }
+                }
+
+                fn try_decode_be(bytes: &[u8]) -> Result<(Self, usize), DecodeError> {
+                    let mut value: $t = 0;
+                    for (i, &byte) in bytes.iter().take(Self::MAX_LEN).enumerate() {
+                        if value >> (<$t>::BITS - 7) != 0 {
+                            return Err(DecodeError::Overflow);
+                        }
+                        value = value << 7 | (byte & 0x7F) as $t;
+                        if byte & 0x80 == 0 {
+                            return Ok(($name(value), i + 1));
+                        }
+                    }
+                    match bytes.len() >= Self::MAX_LEN {
+                        true => Err(DecodeError::Overflow),
+                        false => Err(DecodeError::Truncated)
+                    }
+                }
+            }
+
+            #[cfg(feature = "arbitrary")]
+            impl<'a> arbitrary::Arbitrary<'a> for $name {
+                fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                    <$t>::arbitrary(u).map($name)
+                }
+
+                fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                    <$t>::size_hint(depth)
+                }
+            }
+
+            impl crate::ByteSize for $name {
+                fn byte_size(&self) -> usize {
+                    self.encoded_len()
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl crate::WriteTo for $name {
+                fn write_to_le<T: std::io::Write>(&self, mut dst: T) -> std::io::Result<()> {
+                    let mut block = [0; Self::MAX_LEN];
+                    let len = self.encode_le(&mut block);
+                    dst.write_all(&block[..len])
+                }
+
+                fn write_to_be<T: std::io::Write>(&self, mut dst: T) -> std::io::Result<()> {
+                    let mut block = [0; Self::MAX_LEN];
+                    let len = self.encode_be(&mut block);
+                    dst.write_all(&block[..len])
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl crate::ReadFrom for $name {
+                fn read_from_le<T: std::io::Read>(src: T) -> std::io::Result<Self> {
+                    let mut block = [0; Self::MAX_LEN];
+                    let len = read_groups::<Self, _>(src, &mut block)?;
+                    Self::try_decode_le(&block[..len]).map(|(v, _)| v).map_err(invalid_data)
+                }
+
+                fn read_from_be<T: std::io::Read>(src: T) -> std::io::Result<Self> {
+                    let mut block = [0; Self::MAX_LEN];
+                    let len = read_groups::<Self, _>(src, &mut block)?;
+                    Self::try_decode_be(&block[..len]).map(|(v, _)| v).map_err(invalid_data)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "std")]
+fn invalid_data(e: DecodeError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e.message())
+}
+
+/// Reads bytes until one without the continuation bit is found or the block is full.
+#[cfg(feature = "std")]
+fn read_groups<V, T: std::io::Read>(mut src: T, block: &mut [u8]) -> std::io::Result<usize> {
+    for i in 0..block.len() {
+        src.read_exact(&mut block[i..i + 1]).map_err(crate::error::attach::<V>)?;
+        if block[i] & 0x80 == 0 {
+            return Ok(i + 1);
+        }
+    }
+    Err(invalid_data(DecodeError::Overflow))
+}
+
+impl_varint!(
+    /// A variable-length encoded [u32], occupying between 1 and 5 bytes.
+    ///
+    /// The little endian encoding is LEB128 (least significant group of 7 bits first) and the big
+    /// endian encoding is VLQ (most significant group of 7 bits first). In both encodings, the
+    /// high bit of each byte is set when more bytes follow.
+    ///
+    /// *As its size depends on its value, it only implements the stream traits; use
+    /// [encode_le](VarU32::encode_le) and [decode_le](VarU32::decode_le) with buffers.*
+    VarU32: u32
+    /// A variable-length encoded [u64], occupying between 1 and 10 bytes.
+    ///
+    /// The little endian encoding is LEB128 (least significant group of 7 bits first) and the big
+    /// endian encoding is VLQ (most significant group of 7 bits first). In both encodings, the
+    /// high bit of each byte is set when more bytes follow.
+    ///
+    /// *As its size depends on its value, it only implements the stream traits; use
+    /// [encode_le](VarU64::encode_le) and [decode_le](VarU64::decode_le) with buffers.*
+    VarU64: u64
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{ReadBytes, VarU32, VarU64};
+
+    #[test]
+    fn encoding() {
+        let mut block = [0; 10];
+        assert_eq!(VarU64(300).encode_le(&mut block), 2);
+        assert_eq!(&block[..2], &[0xAC, 0x02]);
+        assert_eq!(VarU64::decode_le(&block), (VarU64(300), 2));
+        assert_eq!(VarU64(300).encode_be(&mut block), 2);
+        assert_eq!(&block[..2], &[0x82, 0x2C]);
+        assert_eq!(VarU64::decode_be(&block), (VarU64(300), 2));
+        assert_eq!(VarU32::decode_le(&[0]), (VarU32(0), 1));
+        assert_eq!(VarU32(u32::MAX).encoded_len(), VarU32::MAX_LEN);
+        VarU64(u64::MAX).encode_le(&mut block);
+        assert_eq!(VarU64::decode_le(&block), (VarU64(u64::MAX), 10));
+        VarU64(u64::MAX).encode_be(&mut block);
+        assert_eq!(VarU64::decode_be(&block), (VarU64(u64::MAX), 10));
+        assert_eq!(u32::read_bytes_le_counted(&[1, 0, 0, 0, 5]), (1, 4));
+    }
+
+    #[test]
+    #[should_panic(expected = "truncated")]
+    fn truncated() {
+        VarU32::decode_le(&[0x80, 0x80]);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn overflow() {
+        VarU32::decode_le(&[0xFF, 0xFF, 0xFF, 0xFF, 0x7F]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn slice_matches_stream() {
+        use crate::{ReadFrom, WriteTo};
+
+        for value in [0, 1, 127, 128, 300, 1 << 35, u64::MAX] {
+            let value = VarU64(value);
+            let mut block = [0xEE; 12];
+            let mut stream = Vec::new();
+            let len = value.encode_le(&mut block);
+            value.write_to_le(&mut stream).unwrap();
+            assert_eq!(stream, block[..len]);
+            assert_eq!(VarU64::read_from_le(&block[..len]).unwrap(), value);
+            let len = value.encode_be(&mut block);
+            stream.clear();
+            value.write_to_be(&mut stream).unwrap();
+            assert_eq!(stream, block[..len]);
+            assert_eq!(VarU64::decode_be(&stream), (value, len));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io() {
+        use std::io::{Cursor, ErrorKind};
+
+        use crate::{ReadExt, WriteExt};
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_le(VarU32(127)).unwrap();
+        cursor.write_be(VarU64(128)).unwrap();
+        cursor.write_le(VarU32(u32::MAX)).unwrap();
+        assert_eq!(cursor.get_ref().len(), 8);
+        cursor.set_position(0);
+        assert_eq!(cursor.read_le::<VarU32>().unwrap(), VarU32(127));
+        assert_eq!(cursor.read_be::<VarU64>().unwrap(), VarU64(128));
+        assert_eq!(cursor.read_le::<VarU32>().unwrap(), VarU32(u32::MAX));
+        crate::testutil::assert_roundtrip_io(VarU64(u64::MAX));
+        crate::testutil::assert_roundtrip_io(VarU32(300));
+        let mut cursor = Cursor::new([0xAC, 0x02]);
+        assert_eq!(cursor.read_le::<VarU32>().unwrap(), VarU32(300));
+        assert_eq!(cursor.position(), 2);
+        let mut bad = Cursor::new([0xFF; 6]);
+        assert_eq!(bad.read_le::<VarU32>().unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Formatter};
+use std::io::{ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+/// Details about data which did not read back identically after being written.
+///
+/// A [VerifyError] is carried as the payload of an [Error](std::io::Error) of kind
+/// [InvalidData](ErrorKind::InvalidData) returned by [VerifyingWriter]. Use
+/// [downcast](VerifyError::downcast) to retrieve it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyError {
+    offset: u64
+}
+
+impl VerifyError {
+    /// Returns the stream offset of the first byte which did not match.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Extracts the [VerifyError] carried by the given [Error](std::io::Error), if any.
+    pub fn downcast(error: &std::io::Error) -> Option<&VerifyError> {
+        error.get_ref()?.downcast_ref()
+    }
+}
+
+impl Display for VerifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "verification failed at offset {}", self.offset)
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl From<VerifyError> for std::io::Error {
+    fn from(value: VerifyError) -> Self {
+        std::io::Error::new(ErrorKind::InvalidData, value)
+    }
+}
+
+/// A [Write] wrapper which reads back everything written and compares it with the original
+/// data.
+///
+/// By default, every call to [write](Write::write) is verified immediately. In batch mode
+/// (see [verify_batch](VerifyingWriter::verify_batch)), verification is deferred until
+/// [flush](Write::flush) in order to reduce the number of seeks.
+pub struct VerifyingWriter<W> {
+    inner: W,
+    batch: bool,
+    pending: Vec<(u64, Vec<u8>)>
+}
+
+impl<W> VerifyingWriter<W> {
+    /// Creates a new [VerifyingWriter] wrapping the given stream.
+    pub fn new(inner: W) -> VerifyingWriter<W> {
+        Self {
+            inner,
+            batch: false,
+            pending: Vec::new()
+        }
+    }
+
+    /// Switches this [VerifyingWriter] to batch mode, where written data is only verified on
+    /// [flush](Write::flush).
+    pub fn verify_batch(mut self) -> Self {
+        self.batch = true;
+        self
+    }
+
+    /// Returns true if some written data has not been verified yet.
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Returns a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped stream; bytes written through it are not
+    /// verified.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Extracts the wrapped stream.
+    ///
+    /// returns: the wrapped stream and true if some written data was never verified.
+    pub fn into_inner(self) -> (W, bool) {
+        let pending = self.is_pending();
+        (self.inner, pending)
+    }
+}
+
+impl<W: Read + Seek> VerifyingWriter<W> {
+    fn verify(&mut self, pos: u64, expected: &[u8]) -> Result<()> {
+        let mut block = vec![0; expected.len()];
+        self.inner.seek(SeekFrom::Start(pos))?;
+        self.inner.read_exact(&mut block)?;
+        match block.iter().zip(expected).position(|(a, b)| a != b) {
+            Some(index) => Err(VerifyError { offset: pos + index as u64 }.into()),
+            None => Ok(())
+        }
+    }
+}
+
+impl<W: Write + Read + Seek> Write for VerifyingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let pos = self.inner.stream_position()?;
+        let len = self.inner.write(buf)?;
+        if self.batch {
+            match self.pending.last_mut() {
+                Some((start, data)) if *start + data.len() as u64 == pos => data.extend_from_slice(&buf[..len]),
+                _ => self.pending.push((pos, buf[..len].to_vec()))
+            }
+        } else {
+            self.verify(pos, &buf[..len])?;
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()?;
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let end = self.inner.stream_position()?;
+        let res = std::mem::take(&mut self.pending)
+            .into_iter()
+            .try_for_each(|(pos, data)| self.verify(pos, &data));
+        self.inner.seek(SeekFrom::Start(end))?;
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+    use crate::{VerifyError, VerifyingWriter};
+
+    /// A stream which silently flips the first byte of its `corrupt`-th write.
+    struct Flaky {
+        inner: Cursor<Vec<u8>>,
+        writes: usize,
+        corrupt: usize
+    }
+
+    impl Flaky {
+        fn new(corrupt: usize) -> Flaky {
+            Self {
+                inner: Cursor::new(Vec::new()),
+                writes: 0,
+                corrupt
+            }
+        }
+    }
+
+    impl Write for Flaky {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.writes += 1;
+            if self.writes == self.corrupt && !buf.is_empty() {
+                let mut block = buf.to_vec();
+                block[0] ^= 0xFF;
+                return self.inner.write(&block);
+            }
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Read for Flaky {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for Flaky {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn immediate() {
+        let mut writer = VerifyingWriter::new(Flaky::new(3));
+        writer.write_all(&[1, 2, 3, 4]).unwrap();
+        writer.write_all(&[5, 6]).unwrap();
+        let err = writer.write_all(&[7, 8]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(VerifyError::downcast(&err).unwrap().offset(), 6);
+        let (_, pending) = writer.into_inner();
+        assert!(!pending);
+    }
+
+    #[test]
+    fn batch() {
+        let mut writer = VerifyingWriter::new(Flaky::new(2)).verify_batch();
+        writer.write_all(&[1, 2, 3]).unwrap();
+        writer.write_all(&[4, 5, 6]).unwrap();
+        writer.write_all(&[7]).unwrap();
+        assert!(writer.is_pending());
+        let err = writer.flush().unwrap_err();
+        assert_eq!(VerifyError::downcast(&err).unwrap().offset(), 3);
+        assert!(!writer.is_pending());
+        writer.write_all(&[8, 9]).unwrap();
+        writer.flush().unwrap();
+        let (stream, pending) = writer.into_inner();
+        assert!(!pending);
+        assert_eq!(stream.inner.into_inner(), [1, 2, 3, !4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn pending() {
+        let mut writer = VerifyingWriter::new(Cursor::new(Vec::new())).verify_batch();
+        writer.write_all(&[1, 2]).unwrap();
+        let (stream, pending) = writer.into_inner();
+        assert!(pending);
+        assert_eq!(stream.into_inner(), [1, 2]);
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use core::ops::Range;
+
+use crate::cursor::check_bounds;
+use crate::{BoundsError, ByteBuf, FixedSize, ReadBytes, WriteBytes};
+
+/// A 2D strided view over the bytes of a [ByteBuf], such as a texture or a heightmap whose rows
+/// are `pitch` bytes apart but only `width` bytes wide.
+///
+/// This is created by [view_2d](ByteBuf::view_2d) and [view_2d_mut](ByteBuf::view_2d_mut).
+/// Fields are addressed by their index `x` within a row and by the index `y` of the row, and
+/// must fit in the `width` bytes of the row: the padding between rows is never accessible.
+///
+/// *The offsets reported by a [BoundsError] are relative to the start of the view for an
+/// invalid row and relative to the start of the row for an invalid field.*
+#[derive(Debug)]
+pub struct View2D<B> {
+    bytes: B,
+    origin: usize,
+    width: usize,
+    height: usize,
+    pitch: usize
+}
+
+/// Checks that the last row of a view fits in a buffer of length `len`.
+fn check_layout(len: usize, origin: usize, width: usize, height: usize, pitch: usize) -> Result<(), BoundsError> {
+    if height == 0 {
+        return check_bounds(origin, 0, len);
+    }
+    let last = (height - 1).checked_mul(pitch).and_then(|offset| offset.checked_add(origin));
+    check_bounds(last.unwrap_or(usize::MAX), width, len)
+}
+
+impl<T: AsRef<[u8]>> ByteBuf<T> {
+    /// Returns a 2D strided view over this buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin`: the offset in bytes of the first row.
+    /// * `width`: the number of accessible bytes in each row.
+    /// * `height`: the number of rows.
+    /// * `pitch`: the distance in bytes between the starts of two consecutive rows.
+    ///
+    /// returns: Result<View2D<&[u8]>, BoundsError>
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the last row does not fit in the buffer.
+    pub fn view_2d(&self, origin: usize, width: usize, height: usize, pitch: usize) -> Result<View2D<&[u8]>, BoundsError> {
+        View2D::new(self.as_ref(), origin, width, height, pitch)
+    }
+}
+
+impl<T: AsMut<[u8]>> ByteBuf<T> {
+    /// Returns a mutable 2D strided view over this buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin`: the offset in bytes of the first row.
+    /// * `width`: the number of accessible bytes in each row.
+    /// * `height`: the number of rows.
+    /// * `pitch`: the distance in bytes between the starts of two consecutive rows.
+    ///
+    /// returns: Result<View2D<&mut [u8]>, BoundsError>
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if the last row does not fit in the buffer.
+    pub fn view_2d_mut(&mut self, origin: usize, width: usize, height: usize, pitch: usize) -> Result<View2D<&mut [u8]>, BoundsError> {
+        View2D::new(self.as_mut(), origin, width, height, pitch)
+    }
+}
+
+impl<B> View2D<B> {
+    /// Returns the number of accessible bytes in each row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the distance in bytes between the starts of two consecutive rows.
+    pub fn pitch(&self) -> usize {
+        self.pitch
+    }
+
+    fn row_range(&self, y: usize) -> Result<Range<usize>, BoundsError> {
+        if y >= self.height {
+            let span = match self.height {
+                0 => 0,
+                _ => (self.height - 1) * self.pitch + self.width
+            };
+            return Err(BoundsError::new(y.saturating_mul(self.pitch), self.width, span));
+        }
+        let start = self.origin + y * self.pitch;
+        Ok(start..start + self.width)
+    }
+
+    fn field_range<V: FixedSize>(&self, x: usize, y: usize) -> Result<Range<usize>, BoundsError> {
+        let row = self.row_range(y)?;
+        let pos = x.saturating_mul(V::SIZE);
+        check_bounds(pos, V::SIZE, self.width)?;
+        Ok(row.start + pos..row.start + pos + V::SIZE)
+    }
+}
+
+impl<B: AsRef<[u8]>> View2D<B> {
+    fn new(bytes: B, origin: usize, width: usize, height: usize, pitch: usize) -> Result<Self, BoundsError> {
+        check_layout(bytes.as_ref().len(), origin, width, height, pitch)?;
+        Ok(Self {
+            bytes,
+            origin,
+            width,
+            height,
+            pitch
+        })
+    }
+
+    /// Returns the accessible bytes of the row `y`, without its padding.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if `y` is not less than the height of the view.
+    pub fn row(&self, y: usize) -> Result<ByteBuf<&[u8]>, BoundsError> {
+        let range = self.row_range(y)?;
+        Ok(ByteBuf::new(&self.bytes.as_ref()[range]))
+    }
+
+    /// Reads the little-endian field at index `x` of the row `y`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if `y` is out of the view or if the field does not fit in the
+    /// width of the row.
+    pub fn get_le<V: ReadBytes>(&self, x: usize, y: usize) -> Result<V, BoundsError> {
+        let range = self.field_range::<V>(x, y)?;
+        Ok(V::read_bytes_le(&self.bytes.as_ref()[range]))
+    }
+
+    /// Reads the big-endian field at index `x` of the row `y`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if `y` is out of the view or if the field does not fit in the
+    /// width of the row.
+    pub fn get_be<V: ReadBytes>(&self, x: usize, y: usize) -> Result<V, BoundsError> {
+        let range = self.field_range::<V>(x, y)?;
+        Ok(V::read_bytes_be(&self.bytes.as_ref()[range]))
+    }
+}
+
+impl<B: AsMut<[u8]>> View2D<B> {
+    /// Returns the accessible bytes of the row `y` mutably, without its padding.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if `y` is not less than the height of the view.
+    pub fn row_mut(&mut self, y: usize) -> Result<ByteBuf<&mut [u8]>, BoundsError> {
+        let range = self.row_range(y)?;
+        Ok(ByteBuf::new(&mut self.bytes.as_mut()[range]))
+    }
+
+    /// Writes a little-endian field at index `x` of the row `y`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if `y` is out of the view or if the field does not fit in the
+    /// width of the row. Nothing is written in that case.
+    pub fn set_le<V: WriteBytes>(&mut self, x: usize, y: usize, value: V) -> Result<(), BoundsError> {
+        let range = self.field_range::<V>(x, y)?;
+        value.write_bytes_le(&mut self.bytes.as_mut()[range]);
+        Ok(())
+    }
+
+    /// Writes a big-endian field at index `x` of the row `y`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoundsError] if `y` is out of the view or if the field does not fit in the
+    /// width of the row. Nothing is written in that case.
+    pub fn set_be<V: WriteBytes>(&mut self, x: usize, y: usize, value: V) -> Result<(), BoundsError> {
+        let range = self.field_range::<V>(x, y)?;
+        value.write_bytes_be(&mut self.bytes.as_mut()[range]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundsError, ByteBuf};
+
+    #[test]
+    fn strided() {
+        // 3 rows of 3 u16 pixels, padded to 8 bytes, after a 4 bytes header.
+        let mut buffer = ByteBuf::new([0u8; 4 + 2 * 8 + 6]);
+        let mut view = buffer.view_2d_mut(4, 6, 3, 8).unwrap();
+        for y in 0..3 {
+            for x in 0..3 {
+                view.set_le(x, y, (y * 10 + x) as u16).unwrap();
+            }
+        }
+        view.set_be(2, 2, 0x0102u16).unwrap();
+        assert_eq!(view.row_mut(1).unwrap().get_le::<u16>(4), 12);
+        assert_eq!(buffer.as_ref()[10..12], [0, 0]);
+        assert_eq!(buffer.as_ref()[24..], [1, 2]);
+        let view = buffer.view_2d(4, 6, 3, 8).unwrap();
+        assert_eq!(view.get_le::<u16>(1, 2).unwrap(), 21);
+        assert_eq!(view.get_be::<u16>(2, 2).unwrap(), 0x0102);
+        assert_eq!(view.row(0).unwrap().as_ref(), [0, 0, 1, 0, 2, 0]);
+        assert_eq!((view.width(), view.height(), view.pitch()), (6, 3, 8));
+    }
+
+    #[test]
+    fn bounds() {
+        let buffer = ByteBuf::new([0u8; 21]);
+        assert_eq!(buffer.view_2d(0, 6, 3, 8).unwrap_err(), BoundsError::new(16, 6, 21));
+        assert_eq!(buffer.view_2d(1, 6, 3, usize::MAX).unwrap_err().pos(), usize::MAX);
+        assert!(buffer.view_2d(21, 6, 0, 8).is_ok());
+        let view = buffer.view_2d(0, 6, 2, 8).unwrap();
+        // The 4th pixel would still land in the padding of the row.
+        assert_eq!(view.get_le::<u16>(3, 0).unwrap_err(), BoundsError::new(6, 2, 6));
+        assert_eq!(view.get_le::<u32>(1, 1).unwrap_err(), BoundsError::new(4, 4, 6));
+        assert_eq!(view.get_le::<u16>(usize::MAX, 0).unwrap_err().pos(), usize::MAX);
+        assert_eq!(view.get_le::<u16>(0, 2).unwrap_err(), BoundsError::new(16, 6, 14));
+        assert!(view.row(2).is_err());
+    }
+}
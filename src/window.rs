@@ -0,0 +1,169 @@
+// Copyright (c) 2023, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+
+/// A [Read]+[Seek] wrapper which restricts the wrapped stream to the byte range
+/// `start..start + len`.
+///
+/// Position zero of the window maps to `start` in the wrapped stream and reads stop at `len`, which
+/// makes it suitable to expose a nested stream embedded in a container. Seeking outside the window
+/// fails without moving the wrapped stream.
+pub struct WindowReader<R> {
+    inner: R,
+    start: u64,
+    len: u64,
+    pos: u64
+}
+
+impl<R: Seek> WindowReader<R> {
+    /// Creates a new [WindowReader] and moves the wrapped stream to the start of the window.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: the stream to wrap.
+    /// * `start`: the offset in the wrapped stream at which the window starts.
+    /// * `len`: the length of the window in bytes.
+    ///
+    /// returns: Result<WindowReader<R>, Error>
+    ///
+    /// # Errors
+    ///
+    /// Returns an [Error](std::io::Error) of kind InvalidInput if the end of the window overflows a
+    /// u64, or any error returned by the wrapped stream while seeking.
+    pub fn new(mut inner: R, start: u64, len: u64) -> Result<WindowReader<R>> {
+        if start.checked_add(len).is_none() {
+            return Err(Error::new(ErrorKind::InvalidInput, "window end overflows a u64"));
+        }
+        inner.seek(SeekFrom::Start(start))?;
+        Ok(Self {
+            inner,
+            start,
+            len,
+            pos: 0
+        })
+    }
+}
+
+impl<R> WindowReader<R> {
+    /// Returns the length of the window in bytes.
+    pub fn window_len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped stream.
+    ///
+    /// *Reading or seeking through it moves the window out of sync with the wrapped stream until
+    /// the next [seek](Seek::seek) on the window.*
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Extracts the wrapped stream.
+    ///
+    /// *The wrapped stream is left wherever the last read or seek moved it.*
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for WindowReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let remaining = self.len - self.pos;
+        let max = buf.len().min(usize::try_from(remaining).unwrap_or(usize::MAX));
+        let len = self.inner.read(&mut buf[..max])?;
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+impl<R: Seek> Seek for WindowReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset)
+        };
+        let target = match target {
+            Some(target) if target <= self.len => target,
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "seek outside of window"))
+        };
+        self.inner.seek(SeekFrom::Start(self.start + target))?;
+        self.pos = target;
+        Ok(target)
+    }
+
+    fn stream_position(&mut self) -> Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind, Read, Seek, SeekFrom};
+
+    use crate::{ReadExt, WindowReader};
+
+    #[test]
+    fn window() {
+        let data: Vec<u8> = (0..32).collect();
+        let mut reader = WindowReader::new(Cursor::new(data), 8, 8).unwrap();
+        assert_eq!(reader.window_len(), 8);
+        assert_eq!(reader.read_le::<u32>().unwrap(), 0x0B0A0908);
+        assert_eq!(reader.stream_position().unwrap(), 4);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, [12, 13, 14, 15]);
+        assert_eq!(reader.read_le::<u8>().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(reader.seek(SeekFrom::End(-2)).unwrap(), 6);
+        assert_eq!(reader.read_be::<u16>().unwrap(), 0x0E0F);
+        assert_eq!(reader.seek(SeekFrom::Current(-8)).unwrap(), 0);
+        assert_eq!(reader.read_le::<u8>().unwrap(), 8);
+        assert_eq!(reader.get_ref().position(), 9);
+        assert_eq!(reader.into_inner().position(), 9);
+    }
+
+    #[test]
+    fn outside() {
+        let mut reader = WindowReader::new(Cursor::new([0u8; 16]), 4, 4).unwrap();
+        reader.seek(SeekFrom::Start(2)).unwrap();
+        for pos in [SeekFrom::Start(5), SeekFrom::Current(-3), SeekFrom::End(1)] {
+            assert_eq!(reader.seek(pos).unwrap_err().kind(), ErrorKind::InvalidInput);
+        }
+        assert_eq!(reader.stream_position().unwrap(), 2);
+        assert_eq!(reader.get_ref().position(), 6);
+        assert_eq!(reader.seek(SeekFrom::End(0)).unwrap(), 4);
+        assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+        let err = WindowReader::new(Cursor::new([0u8; 0]), u64::MAX, 1).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+}